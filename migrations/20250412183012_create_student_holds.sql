@@ -0,0 +1,15 @@
+-- Add migration script here
+-- STUDENT HOLDS
+CREATE TABLE student_holds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    hold_type TEXT NOT NULL CHECK (hold_type IN ('financial', 'advising', 'disciplinary', 'admin')),
+    reason TEXT NOT NULL,
+    placed_by UUID NOT NULL REFERENCES users(id),
+    placed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ, -- NULL means the hold stays until it is released
+    released_by UUID REFERENCES users(id),
+    released_at TIMESTAMPTZ
+);
+
+CREATE INDEX student_holds_student_id_idx ON student_holds (student_id);
@@ -0,0 +1,34 @@
+-- Add migration script here
+-- SECTIONS: an offering is one section (lecture, lab, tutorial, seminar) of a course in a term
+ALTER TABLE course_offerings
+    ADD COLUMN section_type TEXT NOT NULL DEFAULT 'lecture' CHECK (section_type IN ('lecture', 'lab', 'tutorial', 'seminar')),
+    ADD COLUMN section_number TEXT NOT NULL DEFAULT '001'; -- e.g. 001, L01, T02
+
+-- Number any existing duplicate sections of the same course and term
+UPDATE course_offerings co
+SET section_number = lpad(numbered.n::TEXT, 3, '0')
+FROM (
+    SELECT id, row_number() OVER (PARTITION BY course_id, term_id ORDER BY id) AS n
+    FROM course_offerings
+) numbered
+WHERE numbered.id = co.id;
+
+-- An instructor may now teach several sections of the same course, so sections are unique by number instead
+ALTER TABLE course_offerings
+    DROP CONSTRAINT course_offerings_course_id_term_id_instructor_id_key,
+    ADD CONSTRAINT course_offerings_course_id_term_id_section_number_key UNIQUE (course_id, term_id, section_number);
+
+-- SECTION LINKS: registering in `offering_id` requires exactly one of the group's linked offerings,
+-- e.g. lecture 001 requires one of labs L01-L04
+CREATE TABLE section_link_groups (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    section_type TEXT NOT NULL CHECK (section_type IN ('lecture', 'lab', 'tutorial', 'seminar')),
+    UNIQUE (offering_id, section_type)
+);
+
+CREATE TABLE section_link_options (
+    group_id INT NOT NULL REFERENCES section_link_groups(id) ON DELETE CASCADE,
+    linked_offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    PRIMARY KEY (group_id, linked_offering_id)
+);
@@ -0,0 +1,15 @@
+-- Add migration script here
+-- SEAT RESERVATIONS: `seats` of an offering are held for matching students until `release_at`,
+-- after which any unfilled reserved seats are open to everyone.
+-- When both major and enrollment_year are set, a student must match both.
+CREATE TABLE seat_reservations (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    seats INT NOT NULL CHECK (seats > 0),
+    major TEXT,
+    enrollment_year INT,
+    release_at TIMESTAMPTZ NOT NULL,
+    CHECK (major IS NOT NULL OR enrollment_year IS NOT NULL)
+);
+
+CREATE INDEX seat_reservations_offering_id_idx ON seat_reservations (offering_id);
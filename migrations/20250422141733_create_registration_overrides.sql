@@ -0,0 +1,32 @@
+-- Add migration script here
+ALTER TABLE courses
+    ADD COLUMN requires_consent BOOLEAN NOT NULL DEFAULT false;
+
+-- REGISTRATION OVERRIDES (instructor exceptions to a registration rule for one offering)
+-- A direct override names the student; a permission number may be redeemed once by whoever holds it.
+-- Permission numbers are stored hashed, like session tokens, and are only shown when they are issued.
+CREATE TABLE registration_overrides (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    student_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    rule TEXT NOT NULL CHECK (rule IN ('consent', 'prerequisite', 'capacity', 'time_conflict')),
+    permission_number_hash TEXT UNIQUE,
+    issued_by UUID NOT NULL REFERENCES users(id),
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ,
+    used_by UUID REFERENCES users(id),
+    used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    CHECK (student_id IS NOT NULL OR permission_number_hash IS NOT NULL)
+);
+
+CREATE INDEX registration_overrides_offering_id_idx ON registration_overrides (offering_id);
+
+-- OVERRIDE AUDIT LOG (append-only)
+CREATE TABLE override_audit_log (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    override_id UUID NOT NULL REFERENCES registration_overrides(id) ON DELETE CASCADE,
+    action TEXT NOT NULL CHECK (action IN ('issued', 'consumed', 'revoked')),
+    actor_id UUID NOT NULL REFERENCES users(id),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,13 @@
+-- Add migration script here
+ALTER TABLE users DROP CONSTRAINT users_role_check;
+ALTER TABLE users ADD CONSTRAINT users_role_check CHECK (role IN ('student', 'instructor', 'admin'));
+
+-- FACULTY PROFILES: one per instructor
+CREATE TABLE faculty_profiles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    department_id INT NOT NULL REFERENCES departments(id),
+    title TEXT NOT NULL CHECK (title IN ('lecturer', 'assistant_professor', 'associate_professor', 'professor')),
+    office TEXT,
+    office_hours TEXT,
+    max_teaching_load INT NOT NULL CHECK (max_teaching_load > 0) -- sections per term
+);
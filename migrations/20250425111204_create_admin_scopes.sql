@@ -0,0 +1,7 @@
+-- Add migration script here
+-- ADMIN SCOPES: an admin with a scope may only administer courses and offerings of that department.
+-- Admins without a scope administer everything.
+CREATE TABLE admin_scopes (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    department_id INT NOT NULL REFERENCES departments(id) ON DELETE CASCADE
+);
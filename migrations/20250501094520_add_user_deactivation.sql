@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Deactivated accounts keep their records but cannot sign in. NULL while the account is active.
+ALTER TABLE users ADD COLUMN deactivated_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN deactivated_by UUID REFERENCES users(id) ON DELETE SET NULL;
+
+-- USER PURGES: permanent deletions are recorded here, since the account itself is gone afterwards
+CREATE TABLE user_purges (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    purged_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    purged_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE login_attempts DROP CONSTRAINT login_attempts_outcome_check;
+ALTER TABLE login_attempts ADD CONSTRAINT login_attempts_outcome_check
+    CHECK (outcome IN ('success', 'invalid', 'throttled', 'locked', 'unverified', 'deactivated'));
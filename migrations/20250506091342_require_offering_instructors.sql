@@ -0,0 +1,36 @@
+-- Add migration script here
+-- Offerings must be taught by instructors. Offerings still taught by anyone else, such as the admins who taught every offering before
+-- instructors existed, fail the migration by name, since who should teach them instead is not ours to guess.
+DO $$
+DECLARE
+    misassigned TEXT;
+BEGIN
+    SELECT string_agg(format('%s %s (%s)', c.course_number, co.section_number, u.email), ', ' ORDER BY c.course_number, co.section_number)
+    INTO misassigned
+    FROM course_offerings co
+    JOIN courses c ON c.id = co.course_id
+    JOIN users u ON u.id = co.instructor_id
+    WHERE u.role <> 'instructor';
+
+    IF misassigned IS NOT NULL THEN
+        RAISE EXCEPTION 'Offerings are taught by users who are not instructors: %', misassigned
+            USING HINT = 'Assign these offerings to instructors before migrating.';
+    END IF;
+END $$;
+
+-- From now on the database refuses them too, along with deactivated instructors. Offerings keep instructors who are deactivated later,
+-- as the record of who taught them.
+CREATE FUNCTION check_offering_instructor() RETURNS trigger AS $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM users WHERE id = NEW.instructor_id AND role = 'instructor' AND deactivated_at IS NULL
+    ) THEN
+        RAISE EXCEPTION 'Offerings must be taught by an active instructor'
+            USING ERRCODE = 'check_violation';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER course_offerings_instructor BEFORE INSERT OR UPDATE OF instructor_id ON course_offerings
+    FOR EACH ROW EXECUTE FUNCTION check_offering_instructor();
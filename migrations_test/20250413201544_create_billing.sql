@@ -0,0 +1,47 @@
+-- Add migration script here
+-- Holds placed automatically (e.g. by billing) have no staff member attached
+ALTER TABLE student_holds
+    ALTER COLUMN placed_by DROP NOT NULL;
+
+-- TUITION RATES (one per term)
+CREATE TABLE term_billing_rates (
+    term_id INT PRIMARY KEY REFERENCES terms(id) ON DELETE CASCADE,
+    per_credit_cents BIGINT NOT NULL CHECK (per_credit_cents >= 0),
+    payment_due DATE NOT NULL
+);
+
+-- FLAT PER-TERM FEES (e.g. Student Union, Technology)
+CREATE TABLE term_fees (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    term_id INT NOT NULL REFERENCES terms(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    amount_cents BIGINT NOT NULL CHECK (amount_cents >= 0),
+    UNIQUE (term_id, name)
+);
+
+-- REFUND SCHEDULE: dropping on or before `deadline` refunds `refund_percent` of the charge
+CREATE TABLE refund_rules (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    term_id INT NOT NULL REFERENCES terms(id) ON DELETE CASCADE,
+    deadline DATE NOT NULL,
+    refund_percent INT NOT NULL CHECK (refund_percent BETWEEN 0 AND 100),
+    UNIQUE (term_id, deadline)
+);
+
+-- LEDGER: each entry debits one account and credits another by the same amount
+CREATE TABLE ledger_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    term_id INT NOT NULL REFERENCES terms(id),
+    registration_id UUID REFERENCES registrations(id) ON DELETE SET NULL,
+    term_fee_id INT REFERENCES term_fees(id) ON DELETE SET NULL,
+    entry_type TEXT NOT NULL CHECK (entry_type IN ('tuition', 'fee', 'refund', 'payment')),
+    debit_account TEXT NOT NULL CHECK (debit_account IN ('receivable', 'tuition_revenue', 'fee_revenue', 'cash')),
+    credit_account TEXT NOT NULL CHECK (credit_account IN ('receivable', 'tuition_revenue', 'fee_revenue', 'cash')),
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    description TEXT NOT NULL,
+    posted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (debit_account <> credit_account)
+);
+
+CREATE INDEX ledger_entries_student_term_idx ON ledger_entries (student_id, term_id);
@@ -0,0 +1,31 @@
+-- Add migration script here
+-- EXTERNAL INSTITUTIONS (where transfer credit comes from)
+CREATE TABLE external_institutions (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name TEXT UNIQUE NOT NULL,
+    location TEXT
+);
+
+-- COURSE EQUIVALENCIES (external course -> one of our courses, or generic elective credit)
+CREATE TABLE course_equivalencies (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    institution_id INT NOT NULL REFERENCES external_institutions(id) ON DELETE CASCADE,
+    external_course_code TEXT NOT NULL, -- e.g. CPSC 110
+    external_title TEXT NOT NULL,
+    course_id UUID REFERENCES courses(id) ON DELETE CASCADE, -- NULL for generic elective credit
+    elective_department_id INT REFERENCES departments(id), -- department of generic elective credit, if any
+    credits INT NOT NULL CHECK (credits > 0),
+    UNIQUE (institution_id, external_course_code),
+    CHECK (course_id IS NULL OR elective_department_id IS NULL)
+);
+
+-- TRANSFER CREDITS (on a student's record)
+CREATE TABLE transfer_credits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    equivalency_id INT NOT NULL REFERENCES course_equivalencies(id),
+    external_grade TEXT, -- as reported by the institution, never counted toward GPA
+    term_taken TEXT, -- e.g. Fall 2023
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (student_id, equivalency_id)
+);
@@ -0,0 +1,30 @@
+-- Add migration script here
+-- OFFERING LISTINGS (additional course numbers a cross-listed offering is visible under)
+-- The offering's own course_id is its primary listing and is not repeated here.
+CREATE TABLE offering_listings (
+    offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    course_id UUID NOT NULL REFERENCES courses(id) ON DELETE CASCADE,
+    seat_cap INT CHECK (seat_cap > 0), -- NULL means the listing shares the whole capacity pool
+    PRIMARY KEY (offering_id, course_id)
+);
+
+-- Which listing a student registered under
+ALTER TABLE registrations
+    ADD COLUMN listed_course_id UUID REFERENCES courses(id);
+
+UPDATE registrations r
+SET listed_course_id = co.course_id
+FROM course_offerings co
+WHERE r.offering_id = co.id;
+
+-- Every course an offering is visible under, including its primary course
+CREATE VIEW offering_courses AS
+SELECT id AS offering_id, course_id, NULL::INT AS seat_cap FROM course_offerings
+UNION ALL
+SELECT offering_id, course_id, seat_cap FROM offering_listings;
+
+-- Pairs of courses that are cross-listed with each other through a shared offering
+CREATE VIEW course_cross_listings AS
+SELECT DISTINCT a.course_id, b.course_id AS equivalent_course_id
+FROM offering_courses a
+JOIN offering_courses b ON a.offering_id = b.offering_id AND a.course_id <> b.course_id;
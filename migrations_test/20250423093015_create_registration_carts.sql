@@ -0,0 +1,19 @@
+-- Add migration script here
+-- CREDIT LIMITS: the most credits a student may be registered for in a term
+ALTER TABLE terms ADD COLUMN max_credits INT NOT NULL DEFAULT 18 CHECK (max_credits > 0);
+
+-- REGISTRATION CARTS: offerings a student intends to register in for a term
+CREATE TABLE registration_carts (
+    id UUID PRIMARY KEY,
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    term_id INT NOT NULL REFERENCES terms(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (student_id, term_id)
+);
+
+CREATE TABLE cart_items (
+    cart_id UUID NOT NULL REFERENCES registration_carts(id) ON DELETE CASCADE,
+    offering_id UUID NOT NULL REFERENCES course_offerings(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (cart_id, offering_id)
+);
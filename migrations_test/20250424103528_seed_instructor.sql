@@ -0,0 +1,17 @@
+-- Add migration script here
+-- Seed offerings are taught by an instructor rather than the admin.
+INSERT INTO users (email, hashed_password, first_name, last_name, role)
+VALUES ('instructor@example.edu', 'hashed_instructor_pw', 'Ivan', 'Instructor', 'instructor');
+
+INSERT INTO faculty_profiles (user_id, department_id, title, office, office_hours, max_teaching_load)
+VALUES (
+    (SELECT id FROM users WHERE email = 'instructor@example.edu'),
+    (SELECT id FROM departments WHERE code = 'CS'),
+    'professor',
+    'Room CS300',
+    'Tuesdays 14:00-16:00',
+    10
+);
+
+UPDATE course_offerings
+SET instructor_id = (SELECT id FROM users WHERE email = 'instructor@example.edu');
@@ -0,0 +1,21 @@
+-- Add migration script here
+-- LOGIN ATTEMPTS: history of every sign-in attempt, also used to throttle sources that keep failing
+CREATE TABLE login_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email TEXT NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE, -- NULL when the email matched no account
+    source TEXT NOT NULL, -- where the attempt came from, e.g. an IP address
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'invalid', 'throttled', 'locked')),
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX login_attempts_user_id_idx ON login_attempts (user_id, attempted_at);
+CREATE INDEX login_attempts_source_idx ON login_attempts (source, attempted_at);
+
+-- LOGIN THROTTLES: consecutive failed sign-ins per account
+CREATE TABLE login_throttles (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    failed_count INT NOT NULL DEFAULT 0 CHECK (failed_count >= 0),
+    last_failed_at TIMESTAMPTZ,
+    locked_until TIMESTAMPTZ
+);
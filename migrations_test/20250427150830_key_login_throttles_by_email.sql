@@ -0,0 +1,11 @@
+-- Add migration script here
+-- Throttle by the email being tried rather than by account, so unknown emails back off and lock exactly like real accounts
+-- and the responses do not reveal which emails exist.
+DROP TABLE login_throttles;
+
+CREATE TABLE login_throttles (
+    email TEXT PRIMARY KEY,
+    failed_count INT NOT NULL DEFAULT 0 CHECK (failed_count >= 0),
+    last_failed_at TIMESTAMPTZ,
+    locked_until TIMESTAMPTZ
+);
@@ -0,0 +1,44 @@
+-- Add migration script here
+-- Emails are stored trimmed and lowercased, so addresses differing only by case are duplicates.
+-- Accounts that would collide once normalized fail the migration by name, since which one to keep is not ours to guess.
+DO $$
+DECLARE
+    collisions TEXT;
+BEGIN
+    SELECT string_agg(format('%s (%s)', normalized, emails), '; ' ORDER BY normalized)
+    INTO collisions
+    FROM (
+        SELECT lower(btrim(email)) AS normalized, string_agg(email, ', ' ORDER BY email) AS emails
+        FROM users
+        GROUP BY lower(btrim(email))
+        HAVING COUNT(*) > 1
+    ) duplicates;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION 'Accounts share an email once it is lowercased and trimmed: %', collisions
+            USING HINT = 'Merge or rename the duplicate accounts before migrating.';
+    END IF;
+END $$;
+
+UPDATE users SET email = lower(btrim(email));
+ALTER TABLE users ADD CONSTRAINT users_email_normalized CHECK (email = lower(btrim(email)));
+
+-- NULL while a new account is pending verification. Accounts created before verification existed count as verified.
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMPTZ;
+UPDATE users SET email_verified_at = now();
+
+-- EMAIL VERIFICATION TOKENS: like sessions, only a hash of the token is stored
+CREATE TABLE email_verification_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT UNIQUE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX email_verification_tokens_user_id_idx ON email_verification_tokens (user_id);
+
+ALTER TABLE login_attempts DROP CONSTRAINT login_attempts_outcome_check;
+ALTER TABLE login_attempts ADD CONSTRAINT login_attempts_outcome_check
+    CHECK (outcome IN ('success', 'invalid', 'throttled', 'locked', 'unverified'));
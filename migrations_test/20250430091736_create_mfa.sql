@@ -0,0 +1,23 @@
+-- Add migration script here
+-- MFA ENROLLMENTS: TOTP shared secrets. Unlike passwords the secret must be kept, since codes are derived from it.
+CREATE TABLE mfa_enrollments (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret TEXT NOT NULL, -- base32
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    confirmed_at TIMESTAMPTZ, -- NULL until a code from the authenticator app has been entered
+    last_used_step BIGINT, -- the time step of the last accepted code, so codes cannot be replayed
+    failed_attempts INT NOT NULL DEFAULT 0 CHECK (failed_attempts >= 0), -- wrong codes in a row, across all of the user's logins
+    locked_until TIMESTAMPTZ -- second login steps are refused until then after too many wrong codes
+);
+
+-- MFA RECOVERY CODES: single-use codes for when the authenticator is lost, stored hashed
+CREATE TABLE mfa_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    UNIQUE (user_id, code_hash)
+);
+
+-- Sessions of users with MFA start pending and cannot be used until the second step is completed.
+ALTER TABLE sessions ADD COLUMN mfa_pending BOOLEAN NOT NULL DEFAULT false;
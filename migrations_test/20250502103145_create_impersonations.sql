@@ -0,0 +1,30 @@
+-- Add migration script here
+-- IMPERSONATIONS: an admin viewing the system as a student, through a session of the student's
+CREATE TABLE impersonations (
+    id UUID PRIMARY KEY,
+    admin_id UUID NOT NULL REFERENCES users(id),
+    target_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    session_id UUID UNIQUE NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    -- Read-only impersonations cannot change anything on the student's behalf.
+    read_only BOOLEAN NOT NULL DEFAULT TRUE,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ended_at TIMESTAMPTZ
+);
+
+CREATE INDEX impersonations_target_id_idx ON impersonations (target_id);
+
+-- Every start, stop and action of an impersonation, with the admin behind it
+CREATE TABLE impersonation_events (
+    id UUID PRIMARY KEY,
+    impersonation_id UUID NOT NULL REFERENCES impersonations(id) ON DELETE CASCADE,
+    admin_id UUID NOT NULL REFERENCES users(id),
+    kind TEXT NOT NULL CHECK (kind IN ('start', 'stop', 'action')),
+    detail TEXT NOT NULL,
+    -- Whether an action went through, was refused permission, or failed; only actions have one.
+    outcome TEXT CHECK (outcome IN ('succeeded', 'refused', 'failed')),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK ((kind = 'action') = (outcome IS NOT NULL))
+);
+
+CREATE INDEX impersonation_events_impersonation_id_idx ON impersonation_events (impersonation_id);
@@ -0,0 +1,118 @@
+-- Add migration script here
+-- AUDIT LOG: every create, update and delete of the tables below, written by triggers so no change can skip it.
+-- The actor is whoever the change's transaction was attributed to with `audit.actor_id`, or NULL for changes made by the system.
+-- Entries outlive the rows and users they refer to, so nothing here references other tables.
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    entity TEXT NOT NULL,
+    -- The row's primary key; composite keys are joined with '/'.
+    entity_id TEXT NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('create', 'update', 'delete')),
+    actor_id UUID,
+    -- The admin behind the actor when the change was made through an impersonation.
+    impersonator_id UUID,
+    before JSONB,
+    after JSONB,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_log_entity_idx ON audit_log (entity, entity_id);
+CREATE INDEX audit_log_actor_id_idx ON audit_log (actor_id);
+
+CREATE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'The audit log is append-only.';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
+
+-- Records a change to the row. The trigger's arguments name the primary key columns, `id` if there are none.
+-- Password hashes and override permission numbers are secrets, and are never copied into the log.
+CREATE FUNCTION record_audit() RETURNS trigger AS $$
+DECLARE
+    before_row JSONB;
+    after_row JSONB;
+    key_row JSONB;
+    key_columns TEXT[] := COALESCE(TG_ARGV::TEXT[], ARRAY['id']);
+BEGIN
+    IF TG_OP <> 'INSERT' THEN
+        before_row := to_jsonb(OLD) - 'hashed_password' - 'permission_number_hash';
+    END IF;
+    IF TG_OP <> 'DELETE' THEN
+        after_row := to_jsonb(NEW) - 'hashed_password' - 'permission_number_hash';
+    END IF;
+    IF TG_OP = 'UPDATE' AND before_row = after_row THEN
+        RETURN NULL;
+    END IF;
+    key_row := COALESCE(after_row, before_row);
+
+    INSERT INTO audit_log (entity, entity_id, action, actor_id, impersonator_id, before, after)
+    VALUES (
+        TG_TABLE_NAME,
+        (SELECT string_agg(key_row ->> key, '/' ORDER BY ordinality) FROM unnest(key_columns) WITH ORDINALITY AS key),
+        CASE TG_OP WHEN 'INSERT' THEN 'create' WHEN 'UPDATE' THEN 'update' ELSE 'delete' END,
+        NULLIF(current_setting('audit.actor_id', true), '')::UUID,
+        NULLIF(current_setting('audit.impersonator_id', true), '')::UUID,
+        before_row,
+        after_row
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_users AFTER INSERT OR UPDATE OR DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_student_profiles AFTER INSERT OR UPDATE OR DELETE ON student_profiles
+    FOR EACH ROW EXECUTE FUNCTION record_audit('user_id');
+CREATE TRIGGER audit_faculty_profiles AFTER INSERT OR UPDATE OR DELETE ON faculty_profiles
+    FOR EACH ROW EXECUTE FUNCTION record_audit('user_id');
+CREATE TRIGGER audit_admin_scopes AFTER INSERT OR UPDATE OR DELETE ON admin_scopes
+    FOR EACH ROW EXECUTE FUNCTION record_audit('user_id');
+CREATE TRIGGER audit_departments AFTER INSERT OR UPDATE OR DELETE ON departments
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_terms AFTER INSERT OR UPDATE OR DELETE ON terms
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_courses AFTER INSERT OR UPDATE OR DELETE ON courses
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_course_prerequisites AFTER INSERT OR UPDATE OR DELETE ON course_prerequisites
+    FOR EACH ROW EXECUTE FUNCTION record_audit('course_id', 'prerequisite_id');
+CREATE TRIGGER audit_course_offerings AFTER INSERT OR UPDATE OR DELETE ON course_offerings
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_course_meeting_times AFTER INSERT OR UPDATE OR DELETE ON course_meeting_times
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_offering_listings AFTER INSERT OR UPDATE OR DELETE ON offering_listings
+    FOR EACH ROW EXECUTE FUNCTION record_audit('offering_id', 'course_id');
+CREATE TRIGGER audit_section_link_groups AFTER INSERT OR UPDATE OR DELETE ON section_link_groups
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_section_link_options AFTER INSERT OR UPDATE OR DELETE ON section_link_options
+    FOR EACH ROW EXECUTE FUNCTION record_audit('group_id', 'linked_offering_id');
+CREATE TRIGGER audit_seat_reservations AFTER INSERT OR UPDATE OR DELETE ON seat_reservations
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_registrations AFTER INSERT OR UPDATE OR DELETE ON registrations
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_registration_carts AFTER INSERT OR UPDATE OR DELETE ON registration_carts
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_cart_items AFTER INSERT OR UPDATE OR DELETE ON cart_items
+    FOR EACH ROW EXECUTE FUNCTION record_audit('cart_id', 'offering_id');
+CREATE TRIGGER audit_registration_overrides AFTER INSERT OR UPDATE OR DELETE ON registration_overrides
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_student_holds AFTER INSERT OR UPDATE OR DELETE ON student_holds
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_ledger_entries AFTER INSERT OR UPDATE OR DELETE ON ledger_entries
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_term_billing_rates AFTER INSERT OR UPDATE OR DELETE ON term_billing_rates
+    FOR EACH ROW EXECUTE FUNCTION record_audit('term_id');
+CREATE TRIGGER audit_term_fees AFTER INSERT OR UPDATE OR DELETE ON term_fees
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_refund_rules AFTER INSERT OR UPDATE OR DELETE ON refund_rules
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_external_institutions AFTER INSERT OR UPDATE OR DELETE ON external_institutions
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_course_equivalencies AFTER INSERT OR UPDATE OR DELETE ON course_equivalencies
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_transfer_credits AFTER INSERT OR UPDATE OR DELETE ON transfer_credits
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
@@ -0,0 +1,31 @@
+-- Add migration script here
+-- Store roles, registration statuses, weekdays, majors and grades as native enums instead of TEXT with CHECK constraints.
+-- Existing values are normalized on the way; anything that still does not match a label fails the migration rather than being guessed at.
+CREATE TYPE user_role AS ENUM ('student', 'instructor', 'admin');
+CREATE TYPE registration_status AS ENUM ('registered', 'dropped', 'waitlisted');
+CREATE TYPE weekday AS ENUM ('Monday', 'Tuesday', 'Wednesday', 'Thursday', 'Friday');
+CREATE TYPE student_major AS ENUM (
+    'Computer Science', 'Engineering', 'Biology', 'Mathematics', 'Physics', 'Psychology', 'Sociology',
+    'Politics', 'Literature', 'Business', 'Fine Arts', 'Nursing', 'Education'
+);
+CREATE TYPE grade AS ENUM ('A', 'B', 'C', 'D', 'F');
+
+ALTER TABLE users
+    DROP CONSTRAINT users_role_check,
+    ALTER COLUMN role TYPE user_role USING lower(btrim(role))::user_role;
+ALTER TABLE user_purges
+    ALTER COLUMN role TYPE user_role USING lower(btrim(role))::user_role;
+
+ALTER TABLE registrations
+    DROP CONSTRAINT registrations_status_check,
+    ALTER COLUMN status TYPE registration_status USING lower(btrim(status))::registration_status,
+    ALTER COLUMN grade TYPE grade USING upper(btrim(grade))::grade;
+
+ALTER TABLE course_meeting_times
+    DROP CONSTRAINT course_meeting_times_day_of_week_check,
+    ALTER COLUMN day_of_week TYPE weekday USING initcap(btrim(day_of_week))::weekday;
+
+ALTER TABLE student_profiles
+    ALTER COLUMN major TYPE student_major USING initcap(btrim(major))::student_major;
+ALTER TABLE seat_reservations
+    ALTER COLUMN major TYPE student_major USING initcap(btrim(major))::student_major;
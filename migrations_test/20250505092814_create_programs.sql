@@ -0,0 +1,123 @@
+-- Add migration script here
+-- PROGRAMS: the majors, minors, honours and certificates offered by departments. They replace the fixed list of majors.
+CREATE TYPE program_kind AS ENUM ('major', 'minor', 'honours', 'certificate');
+
+CREATE TABLE programs (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    department_id INT NOT NULL REFERENCES departments(id),
+    name TEXT NOT NULL,
+    kind program_kind NOT NULL,
+    UNIQUE (name, kind)
+);
+
+-- A student's declaration of a program. Declarations are ended rather than deleted, so a student's past programs stay on record.
+CREATE TABLE program_declarations (
+    id INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    program_id INT NOT NULL REFERENCES programs(id),
+    declared_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    ended_on DATE,
+    CHECK (ended_on IS NULL OR ended_on >= declared_on)
+);
+
+CREATE INDEX program_declarations_student_id_idx ON program_declarations (student_id);
+CREATE UNIQUE INDEX program_declarations_current_idx ON program_declarations (student_id, program_id) WHERE ended_on IS NULL;
+
+-- MAJOR CHANGE REQUESTS: a student asks to declare `to_program_id`, replacing `from_program_id` if set, and an admin decides.
+-- Decided requests are kept as the history of the student's changes of major.
+CREATE TYPE major_change_status AS ENUM ('pending', 'approved', 'rejected');
+
+CREATE TABLE major_change_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    student_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    from_program_id INT REFERENCES programs(id),
+    to_program_id INT NOT NULL REFERENCES programs(id),
+    reason TEXT NOT NULL,
+    status major_change_status NOT NULL DEFAULT 'pending',
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    decided_by UUID REFERENCES users(id),
+    decided_at TIMESTAMPTZ,
+    CHECK ((status = 'pending') = (decided_at IS NULL))
+);
+
+-- A student has at most one request waiting for a decision.
+CREATE UNIQUE INDEX major_change_requests_pending_idx ON major_change_requests (student_id) WHERE status = 'pending';
+
+-- Majors in use become majors of the department that offers them. Departments are looked up by an explicit code rather than matched on
+-- names, and a major in use whose department does not exist fails the migration instead of a department being made up for it.
+CREATE TEMPORARY TABLE major_departments (
+    major student_major PRIMARY KEY,
+    department_code TEXT NOT NULL
+);
+INSERT INTO major_departments (major, department_code)
+VALUES
+    ('Computer Science', 'CS'),
+    ('Engineering', 'ENGR'),
+    ('Biology', 'BIOL'),
+    ('Mathematics', 'MATH'),
+    ('Physics', 'PHYS'),
+    ('Psychology', 'PSYC'),
+    ('Sociology', 'SOC'),
+    ('Politics', 'POLS'),
+    ('Literature', 'LIT'),
+    ('Business', 'BUS'),
+    ('Fine Arts', 'ARTS'),
+    ('Nursing', 'NURS'),
+    ('Education', 'EDUC');
+
+CREATE TEMPORARY TABLE majors_in_use AS
+SELECT major FROM student_profiles
+UNION
+SELECT major FROM seat_reservations WHERE major IS NOT NULL;
+
+DO $$
+DECLARE
+    unmapped TEXT;
+BEGIN
+    SELECT string_agg(format('%s (department %s)', m.major, md.department_code), ', ' ORDER BY m.major)
+    INTO unmapped
+    FROM majors_in_use m
+    JOIN major_departments md ON md.major = m.major
+    WHERE NOT EXISTS (SELECT 1 FROM departments d WHERE d.code = md.department_code);
+
+    IF unmapped IS NOT NULL THEN
+        RAISE EXCEPTION 'Majors in use have no department to offer them: %', unmapped
+            USING HINT = 'Create the departments, or change their codes in major_departments, before migrating.';
+    END IF;
+END $$;
+
+INSERT INTO programs (department_id, name, kind)
+SELECT d.id, m.major::TEXT, 'major'
+FROM majors_in_use m
+JOIN major_departments md ON md.major = m.major
+JOIN departments d ON d.code = md.department_code;
+
+DROP TABLE majors_in_use, major_departments;
+
+-- Students keep their major, declared when their account was created.
+INSERT INTO program_declarations (student_id, program_id, declared_on)
+SELECT sp.user_id, p.id, COALESCE(u.created_at::DATE, CURRENT_DATE)
+FROM student_profiles sp
+JOIN users u ON u.id = sp.user_id
+JOIN programs p ON p.kind = 'major' AND p.name = sp.major::TEXT;
+
+ALTER TABLE student_profiles DROP COLUMN major;
+
+-- Seats are now reserved for the students of a program. Dropping `major` also drops the CHECK it was part of.
+ALTER TABLE seat_reservations ADD COLUMN program_id INT REFERENCES programs(id);
+UPDATE seat_reservations sr
+SET program_id = p.id
+FROM programs p
+WHERE p.kind = 'major' AND p.name = sr.major::TEXT;
+ALTER TABLE seat_reservations
+    DROP COLUMN major,
+    ADD CHECK (program_id IS NOT NULL OR enrollment_year IS NOT NULL);
+
+DROP TYPE student_major;
+
+CREATE TRIGGER audit_programs AFTER INSERT OR UPDATE OR DELETE ON programs
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_program_declarations AFTER INSERT OR UPDATE OR DELETE ON program_declarations
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
+CREATE TRIGGER audit_major_change_requests AFTER INSERT OR UPDATE OR DELETE ON major_change_requests
+    FOR EACH ROW EXECUTE FUNCTION record_audit();
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use crate::notifications::NotifyError;
+use crate::security::{
+    authorization::Forbidden, password_policy::WeakPassword, throttling::Throttled,
+};
+
+/// The error type returned by service functions, and by model functions that validate their input.
+/// Callers should match on the variant to decide how to react; the `String`s are messages meant for the user.
+#[derive(Debug)]
+pub enum Error {
+    /// The input was rejected before anything was changed, e.g. a term that ends before it starts.
+    Validation(String),
+    /// Something the operation needs does not exist, e.g. an offering that has been deleted.
+    NotFound(String),
+    /// The operation is not possible in the current state, e.g. registering in a full offering.
+    Conflict(String),
+    /// The acting user does not have permission to perform the operation.
+    Forbidden(Forbidden),
+    /// A login attempt was refused because of too many recent failures.
+    Throttled(Throttled),
+    /// A new password does not follow the password policy.
+    WeakPassword(WeakPassword),
+    /// The credentials were right but the account is pending email verification.
+    EmailNotVerified,
+    /// The credentials were right but the account has been deactivated.
+    AccountDeactivated,
+    /// A token, session or two-factor code was invalid or has expired.
+    Unauthenticated(String),
+    /// A notification could not be delivered.
+    Notification(NotifyError),
+    /// Something failed that never should, such as password hashing or reading a stored two-factor secret.
+    Internal(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Validation(message)
+            | Error::NotFound(message)
+            | Error::Conflict(message)
+            | Error::Unauthenticated(message) => write!(f, "{}", message),
+            Error::Forbidden(forbidden) => write!(f, "{}", forbidden),
+            Error::Throttled(throttled) => write!(f, "{}", throttled),
+            Error::WeakPassword(weak) => write!(f, "{}", weak),
+            Error::EmailNotVerified => write!(
+                f,
+                "Your email has not been verified yet. Use the token sent to your email to verify it."
+            ),
+            Error::AccountDeactivated => write!(
+                f,
+                "This account has been deactivated. Contact an administrator to reactivate it."
+            ),
+            Error::Notification(err) => write!(f, "Failed to send notification: {}", err),
+            Error::Internal(message) => write!(f, "Internal error: {}", message),
+            Error::Database(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        Error::Database(err)
+    }
+}
+
+impl From<Forbidden> for Error {
+    fn from(forbidden: Forbidden) -> Self {
+        Error::Forbidden(forbidden)
+    }
+}
+
+impl From<Throttled> for Error {
+    fn from(throttled: Throttled) -> Self {
+        Error::Throttled(throttled)
+    }
+}
+
+impl From<WeakPassword> for Error {
+    fn from(weak: WeakPassword) -> Self {
+        Error::WeakPassword(weak)
+    }
+}
+
+impl From<argon2::password_hash::Error> for Error {
+    fn from(err: argon2::password_hash::Error) -> Self {
+        Error::Internal(format!("Password hashing failed: {}", err))
+    }
+}
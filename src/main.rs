@@ -1,4 +1,6 @@
+mod error;
 mod models;
+mod notifications;
 mod security;
 mod services;
 mod tests;
@@ -10,12 +12,39 @@ use ui::app::{App, title_fn, update_fn, view_fn};
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<(), sqlx::Error> {
     dotenvy::dotenv().ok();
+    // `school_system bench-password [target ms] [max memory MiB]` suggests Argon2 parameters for this machine.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("bench-password") {
+        let target_ms = args.get(2).and_then(|arg| arg.parse().ok()).unwrap_or(250);
+        let max_memory_mib: u32 = args.get(3).and_then(|arg| arg.parse().ok()).unwrap_or(256);
+        let suggestion = security::password::suggest_params(
+            std::time::Duration::from_millis(target_ms),
+            max_memory_mib * 1024,
+            1,
+        );
+        println!(
+            "ARGON2_MEMORY_KIB={}\nARGON2_ITERATIONS={}\nARGON2_PARALLELISM={}\n# one hash took {} ms",
+            suggestion.params.memory_kib,
+            suggestion.params.iterations,
+            suggestion.params.parallelism,
+            suggestion.hash_time.as_millis()
+        );
+        return Ok(());
+    }
+
+    // Refuse to start with Argon2 parameters that would make every hash fail.
+    if let Err(err) = security::password::PasswordParams::from_env() {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+
     let database_url =
         std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in the .env file");
 
     // Create the database pool.
     let pool = PgPool::connect(&database_url).await?;
     let handle = tokio::runtime::Handle::current();
+    security::authentication::init();
     // Launch the application using the builder API.
     iced::application(title_fn, update_fn, view_fn)
         .run_with(move || App::new(pool, handle))
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// One change in the audit log. Entries are written by database triggers whenever an audited table changes, and can never be altered.
+#[derive(Debug, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// The table that changed, e.g. `course_offerings`.
+    pub entity: String,
+    /// The changed row's primary key. Composite keys are joined with `/`.
+    pub entity_id: String,
+    pub action: AuditAction,
+    /// `None` for changes made by the system rather than on behalf of a user.
+    pub actor_id: Option<Uuid>,
+    /// The admin behind the actor when the change was made through an impersonation.
+    pub impersonator_id: Option<Uuid>,
+    /// The row as JSON before the change, or `None` when it was created.
+    pub before: Option<String>,
+    /// The row as JSON after the change, or `None` when it was deleted.
+    pub after: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Narrows a search of the audit log. Unset fields match every entry.
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub entity: Option<String>,
+    pub entity_id: Option<String>,
+    pub actor_id: Option<Uuid>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries older than this one, for paging through results.
+    pub before_id: Option<i64>,
+}
+
+impl AuditEntry {
+    /// Returns the entries matching `filter`, newest first, at most `limit` of them.
+    pub async fn search<'e>(
+        filter: &AuditFilter,
+        limit: i64,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let entries = sqlx::query_as!(
+            AuditEntry,
+            r#"
+            SELECT id, entity, entity_id, action AS "action: AuditAction", actor_id, impersonator_id,
+                   before::TEXT AS before, after::TEXT AS after, changed_at
+            FROM audit_log
+            WHERE ($1::TEXT IS NULL OR entity = $1)
+            AND ($2::TEXT IS NULL OR entity_id = $2)
+            AND ($3::UUID IS NULL OR actor_id = $3)
+            AND ($4::TIMESTAMPTZ IS NULL OR changed_at >= $4)
+            AND ($5::TIMESTAMPTZ IS NULL OR changed_at < $5)
+            AND ($6::BIGINT IS NULL OR id < $6)
+            ORDER BY id DESC
+            LIMIT $7
+            "#,
+            filter.entity,
+            filter.entity_id,
+            filter.actor_id,
+            filter.since,
+            filter.until,
+            filter.before_id,
+            limit
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action_str = match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        };
+        write!(f, "{}", action_str)
+    }
+}
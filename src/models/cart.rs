@@ -0,0 +1,148 @@
+use sqlx::{
+    Acquire, FromRow, PgExecutor, Postgres,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// A student's registration cart for a term: offerings they intend to register in, validated and submitted together.
+#[derive(Debug, FromRow)]
+pub struct Cart {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CartItem {
+    pub cart_id: Uuid,
+    pub offering_id: Uuid,
+    pub added_at: DateTime<Utc>,
+}
+
+impl Cart {
+    /// Returns the student's cart for a term, creating an empty one if they do not have one yet.
+    pub async fn get_or_create<'e>(
+        student_id: Uuid,
+        term_id: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Cart, sqlx::Error> {
+        let cart = sqlx::query_as!(
+            Cart,
+            r#"
+            INSERT INTO registration_carts (id, student_id, term_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (student_id, term_id) DO UPDATE SET student_id = EXCLUDED.student_id
+            RETURNING id, student_id, term_id, created_at
+            "#,
+            Uuid::new_v4(),
+            student_id,
+            term_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(cart)
+    }
+
+    /// Returns the student's cart for a term, or `None` if they do not have one yet.
+    pub async fn find<'e>(
+        student_id: Uuid,
+        term_id: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Option<Cart>, sqlx::Error> {
+        let cart = sqlx::query_as!(
+            Cart,
+            r#"
+            SELECT id, student_id, term_id, created_at
+            FROM registration_carts
+            WHERE student_id = $1 AND term_id = $2
+            "#,
+            student_id,
+            term_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(cart)
+    }
+
+    /// Adds an offering to the cart. The offering must be in the cart's term; adding an offering twice has no effect.
+    pub async fn add_item<'a>(
+        &self,
+        offering_id: Uuid,
+        conn: impl Acquire<'a, Database = Postgres>,
+    ) -> Result<(), Error> {
+        let mut conn = conn.acquire().await?;
+        let term_id = sqlx::query_scalar!(
+            r#"
+            SELECT term_id FROM course_offerings WHERE id = $1
+            "#,
+            offering_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| Error::NotFound("Course offering does not exist.".to_string()))?;
+        if term_id != self.term_id {
+            return Err(Error::Validation(
+                "Course offering is not in this cart's term.".to_string(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO cart_items (cart_id, offering_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            self.id,
+            offering_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes an offering from the cart. Returns `false` if it was not in the cart.
+    pub async fn remove_item<'e>(
+        &self,
+        offering_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM cart_items WHERE cart_id = $1 AND offering_id = $2
+            "#,
+            self.id,
+            offering_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The offerings in the cart, in the order they were added.
+    pub async fn items<'e>(
+        &self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Vec<CartItem>, sqlx::Error> {
+        let items = sqlx::query_as!(
+            CartItem,
+            r#"
+            SELECT cart_id, offering_id, added_at
+            FROM cart_items
+            WHERE cart_id = $1
+            ORDER BY added_at, offering_id
+            "#,
+            self.id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(items)
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgExecutor, PgPool};
 use uuid::Uuid;
 
 use super::course_prerequisite::CoursePrerequisite;
@@ -13,6 +13,8 @@ pub struct Course {
     pub title: String,
     pub description: Option<String>,
     pub credits: i32,
+    /// Whether students need the instructor's permission to register in an offering of this course.
+    pub requires_consent: bool,
 }
 
 pub fn create_course(
@@ -30,6 +32,7 @@ pub fn create_course(
         title,
         description,
         credits,
+        requires_consent: false,
     }
 }
 
@@ -39,7 +42,7 @@ impl Course {
         let prerequisites = sqlx::query_as!(
             Course,
             r#"
-                SELECT c.id, c.department_id, c.course_number, c.title, c.description, c.credits
+                SELECT c.id, c.department_id, c.course_number, c.title, c.description, c.credits, c.requires_consent
                 FROM course_prerequisites cp
                 JOIN courses c ON cp.prerequisite_id = c.id
                 WHERE cp.course_id = $1
@@ -52,9 +55,9 @@ impl Course {
         Ok(prerequisites)
     }
 
-    pub async fn add_prerequisite(
+    pub async fn add_prerequisite<'e>(
         &self,
-        pool: &sqlx::PgPool,
+        executor: impl PgExecutor<'e>,
         prerequisite: &CoursePrerequisite,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
@@ -66,14 +69,14 @@ impl Course {
             prerequisite.course_id,
             prerequisite.prerequisite_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
 
-    pub async fn remove_prerequisite(
+    pub async fn remove_prerequisite<'e>(
         &self,
-        pool: &sqlx::PgPool,
+        executor: impl PgExecutor<'e>,
         prerequisite: &CoursePrerequisite,
     ) -> Result<(), sqlx::Error> {
         sqlx::query_as!(
@@ -84,13 +87,13 @@ impl Course {
             prerequisite.course_id,
             prerequisite.prerequisite_id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
-    pub async fn delete(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
         sqlx::query_as!(
             self,
             r#"
@@ -98,35 +101,55 @@ impl Course {
             "#,
             self.id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
 
-    pub async fn insert(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+    pub async fn insert<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
         sqlx::query_as!(
             self,
             r#"
-            INSERT INTO courses (id, department_id, course_number, title, description, credits)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO courses (id, department_id, course_number, title, description, credits, requires_consent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#,
             self.id,
             self.department_id,
             self.course_number,
             self.title,
             self.description,
-            self.credits
+            self.credits,
+            self.requires_consent
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
         Ok(())
     }
 
+    /// Sets whether registering in this course requires instructor consent.
+    pub async fn set_requires_consent<'e>(
+        &mut self,
+        requires_consent: bool,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE courses SET requires_consent = $2 WHERE id = $1
+            "#,
+            self.id,
+            requires_consent
+        )
+        .execute(executor)
+        .await?;
+        self.requires_consent = requires_consent;
+        Ok(())
+    }
+
     /// Returns the number of students currently registered for this course.
     pub async fn current_enrollment(&self, pool: &PgPool) -> Result<i64, sqlx::Error> {
         let record = sqlx::query!(
             r#"
-            SELECT COUNT(*) AS count
+            SELECT COUNT(*) AS "count!"
             FROM registrations r
             JOIN course_offerings co ON r.offering_id = co.id
             WHERE co.course_id = $1 AND r.status = 'registered'
@@ -136,9 +159,7 @@ impl Course {
         .fetch_one(pool)
         .await?;
 
-        Ok(record
-            .count
-            .expect("Should be able to count the number of registered students."))
+        Ok(record.count)
     }
 }
 
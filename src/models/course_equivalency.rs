@@ -0,0 +1,106 @@
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// Maps a course at an external institution to one of our courses, or to generic elective credit when `course_id` is `None`.
+#[derive(Debug, FromRow)]
+pub struct CourseEquivalency {
+    pub id: Option<i32>,
+    pub institution_id: i32,
+    pub external_course_code: String,
+    pub external_title: String,
+    pub course_id: Option<Uuid>,
+    /// The department generic elective credit is granted in. Always `None` when `course_id` is set.
+    pub elective_department_id: Option<i32>,
+    pub credits: i32,
+}
+
+/// What an external course is worth at our institution.
+pub enum Equivalent {
+    Course(Uuid),
+    Elective { department_id: Option<i32> },
+}
+
+impl CourseEquivalency {
+    fn new(
+        institution_id: i32,
+        external_course_code: String,
+        external_title: String,
+        equivalent: Equivalent,
+        credits: i32,
+    ) -> Result<Self, String> {
+        if credits <= 0 {
+            return Err("Credits must be greater than 0!".to_string());
+        }
+        let (course_id, elective_department_id) = match equivalent {
+            Equivalent::Course(course_id) => (Some(course_id), None),
+            Equivalent::Elective { department_id } => (None, department_id),
+        };
+        Ok(CourseEquivalency {
+            id: None,
+            institution_id,
+            external_course_code,
+            external_title,
+            course_id,
+            elective_department_id,
+            credits,
+        })
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<CourseEquivalency, sqlx::Error> {
+        let equivalency = sqlx::query_as!(
+            CourseEquivalency,
+            r#"
+            INSERT INTO course_equivalencies (institution_id, external_course_code, external_title, course_id, elective_department_id, credits)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, institution_id, external_course_code, external_title, course_id, elective_department_id, credits
+            "#,
+            self.institution_id,
+            self.external_course_code,
+            self.external_title,
+            self.course_id,
+            self.elective_department_id,
+            self.credits
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(equivalency)
+    }
+
+    pub async fn create<'e>(
+        institution_id: i32,
+        external_course_code: String,
+        external_title: String,
+        equivalent: Equivalent,
+        credits: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<CourseEquivalency, Error> {
+        let equivalency = Self::new(
+            institution_id,
+            external_course_code,
+            external_title,
+            equivalent,
+            credits,
+        )
+        .map_err(Error::Validation)?;
+        Ok(equivalency.insert(executor).await?)
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM course_equivalencies WHERE id = $1
+            "#,
+            self.id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     str::FromStr,
 };
 
-use sqlx::{FromRow, types::chrono::NaiveTime};
+use sqlx::{FromRow, PgExecutor, types::chrono::NaiveTime};
 use uuid::Uuid;
 
 #[derive(Debug, FromRow)]
@@ -31,38 +31,41 @@ impl CourseMeetingTime {
         }
     }
 
-    async fn insert(self, pool: &sqlx::PgPool) -> Result<CourseMeetingTime, sqlx::Error> {
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<CourseMeetingTime, sqlx::Error> {
         let course_meeting_time = sqlx::query_as!(
             CourseMeetingTime,
             r#"
             INSERT INTO course_meeting_times (offering_id, day_of_week, start_time, end_time)
             VALUES($1, $2, $3, $4)
-            RETURNING id, offering_id, day_of_week, start_time, end_time
+            RETURNING id, offering_id, day_of_week AS "day_of_week: Weekday", start_time, end_time
             "#,
             self.offering_id,
-            self.day_of_week.to_string(),
+            self.day_of_week as Weekday,
             self.start_time,
             self.end_time
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(course_meeting_time)
     }
 
-    pub async fn create(
+    pub async fn create<'e>(
         offering_id: Uuid,
         day_of_week: Weekday,
         start_time: NaiveTime,
         end_time: NaiveTime,
-        pool: &sqlx::PgPool,
+        executor: impl PgExecutor<'e>,
     ) -> Result<CourseMeetingTime, sqlx::Error> {
         let course_meeting_time =
             CourseMeetingTime::new(offering_id, day_of_week, start_time, end_time);
-        Ok(course_meeting_time.insert(pool).await?)
+        course_meeting_time.insert(executor).await
     }
 
-    pub async fn delete(&self, pool: &sqlx::PgPool) -> Result<(), sqlx::Error> {
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
         sqlx::query_as!(
             self,
             r#"
@@ -70,7 +73,7 @@ impl CourseMeetingTime {
             "#,
             self.id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -91,14 +94,14 @@ impl Display for CourseMeetingTime {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "weekday")]
 pub enum Weekday {
     Monday,
     Tuesday,
     Wednesday,
     Thursday,
     Friday,
-    Invalid,
 }
 
 impl FromStr for Weekday {
@@ -115,19 +118,6 @@ impl FromStr for Weekday {
     }
 }
 
-impl From<std::string::String> for Weekday {
-    fn from(value: std::string::String) -> Self {
-        match value.to_lowercase().as_str() {
-            "monday" => Weekday::Monday,
-            "tuesday" => Weekday::Tuesday,
-            "wednesday" => Weekday::Wednesday,
-            "thursday" => Weekday::Thursday,
-            "friday" => Weekday::Friday,
-            _ => Weekday::Invalid, //ideally we never hit this branch. sqlx insists on using From<> instead of TryFrom<>, but we only allow inserting things from the enum so we should be good
-        }
-    }
-}
-
 impl Display for Weekday {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let day_str = match self {
@@ -136,7 +126,6 @@ impl Display for Weekday {
             Weekday::Wednesday => "Wednesday",
             Weekday::Thursday => "Thursday",
             Weekday::Friday => "Friday",
-            Weekday::Invalid => "Invalid",
         };
         write!(f, "{}", day_str)
     }
@@ -1,8 +1,11 @@
-use std::fmt::Display;
+use std::fmt::{self, Display};
 
-use sqlx::{FromRow, PgPool};
+use sqlx::{Acquire, FromRow, PgExecutor, PgPool, Postgres};
 use uuid::Uuid;
 
+use super::offering_listing::OfferingListing;
+use crate::error::Error;
+
 #[derive(Debug, FromRow)]
 pub struct CourseOffering {
     pub id: Uuid,
@@ -11,6 +14,8 @@ pub struct CourseOffering {
     pub instructor_id: Uuid,
     pub capacity: i32,
     pub location: String,
+    pub section_type: SectionType,
+    pub section_number: String,
 }
 
 impl CourseOffering {
@@ -18,12 +23,16 @@ impl CourseOffering {
         course_id: Uuid,
         term_id: i32,
         instructor_id: Uuid,
+        section: Section,
         capacity: i32,
         location: String,
     ) -> Result<Self, String> {
-        if capacity < 0 {
+        if capacity <= 0 {
             return Err("Capacity must be greater than 0!".to_string());
         }
+        if section.number.trim().is_empty() {
+            return Err("Section number cannot be empty!".to_string());
+        }
         Ok(CourseOffering {
             id: Uuid::new_v4(),
             course_id,
@@ -31,41 +40,120 @@ impl CourseOffering {
             instructor_id,
             capacity,
             location,
+            section_type: section.section_type,
+            section_number: section.number,
         })
     }
 
-    async fn insert(self, pool: &PgPool) -> Result<CourseOffering, sqlx::Error> {
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<CourseOffering, sqlx::Error> {
         let course_offering = sqlx::query_as!(
             CourseOffering,
             r#"
-            INSERT INTO course_offerings (course_id, term_id, instructor_id, capacity, location)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, course_id, term_id, instructor_id, capacity, location
+            INSERT INTO course_offerings (course_id, term_id, instructor_id, capacity, location, section_type, section_number)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, course_id, term_id, instructor_id, capacity, location, section_type AS "section_type: SectionType", section_number
             "#,
             self.course_id,
             self.term_id,
             self.instructor_id,
             self.capacity,
-            self.location
+            self.location,
+            self.section_type.to_string(),
+            self.section_number
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(course_offering)
     }
 
-    pub async fn create(
+    pub async fn create<'a>(
         course_id: Uuid,
         term_id: i32,
         instructor_id: Uuid,
+        section: Section,
         capacity: i32,
         location: String,
-        pool: &PgPool,
-    ) -> Result<CourseOffering, sqlx::Error> {
-        let course_offering =
-            CourseOffering::new(course_id, term_id, instructor_id, capacity, location)
-                .map_err(|err| sqlx::Error::Protocol(err))?;
-        course_offering.insert(pool).await
+        conn: impl Acquire<'a, Database = Postgres>,
+    ) -> Result<CourseOffering, Error> {
+        let course_offering = CourseOffering::new(
+            course_id,
+            term_id,
+            instructor_id,
+            section,
+            capacity,
+            location,
+        )
+        .map_err(Error::Validation)?;
+        let mut conn = conn.acquire().await?;
+        Self::check_instructor(instructor_id, term_id, &mut *conn).await?;
+        Ok(course_offering.insert(&mut *conn).await?)
+    }
+
+    /// Refuses an instructor who is not an active user with the instructor role, or who already teaches their maximum load of sections in the term.
+    async fn check_instructor<'e>(
+        instructor_id: Uuid,
+        term_id: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), Error> {
+        let instructor = sqlx::query!(
+            r#"
+            SELECT fp.max_teaching_load, (
+                SELECT COUNT(*) FROM course_offerings WHERE instructor_id = u.id AND term_id = $2
+            ) AS "sections!"
+            FROM users u
+            JOIN faculty_profiles fp ON fp.user_id = u.id
+            WHERE u.id = $1 AND u.role = 'instructor' AND u.deactivated_at IS NULL
+            "#,
+            instructor_id,
+            term_id
+        )
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| {
+            Error::Validation("Offerings must be taught by an instructor!".to_string())
+        })?;
+
+        if instructor.sections >= instructor.max_teaching_load as i64 {
+            return Err(Error::Conflict(format!(
+                "Instructor already teaches their maximum of {} sections this term!",
+                instructor.max_teaching_load
+            )));
+        }
+        Ok(())
+    }
+
+    /// Cross-lists this offering under another course, so students can find and register for it under that course's number.
+    pub async fn cross_list<'e>(
+        &self,
+        course_id: Uuid,
+        seat_cap: Option<i32>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<OfferingListing, Error> {
+        if course_id == self.course_id {
+            return Err(Error::Validation(
+                "An offering cannot be cross-listed under its own course!".to_string(),
+            ));
+        }
+        OfferingListing::create(self.id, course_id, seat_cap, executor).await
+    }
+
+    /// Returns the additional listings of this offering, not including its primary course.
+    pub async fn listings(&self, pool: &PgPool) -> Result<Vec<OfferingListing>, sqlx::Error> {
+        let listings = sqlx::query_as!(
+            OfferingListing,
+            r#"
+            SELECT offering_id, course_id, seat_cap FROM offering_listings WHERE offering_id = $1
+            "#,
+            self.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(listings)
     }
 }
 
@@ -73,8 +161,50 @@ impl Display for CourseOffering {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "ID: {}\nTerm ID: {}\nInstructor ID: {}\nCapacity: {}\nLocation: {}",
-            self.course_id, self.term_id, self.instructor_id, self.capacity, self.location
+            "ID: {}\nSection: {} {}\nTerm ID: {}\nInstructor ID: {}\nCapacity: {}\nLocation: {}",
+            self.course_id,
+            self.section_type,
+            self.section_number,
+            self.term_id,
+            self.instructor_id,
+            self.capacity,
+            self.location
         )
     }
 }
+
+/// Identifies a section of a course within a term, e.g. lab L01.
+pub struct Section {
+    pub section_type: SectionType,
+    pub number: String,
+}
+
+impl Section {
+    pub fn new(section_type: SectionType, number: &str) -> Section {
+        Section {
+            section_type,
+            number: number.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum SectionType {
+    Lecture,
+    Lab,
+    Tutorial,
+    Seminar,
+}
+
+impl fmt::Display for SectionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let section_str = match self {
+            SectionType::Lecture => "lecture",
+            SectionType::Lab => "lab",
+            SectionType::Tutorial => "tutorial",
+            SectionType::Seminar => "seminar",
+        };
+        write!(f, "{}", section_str)
+    }
+}
@@ -1,4 +1,4 @@
-use sqlx::{PgPool, prelude::FromRow};
+use sqlx::{PgExecutor, prelude::FromRow};
 
 #[derive(Debug, FromRow)]
 pub struct Department {
@@ -8,7 +8,7 @@ pub struct Department {
 }
 
 impl Department {
-    pub async fn delete(&self, pool: &PgPool) -> Result<(), sqlx::Error> {
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
         sqlx::query_as!(
             self,
             r#"
@@ -16,13 +16,13 @@ impl Department {
         "#,
             self.id
         )
-        .execute(pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
 
-    async fn insert(self, pool: &PgPool) -> Result<Department, sqlx::Error> {
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<Department, sqlx::Error> {
         let department = sqlx::query_as!(
             Department,
             r#"
@@ -33,7 +33,7 @@ impl Department {
             self.code,
             self.name
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(department)
@@ -49,11 +49,11 @@ fn new(code: String, name: String) -> Department {
     }
 }
 
-pub async fn create_department(
+pub async fn create_department<'e>(
     code: String,
     name: String,
-    pool: &PgPool,
+    executor: impl PgExecutor<'e>,
 ) -> Result<Department, sqlx::Error> {
     let department = new(code, name);
-    Ok(department.insert(pool).await?)
+    department.insert(executor).await
 }
@@ -0,0 +1,51 @@
+use chrono::Duration;
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// How long an email verification token can be redeemed after it is issued.
+pub const VERIFICATION_TOKEN_LIFETIME: Duration = Duration::hours(24);
+
+/// A single-use token proving a user controls their email. The token itself is only sent to the user; the database stores its hash.
+#[derive(Debug, FromRow)]
+pub struct EmailVerificationToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl EmailVerificationToken {
+    pub async fn create<'e>(
+        user_id: Uuid,
+        token_hash: &str,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<EmailVerificationToken, sqlx::Error> {
+        let now = Utc::now();
+        let token = sqlx::query_as!(
+            EmailVerificationToken,
+            r#"
+            INSERT INTO email_verification_tokens (id, user_id, token_hash, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, created_at, expires_at, used_at
+            "#,
+            Uuid::new_v4(),
+            user_id,
+            token_hash,
+            now,
+            now + VERIFICATION_TOKEN_LIFETIME
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// A token can be redeemed once, before it expires.
+    pub fn is_redeemable(&self, now: DateTime<Utc>) -> bool {
+        self.used_at.is_none() && now < self.expires_at
+    }
+}
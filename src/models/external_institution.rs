@@ -0,0 +1,60 @@
+use sqlx::{FromRow, PgExecutor};
+
+/// Another school that a student may bring transfer credit from.
+#[derive(Debug, FromRow)]
+pub struct ExternalInstitution {
+    pub id: Option<i32>,
+    pub name: String,
+    pub location: Option<String>,
+}
+
+impl ExternalInstitution {
+    fn new(name: String, location: Option<String>) -> ExternalInstitution {
+        ExternalInstitution {
+            id: None,
+            name,
+            location,
+        }
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<ExternalInstitution, sqlx::Error> {
+        let institution = sqlx::query_as!(
+            ExternalInstitution,
+            r#"
+            INSERT INTO external_institutions (name, location)
+            VALUES ($1, $2)
+            RETURNING id, name, location
+            "#,
+            self.name,
+            self.location
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(institution)
+    }
+
+    pub async fn create<'e>(
+        name: String,
+        location: Option<String>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<ExternalInstitution, sqlx::Error> {
+        Self::new(name, location).insert(executor).await
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM external_institutions WHERE id = $1
+            "#,
+            self.id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// An instructor's appointment details. `max_teaching_load` is the most sections they may teach in a single term.
+#[derive(Debug, FromRow)]
+pub struct FacultyProfile {
+    pub user_id: Uuid,
+    pub department_id: i32,
+    pub title: FacultyTitle,
+    pub office: Option<String>,
+    pub office_hours: Option<String>,
+    pub max_teaching_load: i32,
+}
+
+impl FacultyProfile {
+    fn new(
+        user_id: Uuid,
+        department_id: i32,
+        title: FacultyTitle,
+        max_teaching_load: i32,
+    ) -> Result<Self, String> {
+        if max_teaching_load <= 0 {
+            return Err("Maximum teaching load must be at least one section!".to_string());
+        }
+        Ok(FacultyProfile {
+            user_id,
+            department_id,
+            title,
+            office: None,
+            office_hours: None,
+            max_teaching_load,
+        })
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<FacultyProfile, sqlx::Error> {
+        let faculty_profile = sqlx::query_as!(
+            FacultyProfile,
+            r#"
+            INSERT INTO faculty_profiles (user_id, department_id, title, office, office_hours, max_teaching_load)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING user_id, department_id, title AS "title: FacultyTitle", office, office_hours, max_teaching_load
+            "#,
+            self.user_id,
+            self.department_id,
+            self.title.to_string(),
+            self.office,
+            self.office_hours,
+            self.max_teaching_load
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(faculty_profile)
+    }
+
+    pub async fn create<'e>(
+        user_id: Uuid,
+        department_id: i32,
+        title: FacultyTitle,
+        max_teaching_load: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<FacultyProfile, Error> {
+        let faculty_profile = Self::new(user_id, department_id, title, max_teaching_load)
+            .map_err(Error::Validation)?;
+        Ok(faculty_profile.insert(executor).await?)
+    }
+
+    /// Sets where and when students can find the instructor. `None` clears the value.
+    pub async fn set_office<'e>(
+        &mut self,
+        office: Option<String>,
+        office_hours: Option<String>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE faculty_profiles SET office = $2, office_hours = $3 WHERE user_id = $1
+            "#,
+            self.user_id,
+            office,
+            office_hours
+        )
+        .execute(executor)
+        .await?;
+
+        self.office = office;
+        self.office_hours = office_hours;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum FacultyTitle {
+    Lecturer,
+    AssistantProfessor,
+    AssociateProfessor,
+    Professor,
+}
+
+impl fmt::Display for FacultyTitle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title_str = match self {
+            FacultyTitle::Lecturer => "lecturer",
+            FacultyTitle::AssistantProfessor => "assistant_professor",
+            FacultyTitle::AssociateProfessor => "associate_professor",
+            FacultyTitle::Professor => "professor",
+        };
+        write!(f, "{}", title_str)
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// A hold on a student's account. While a hold is active the student cannot register for courses.
+#[derive(Debug, FromRow)]
+pub struct Hold {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub hold_type: HoldType,
+    pub reason: String,
+    /// `None` when the hold was placed automatically by the system.
+    pub placed_by: Option<Uuid>,
+    pub placed_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub released_by: Option<Uuid>,
+    pub released_at: Option<DateTime<Utc>>,
+}
+
+impl Hold {
+    fn new(
+        student_id: Uuid,
+        hold_type: HoldType,
+        reason: String,
+        placed_by: Option<Uuid>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, String> {
+        if reason.trim().is_empty() {
+            return Err("A hold must have a reason!".to_string());
+        }
+        Ok(Hold {
+            id: Uuid::new_v4(),
+            student_id,
+            hold_type,
+            reason,
+            placed_by,
+            placed_at: Utc::now(),
+            expires_at,
+            released_by: None,
+            released_at: None,
+        })
+    }
+
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<Hold, sqlx::Error> {
+        let hold = sqlx::query_as!(
+            Hold,
+            r#"
+            INSERT INTO student_holds (id, student_id, hold_type, reason, placed_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, student_id, hold_type AS "hold_type: HoldType", reason, placed_by, placed_at, expires_at, released_by, released_at
+            "#,
+            self.id,
+            self.student_id,
+            self.hold_type.to_string(),
+            self.reason,
+            self.placed_by,
+            self.expires_at
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(hold)
+    }
+
+    pub async fn create<'e>(
+        student_id: Uuid,
+        hold_type: HoldType,
+        reason: String,
+        placed_by: Option<Uuid>,
+        expires_at: Option<DateTime<Utc>>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Hold, Error> {
+        let hold = Hold::new(student_id, hold_type, reason, placed_by, expires_at)
+            .map_err(Error::Validation)?;
+        Ok(hold.insert(executor).await?)
+    }
+
+    /// Marks the hold as released by `released_by`. Releasing a hold that was already released is a no-op.
+    pub async fn release<'e>(
+        &mut self,
+        released_by: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            UPDATE student_holds
+            SET released_by = COALESCE(released_by, $2), released_at = COALESCE(released_at, now())
+            WHERE id = $1
+            RETURNING released_by, released_at
+            "#,
+            self.id,
+            released_by
+        )
+        .fetch_one(executor)
+        .await?;
+
+        self.released_by = record.released_by;
+        self.released_at = record.released_at;
+        Ok(())
+    }
+
+    /// A hold is active if it has not been released and has not yet expired.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.released_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+impl fmt::Display for Hold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hold: {}", self.hold_type, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum HoldType {
+    Financial,
+    Advising,
+    Disciplinary,
+    Admin,
+}
+
+impl fmt::Display for HoldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hold_str = match self {
+            HoldType::Financial => "financial",
+            HoldType::Advising => "advising",
+            HoldType::Disciplinary => "disciplinary",
+            HoldType::Admin => "admin",
+        };
+        write!(f, "{}", hold_str)
+    }
+}
@@ -0,0 +1,210 @@
+use std::fmt;
+
+use chrono::Duration;
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// How long an impersonation lasts before the admin has to start a new one.
+pub const IMPERSONATION_LIFETIME: Duration = Duration::hours(1);
+
+/// An admin acting as a student through a session of the student's, e.g. to see what they see when debugging a registration problem.
+#[derive(Debug, Clone, FromRow)]
+pub struct Impersonation {
+    pub id: Uuid,
+    /// The admin doing the impersonating.
+    pub admin_id: Uuid,
+    /// The student being impersonated.
+    pub target_id: Uuid,
+    pub session_id: Uuid,
+    pub reason: String,
+    /// Read-only impersonations cannot change anything on the student's behalf.
+    pub read_only: bool,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl Impersonation {
+    pub async fn create<'e>(
+        admin_id: Uuid,
+        target_id: Uuid,
+        session_id: Uuid,
+        reason: &str,
+        read_only: bool,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Impersonation, Error> {
+        if reason.trim().is_empty() {
+            return Err(Error::Validation(
+                "A reason is required to impersonate a user.".to_string(),
+            ));
+        }
+        let impersonation = sqlx::query_as!(
+            Impersonation,
+            r#"
+            INSERT INTO impersonations (id, admin_id, target_id, session_id, reason, read_only)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, admin_id, target_id, session_id, reason, read_only, started_at, ended_at
+            "#,
+            Uuid::new_v4(),
+            admin_id,
+            target_id,
+            session_id,
+            reason.trim(),
+            read_only
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(impersonation)
+    }
+
+    /// Returns the impersonation carried out through a session, if it has not ended.
+    pub async fn active_for_session<'e>(
+        session_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Option<Impersonation>, sqlx::Error> {
+        let impersonation = sqlx::query_as!(
+            Impersonation,
+            r#"
+            SELECT id, admin_id, target_id, session_id, reason, read_only, started_at, ended_at
+            FROM impersonations
+            WHERE session_id = $1 AND ended_at IS NULL
+            "#,
+            session_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(impersonation)
+    }
+
+    /// Returns every impersonation of a user, newest first.
+    pub async fn for_target<'e>(
+        target_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Vec<Impersonation>, sqlx::Error> {
+        let impersonations = sqlx::query_as!(
+            Impersonation,
+            r#"
+            SELECT id, admin_id, target_id, session_id, reason, read_only, started_at, ended_at
+            FROM impersonations
+            WHERE target_id = $1
+            ORDER BY started_at DESC
+            "#,
+            target_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(impersonations)
+    }
+}
+
+/// One entry in an impersonation's audit trail.
+#[derive(Debug, FromRow)]
+pub struct ImpersonationEvent {
+    pub id: Uuid,
+    pub impersonation_id: Uuid,
+    /// The admin behind the impersonation, rather than the student it acted as.
+    pub admin_id: Uuid,
+    pub kind: ImpersonationEventKind,
+    pub detail: String,
+    /// How an action turned out. Only actions have an outcome.
+    pub outcome: Option<ActionOutcome>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ImpersonationEvent {
+    pub async fn record<'e>(
+        impersonation: &Impersonation,
+        kind: ImpersonationEventKind,
+        detail: &str,
+        outcome: Option<ActionOutcome>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<ImpersonationEvent, sqlx::Error> {
+        let event = sqlx::query_as!(
+            ImpersonationEvent,
+            r#"
+            INSERT INTO impersonation_events (id, impersonation_id, admin_id, kind, detail, outcome)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, impersonation_id, admin_id, kind AS "kind: ImpersonationEventKind", detail, outcome AS "outcome: ActionOutcome", occurred_at
+            "#,
+            Uuid::new_v4(),
+            impersonation.id,
+            impersonation.admin_id,
+            kind.to_string(),
+            detail,
+            outcome.map(|outcome| outcome.to_string())
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(event)
+    }
+
+    /// Returns an impersonation's audit trail in the order it happened.
+    pub async fn for_impersonation<'e>(
+        impersonation_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Vec<ImpersonationEvent>, sqlx::Error> {
+        let events = sqlx::query_as!(
+            ImpersonationEvent,
+            r#"
+            SELECT id, impersonation_id, admin_id, kind AS "kind: ImpersonationEventKind", detail, outcome AS "outcome: ActionOutcome", occurred_at
+            FROM impersonation_events
+            WHERE impersonation_id = $1
+            ORDER BY occurred_at
+            "#,
+            impersonation_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(events)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ImpersonationEventKind {
+    Start,
+    Stop,
+    /// A service the impersonation called, whether or not it was allowed.
+    Action,
+}
+
+impl fmt::Display for ImpersonationEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_str = match self {
+            ImpersonationEventKind::Start => "start",
+            ImpersonationEventKind::Stop => "stop",
+            ImpersonationEventKind::Action => "action",
+        };
+        write!(f, "{}", kind_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ActionOutcome {
+    Succeeded,
+    /// The actor was not allowed to take the action, e.g. through a read-only impersonation.
+    Refused,
+    /// The action was allowed but did not go through, e.g. because the offering was full.
+    Failed,
+}
+
+impl fmt::Display for ActionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome_str = match self {
+            ActionOutcome::Succeeded => "succeeded",
+            ActionOutcome::Refused => "refused",
+            ActionOutcome::Failed => "failed",
+        };
+        write!(f, "{}", outcome_str)
+    }
+}
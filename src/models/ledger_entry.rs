@@ -0,0 +1,207 @@
+use std::fmt;
+
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// A single double-entry posting on a student's account: `amount_cents` is debited to `debit_account` and credited to `credit_account`.
+/// The student's balance is the net debit of the `Receivable` account.
+#[derive(Debug, FromRow)]
+pub struct LedgerEntry {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub term_id: i32,
+    pub registration_id: Option<Uuid>,
+    pub term_fee_id: Option<i32>,
+    pub entry_type: EntryType,
+    pub debit_account: LedgerAccount,
+    pub credit_account: LedgerAccount,
+    pub amount_cents: i64,
+    pub description: String,
+    pub posted_at: DateTime<Utc>,
+}
+
+impl LedgerEntry {
+    fn new(
+        student_id: Uuid,
+        term_id: i32,
+        entry_type: EntryType,
+        amount_cents: i64,
+        description: String,
+        reference: EntryReference,
+    ) -> Result<Self, String> {
+        if amount_cents <= 0 {
+            return Err("Ledger entries must have a positive amount!".to_string());
+        }
+        let (debit_account, credit_account) = entry_type.accounts(&reference);
+        let (registration_id, term_fee_id) = match reference {
+            EntryReference::Registration(id) => (Some(id), None),
+            EntryReference::TermFee(id) => (None, Some(id)),
+            EntryReference::None => (None, None),
+        };
+        Ok(LedgerEntry {
+            id: Uuid::new_v4(),
+            student_id,
+            term_id,
+            registration_id,
+            term_fee_id,
+            entry_type,
+            debit_account,
+            credit_account,
+            amount_cents,
+            description,
+            posted_at: Utc::now(),
+        })
+    }
+
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<LedgerEntry, sqlx::Error> {
+        let entry = sqlx::query_as!(
+            LedgerEntry,
+            r#"
+            INSERT INTO ledger_entries (id, student_id, term_id, registration_id, term_fee_id, entry_type, debit_account, credit_account, amount_cents, description)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, student_id, term_id, registration_id, term_fee_id, entry_type AS "entry_type: EntryType", debit_account AS "debit_account: LedgerAccount", credit_account AS "credit_account: LedgerAccount", amount_cents, description, posted_at
+            "#,
+            self.id,
+            self.student_id,
+            self.term_id,
+            self.registration_id,
+            self.term_fee_id,
+            self.entry_type.to_string(),
+            self.debit_account.to_string(),
+            self.credit_account.to_string(),
+            self.amount_cents,
+            self.description
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(entry)
+    }
+
+    /// Posts an entry to the ledger. The debit and credit accounts are determined by `entry_type` and what the entry refers to.
+    pub async fn post<'e>(
+        student_id: Uuid,
+        term_id: i32,
+        entry_type: EntryType,
+        amount_cents: i64,
+        description: String,
+        reference: EntryReference,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<LedgerEntry, Error> {
+        let entry = LedgerEntry::new(
+            student_id,
+            term_id,
+            entry_type,
+            amount_cents,
+            description,
+            reference,
+        )
+        .map_err(Error::Validation)?;
+        Ok(entry.insert(executor).await?)
+    }
+
+    /// The effect of this entry on the student's balance: positive for charges, negative for refunds and payments.
+    pub fn balance_effect(&self) -> i64 {
+        if self.debit_account == LedgerAccount::Receivable {
+            self.amount_cents
+        } else if self.credit_account == LedgerAccount::Receivable {
+            -self.amount_cents
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for LedgerEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<8} {:<40} {}",
+            self.posted_at.format("%Y-%m-%d"),
+            self.entry_type,
+            self.description,
+            format_cents(self.balance_effect())
+        )
+    }
+}
+
+/// Formats an amount of cents as dollars, e.g. `-1250` becomes `-$12.50`.
+pub fn format_cents(amount_cents: i64) -> String {
+    let sign = if amount_cents < 0 { "-" } else { "" };
+    format!(
+        "{}${}.{:02}",
+        sign,
+        amount_cents.abs() / 100,
+        amount_cents.abs() % 100
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum EntryType {
+    Tuition,
+    Fee,
+    Refund,
+    Payment,
+}
+
+/// What a ledger entry was posted for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryReference {
+    Registration(Uuid),
+    TermFee(i32),
+    None,
+}
+
+impl EntryType {
+    /// The (debit, credit) accounts for each kind of entry. Refunds reverse whichever revenue account the original charge went to.
+    fn accounts(&self, reference: &EntryReference) -> (LedgerAccount, LedgerAccount) {
+        match (self, reference) {
+            (EntryType::Tuition, _) => (LedgerAccount::Receivable, LedgerAccount::TuitionRevenue),
+            (EntryType::Fee, _) => (LedgerAccount::Receivable, LedgerAccount::FeeRevenue),
+            (EntryType::Refund, EntryReference::TermFee(_)) => {
+                (LedgerAccount::FeeRevenue, LedgerAccount::Receivable)
+            }
+            (EntryType::Refund, _) => (LedgerAccount::TuitionRevenue, LedgerAccount::Receivable),
+            (EntryType::Payment, _) => (LedgerAccount::Cash, LedgerAccount::Receivable),
+        }
+    }
+}
+
+impl fmt::Display for EntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entry_str = match self {
+            EntryType::Tuition => "tuition",
+            EntryType::Fee => "fee",
+            EntryType::Refund => "refund",
+            EntryType::Payment => "payment",
+        };
+        write!(f, "{}", entry_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum LedgerAccount {
+    Receivable,
+    TuitionRevenue,
+    FeeRevenue,
+    Cash,
+}
+
+impl fmt::Display for LedgerAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let account_str = match self {
+            LedgerAccount::Receivable => "receivable",
+            LedgerAccount::TuitionRevenue => "tuition_revenue",
+            LedgerAccount::FeeRevenue => "fee_revenue",
+            LedgerAccount::Cash => "cash",
+        };
+        write!(f, "{}", account_str)
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// One entry in the login history. Every attempt is recorded, whether or not it succeeded.
+#[derive(Debug, FromRow)]
+pub struct LoginAttempt {
+    pub id: Uuid,
+    pub email: String,
+    /// `None` when the email did not match an account.
+    pub user_id: Option<Uuid>,
+    pub source: String,
+    pub outcome: LoginOutcome,
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl LoginAttempt {
+    pub async fn record<'e>(
+        email: &str,
+        user_id: Option<Uuid>,
+        source: &str,
+        outcome: LoginOutcome,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<LoginAttempt, sqlx::Error> {
+        let attempt = sqlx::query_as!(
+            LoginAttempt,
+            r#"
+            INSERT INTO login_attempts (id, email, user_id, source, outcome)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, email, user_id, source, outcome AS "outcome: LoginOutcome", attempted_at
+            "#,
+            Uuid::new_v4(),
+            email,
+            user_id,
+            source,
+            outcome.to_string()
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(attempt)
+    }
+
+    /// Returns a user's login history, newest first.
+    pub async fn for_user<'e>(
+        user_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Vec<LoginAttempt>, sqlx::Error> {
+        let attempts = sqlx::query_as!(
+            LoginAttempt,
+            r#"
+            SELECT id, email, user_id, source, outcome AS "outcome: LoginOutcome", attempted_at
+            FROM login_attempts
+            WHERE user_id = $1
+            ORDER BY attempted_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(attempts)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum LoginOutcome {
+    Success,
+    /// The email or password was wrong.
+    Invalid,
+    /// The attempt was refused without checking the password because of recent failures.
+    Throttled,
+    /// The attempt was refused because the account is locked.
+    Locked,
+    /// The password was right but the account is pending email verification.
+    Unverified,
+    /// The password was right but the account has been deactivated.
+    Deactivated,
+}
+
+impl fmt::Display for LoginOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome_str = match self {
+            LoginOutcome::Success => "success",
+            LoginOutcome::Invalid => "invalid",
+            LoginOutcome::Throttled => "throttled",
+            LoginOutcome::Locked => "locked",
+            LoginOutcome::Unverified => "unverified",
+            LoginOutcome::Deactivated => "deactivated",
+        };
+        write!(f, "{}", outcome_str)
+    }
+}
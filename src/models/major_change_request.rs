@@ -0,0 +1,132 @@
+use std::fmt;
+
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// A student's request to declare `to_program_id` as a major, replacing `from_program_id` if it is set. Requests are kept after they are
+/// decided, as the history of the student's changes of major.
+#[derive(Debug, FromRow)]
+pub struct MajorChangeRequest {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub from_program_id: Option<i32>,
+    pub to_program_id: i32,
+    pub reason: String,
+    pub status: MajorChangeStatus,
+    pub requested_at: DateTime<Utc>,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl MajorChangeRequest {
+    fn new(
+        student_id: Uuid,
+        from_program_id: Option<i32>,
+        to_program_id: i32,
+        reason: String,
+    ) -> Result<Self, String> {
+        if reason.trim().is_empty() {
+            return Err("A major change request must have a reason!".to_string());
+        }
+        if from_program_id == Some(to_program_id) {
+            return Err("A major cannot be changed to itself!".to_string());
+        }
+        Ok(MajorChangeRequest {
+            id: Uuid::new_v4(),
+            student_id,
+            from_program_id,
+            to_program_id,
+            reason,
+            status: MajorChangeStatus::Pending,
+            requested_at: Utc::now(),
+            decided_by: None,
+            decided_at: None,
+        })
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<MajorChangeRequest, sqlx::Error> {
+        let request = sqlx::query_as!(
+            MajorChangeRequest,
+            r#"
+            INSERT INTO major_change_requests (id, student_id, from_program_id, to_program_id, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, student_id, from_program_id, to_program_id, reason, status AS "status: MajorChangeStatus", requested_at, decided_by, decided_at
+            "#,
+            self.id,
+            self.student_id,
+            self.from_program_id,
+            self.to_program_id,
+            self.reason
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(request)
+    }
+
+    pub async fn create<'e>(
+        student_id: Uuid,
+        from_program_id: Option<i32>,
+        to_program_id: i32,
+        reason: String,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<MajorChangeRequest, Error> {
+        let request = MajorChangeRequest::new(student_id, from_program_id, to_program_id, reason)
+            .map_err(Error::Validation)?;
+        Ok(request.insert(executor).await?)
+    }
+
+    /// Records the decision on the request by `decided_by`.
+    pub async fn decide<'e>(
+        &mut self,
+        status: MajorChangeStatus,
+        decided_by: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
+        let record = sqlx::query!(
+            r#"
+            UPDATE major_change_requests
+            SET status = $2, decided_by = $3, decided_at = now()
+            WHERE id = $1
+            RETURNING decided_at
+            "#,
+            self.id,
+            status as MajorChangeStatus,
+            decided_by
+        )
+        .fetch_one(executor)
+        .await?;
+
+        self.status = status;
+        self.decided_by = Some(decided_by);
+        self.decided_at = record.decided_at;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "major_change_status", rename_all = "lowercase")]
+pub enum MajorChangeStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl fmt::Display for MajorChangeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status_str = match self {
+            MajorChangeStatus::Pending => "pending",
+            MajorChangeStatus::Approved => "approved",
+            MajorChangeStatus::Rejected => "rejected",
+        };
+        write!(f, "{}", status_str)
+    }
+}
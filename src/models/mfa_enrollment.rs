@@ -0,0 +1,26 @@
+use sqlx::{
+    FromRow,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// A user's TOTP authenticator. Enrollment is confirmed once the user has entered a code from it.
+#[derive(Debug, FromRow)]
+pub struct MfaEnrollment {
+    pub user_id: Uuid,
+    /// The base32 shared secret.
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    /// The time step of the last accepted code. Codes from this step or earlier are refused.
+    pub last_used_step: Option<i64>,
+    /// Wrong codes entered in a row, across all of the user's pending logins.
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+impl MfaEnrollment {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
@@ -1,9 +1,31 @@
+pub mod audit_entry;
+pub mod cart;
 pub mod course;
+pub mod course_equivalency;
 pub mod course_meeting_time;
 pub mod course_offering;
 pub mod course_prerequisite;
 pub mod department;
+pub mod email_verification;
+pub mod external_institution;
+pub mod faculty_profile;
+pub mod hold;
+pub mod impersonation;
+pub mod ledger_entry;
+pub mod login_attempt;
+pub mod major_change_request;
+pub mod mfa_enrollment;
+pub mod offering_listing;
+pub mod password_reset;
+pub mod program;
 pub mod registration;
+pub mod registration_override;
+pub mod seat_reservation;
+pub mod section_link;
+pub mod session;
 pub mod student_profile;
 pub mod term;
+pub mod term_billing;
+pub mod transfer_credit;
 pub mod user;
+pub mod user_purge;
@@ -0,0 +1,72 @@
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// An additional course number a cross-listed offering is visible under, e.g. MATH 340 for a COSC 340 offering.
+/// Registrations under every listing share the offering's capacity, and `seat_cap` optionally limits how many of those seats this listing may take.
+#[derive(Debug, FromRow)]
+pub struct OfferingListing {
+    pub offering_id: Uuid,
+    pub course_id: Uuid,
+    pub seat_cap: Option<i32>,
+}
+
+impl OfferingListing {
+    fn new(offering_id: Uuid, course_id: Uuid, seat_cap: Option<i32>) -> Result<Self, String> {
+        if seat_cap.is_some_and(|cap| cap <= 0) {
+            return Err("Seat cap must be greater than 0!".to_string());
+        }
+        Ok(OfferingListing {
+            offering_id,
+            course_id,
+            seat_cap,
+        })
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<OfferingListing, sqlx::Error> {
+        let listing = sqlx::query_as!(
+            OfferingListing,
+            r#"
+            INSERT INTO offering_listings (offering_id, course_id, seat_cap)
+            VALUES ($1, $2, $3)
+            RETURNING offering_id, course_id, seat_cap
+            "#,
+            self.offering_id,
+            self.course_id,
+            self.seat_cap
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(listing)
+    }
+
+    pub async fn create<'e>(
+        offering_id: Uuid,
+        course_id: Uuid,
+        seat_cap: Option<i32>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<OfferingListing, Error> {
+        let listing =
+            OfferingListing::new(offering_id, course_id, seat_cap).map_err(Error::Validation)?;
+        Ok(listing.insert(executor).await?)
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM offering_listings WHERE offering_id = $1 AND course_id = $2
+            "#,
+            self.offering_id,
+            self.course_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
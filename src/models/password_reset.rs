@@ -0,0 +1,51 @@
+use chrono::Duration;
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// How long a password reset token can be redeemed after it is issued.
+pub const RESET_TOKEN_LIFETIME: Duration = Duration::hours(1);
+
+/// A single-use token letting a user set a new password. The token itself is only sent to the user; the database stores its hash.
+#[derive(Debug, FromRow)]
+pub struct PasswordResetToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl PasswordResetToken {
+    pub async fn create<'e>(
+        user_id: Uuid,
+        token_hash: &str,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<PasswordResetToken, sqlx::Error> {
+        let now = Utc::now();
+        let token = sqlx::query_as!(
+            PasswordResetToken,
+            r#"
+            INSERT INTO password_reset_tokens (id, user_id, token_hash, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, created_at, expires_at, used_at
+            "#,
+            Uuid::new_v4(),
+            user_id,
+            token_hash,
+            now,
+            now + RESET_TOKEN_LIFETIME
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// A token can be redeemed once, before it expires.
+    pub fn is_redeemable(&self, now: DateTime<Utc>) -> bool {
+        self.used_at.is_none() && now < self.expires_at
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use sqlx::{FromRow, PgExecutor, types::chrono::NaiveDate};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// A major, minor, honours program or certificate offered by a department.
+#[derive(Debug, FromRow)]
+pub struct Program {
+    pub id: i32,
+    pub department_id: i32,
+    pub name: String,
+    pub kind: ProgramKind,
+}
+
+impl Program {
+    pub async fn create<'e>(
+        department_id: i32,
+        name: String,
+        kind: ProgramKind,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Program, Error> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::Validation("A program must have a name!".to_string()));
+        }
+        let program = sqlx::query_as!(
+            Program,
+            r#"
+            INSERT INTO programs (department_id, name, kind)
+            VALUES ($1, $2, $3)
+            RETURNING id, department_id, name, kind AS "kind: ProgramKind"
+            "#,
+            department_id,
+            name,
+            kind as ProgramKind
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(program)
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "program_kind", rename_all = "lowercase")]
+pub enum ProgramKind {
+    Major,
+    Minor,
+    Honours,
+    Certificate,
+}
+
+impl fmt::Display for ProgramKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_str = match self {
+            ProgramKind::Major => "major",
+            ProgramKind::Minor => "minor",
+            ProgramKind::Honours => "honours",
+            ProgramKind::Certificate => "certificate",
+        };
+        write!(f, "{}", kind_str)
+    }
+}
+
+/// A student's declaration of a program. Declarations are ended instead of deleted, so those with an `ended_on` are the student's past programs.
+#[derive(Debug, FromRow)]
+pub struct ProgramDeclaration {
+    pub id: i32,
+    pub student_id: Uuid,
+    pub program_id: i32,
+    pub declared_on: NaiveDate,
+    pub ended_on: Option<NaiveDate>,
+}
+
+impl ProgramDeclaration {
+    /// Declares a program for a student as of today.
+    pub async fn create<'e>(
+        student_id: Uuid,
+        program_id: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<ProgramDeclaration, sqlx::Error> {
+        let declaration = sqlx::query_as!(
+            ProgramDeclaration,
+            r#"
+            INSERT INTO program_declarations (student_id, program_id)
+            VALUES ($1, $2)
+            RETURNING id, student_id, program_id, declared_on, ended_on
+            "#,
+            student_id,
+            program_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(declaration)
+    }
+
+    /// Ends the student's current declaration of a program as of today. Returns `None` if the program is not currently declared.
+    pub async fn end<'e>(
+        student_id: Uuid,
+        program_id: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Option<ProgramDeclaration>, sqlx::Error> {
+        let declaration = sqlx::query_as!(
+            ProgramDeclaration,
+            r#"
+            UPDATE program_declarations
+            SET ended_on = CURRENT_DATE
+            WHERE student_id = $1 AND program_id = $2 AND ended_on IS NULL
+            RETURNING id, student_id, program_id, declared_on, ended_on
+            "#,
+            student_id,
+            program_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        Ok(declaration)
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.ended_on.is_none()
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use sqlx::{
-    PgPool,
+    PgExecutor,
     types::chrono::{DateTime, Utc},
 };
 use uuid::Uuid;
@@ -10,6 +10,8 @@ pub struct Registration {
     pub id: Uuid,
     pub student_id: Option<Uuid>,
     pub offering_id: Option<Uuid>,
+    /// The course number the student registered under, which differs from the offering's course for cross-listed offerings.
+    pub listed_course_id: Option<Uuid>,
     pub registered_at: Option<DateTime<Utc>>,
     pub status: RegistrationStatus,
     pub grade: Option<Grade>,
@@ -25,6 +27,7 @@ impl Registration {
             id: Uuid::new_v4(),
             student_id,
             offering_id,
+            listed_course_id: None,
             registered_at: None,
             status,
             grade: None,
@@ -32,19 +35,19 @@ impl Registration {
     }
 
     //pain in the ass
-    async fn insert(self, pool: &PgPool) -> Result<Registration, sqlx::Error> {
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<Registration, sqlx::Error> {
         let row = sqlx::query!(
             r#"
-            INSERT INTO registrations (id, student_id, offering_id, status)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, student_id, offering_id, registered_at, status, grade
+            INSERT INTO registrations (id, student_id, offering_id, status, listed_course_id)
+            VALUES ($1, $2, $3, $4, (SELECT course_id FROM course_offerings WHERE id = $3))
+            RETURNING id, student_id, offering_id, listed_course_id, registered_at, status AS "status: RegistrationStatus", grade AS "grade: Grade"
             "#,
             self.id,
             self.student_id,
             self.offering_id,
-            self.status.to_string()
+            self.status as RegistrationStatus
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         // Convert the raw row into our Registration, mapping the Option<String> grade into Option<Grade>. Because Rust doesn't want to implement From<Option<String>> for Option<Grade>
@@ -52,53 +55,47 @@ impl Registration {
             id: row.id,
             student_id: row.student_id,
             offering_id: row.offering_id,
+            listed_course_id: row.listed_course_id,
             registered_at: row.registered_at,
-            status: row.status.into(), // String -> RegistrationStatus
-            grade: row.grade.map(|g| g.into()), // Option<String> -> Option<Grade>
+            status: row.status,
+            grade: row.grade,
         };
         Ok(registration)
     }
 
-    pub async fn create(
+    pub async fn create<'e>(
         student_id: Option<Uuid>,
         offering_id: Option<Uuid>,
         status: RegistrationStatus,
-        pool: &PgPool,
+        executor: impl PgExecutor<'e>,
     ) -> Result<Registration, sqlx::Error> {
         Ok(Self::new(student_id, offering_id, status)
-            .insert(pool)
+            .insert(executor)
             .await?)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "registration_status", rename_all = "lowercase")]
 pub enum RegistrationStatus {
     Registered,
     Dropped,
     Waitlisted,
 }
 
-impl From<String> for RegistrationStatus {
-    fn from(value: String) -> Self {
-        match value.trim() {
-            "registered" => RegistrationStatus::Registered,
-            "dropped" => RegistrationStatus::Dropped,
-            "waitlisted" => RegistrationStatus::Waitlisted,
-            _ => panic!("Invalid registration status in database!"),
-        }
-    }
-}
-
 impl fmt::Display for RegistrationStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let reg_string = match self {
-            RegistrationStatus::Registered => "Registered",
-            RegistrationStatus::Dropped => "Dropped",
-            RegistrationStatus::Waitlisted => "Waitlisted",
+            RegistrationStatus::Registered => "registered",
+            RegistrationStatus::Dropped => "dropped",
+            RegistrationStatus::Waitlisted => "waitlisted",
         };
         write!(f, "{}", reg_string)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "grade")]
 pub enum Grade {
     A,
     B,
@@ -107,15 +104,15 @@ pub enum Grade {
     F,
 }
 
-impl From<String> for Grade {
-    fn from(value: String) -> Self {
-        match value.trim() {
-            "A" => Grade::A,
-            "B" => Grade::B,
-            "C" => Grade::C,
-            "D" => Grade::D,
-            "F" => Grade::F,
-            _ => panic!("Invalid grade found in database!"),
+impl Grade {
+    /// Grade points on a 4.0 scale.
+    pub fn points(&self) -> f64 {
+        match self {
+            Grade::A => 4.0,
+            Grade::B => 3.0,
+            Grade::C => 2.0,
+            Grade::D => 1.0,
+            Grade::F => 0.0,
         }
     }
 }
@@ -0,0 +1,187 @@
+use std::fmt;
+
+use rand::Rng;
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::security::tokens::hash_token;
+
+/// An instructor's exception to one registration rule for one offering. Each override can be used once.
+/// A direct override is tied to `student_id`; a permission number can be redeemed by any student who is given it.
+#[derive(Debug, FromRow)]
+pub struct RegistrationOverride {
+    pub id: Uuid,
+    pub offering_id: Uuid,
+    pub student_id: Option<Uuid>,
+    pub rule: OverrideRule,
+    /// The permission number, which is only known when it is issued. It is stored hashed, so it cannot be shown again.
+    pub permission_number: Option<String>,
+    pub issued_by: Uuid,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub used_by: Option<Uuid>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl RegistrationOverride {
+    fn new(
+        offering_id: Uuid,
+        student_id: Option<Uuid>,
+        rule: OverrideRule,
+        issued_by: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RegistrationOverride {
+        // Overrides that are not for a specific student must be redeemed with a permission number.
+        let permission_number = match student_id {
+            Some(_) => None,
+            None => Some(Self::generate_permission_number()),
+        };
+        RegistrationOverride {
+            id: Uuid::new_v4(),
+            offering_id,
+            student_id,
+            rule,
+            permission_number,
+            issued_by,
+            issued_at: Utc::now(),
+            expires_at,
+            used_by: None,
+            used_at: None,
+            revoked_at: None,
+        }
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<RegistrationOverride, sqlx::Error> {
+        let registration_override = sqlx::query_as!(
+            RegistrationOverride,
+            r#"
+            INSERT INTO registration_overrides (id, offering_id, student_id, rule, permission_number_hash, issued_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, offering_id, student_id, rule AS "rule: OverrideRule", NULL::TEXT AS "permission_number?", issued_by, issued_at, expires_at, used_by, used_at, revoked_at
+            "#,
+            self.id,
+            self.offering_id,
+            self.student_id,
+            self.rule.to_string(),
+            self.permission_number.as_deref().map(hash_token),
+            self.issued_by,
+            self.expires_at
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(RegistrationOverride {
+            permission_number: self.permission_number,
+            ..registration_override
+        })
+    }
+
+    /// Creates an override. Passing `None` for `student_id` creates a permission number instead of a direct override.
+    pub async fn create<'e>(
+        offering_id: Uuid,
+        student_id: Option<Uuid>,
+        rule: OverrideRule,
+        issued_by: Uuid,
+        expires_at: Option<DateTime<Utc>>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<RegistrationOverride, sqlx::Error> {
+        Self::new(offering_id, student_id, rule, issued_by, expires_at)
+            .insert(executor)
+            .await
+    }
+
+    /// An override can be used if it has not been used or revoked and has not expired.
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.used_at.is_none()
+            && self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+
+    fn generate_permission_number() -> String {
+        let mut rng = rand::rng();
+        rng.random_range(100_000_000..=999_999_999).to_string()
+    }
+}
+
+/// An entry in the append-only log of everything that happens to an override.
+#[derive(Debug, FromRow)]
+pub struct OverrideAuditEntry {
+    pub id: i64,
+    pub override_id: Uuid,
+    pub action: OverrideAction,
+    pub actor_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl OverrideAuditEntry {
+    pub async fn record<'e>(
+        override_id: Uuid,
+        action: OverrideAction,
+        actor_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<OverrideAuditEntry, sqlx::Error> {
+        let entry = sqlx::query_as!(
+            OverrideAuditEntry,
+            r#"
+            INSERT INTO override_audit_log (override_id, action, actor_id)
+            VALUES ($1, $2, $3)
+            RETURNING id, override_id, action AS "action: OverrideAction", actor_id, occurred_at
+            "#,
+            override_id,
+            action.to_string(),
+            actor_id
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(entry)
+    }
+}
+
+/// The registration rules an instructor can override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+pub enum OverrideRule {
+    Consent,
+    Prerequisite,
+    Capacity,
+    TimeConflict,
+}
+
+impl fmt::Display for OverrideRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule_str = match self {
+            OverrideRule::Consent => "consent",
+            OverrideRule::Prerequisite => "prerequisite",
+            OverrideRule::Capacity => "capacity",
+            OverrideRule::TimeConflict => "time_conflict",
+        };
+        write!(f, "{}", rule_str)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum OverrideAction {
+    Issued,
+    Consumed,
+    Revoked,
+}
+
+impl fmt::Display for OverrideAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action_str = match self {
+            OverrideAction::Issued => "issued",
+            OverrideAction::Consumed => "consumed",
+            OverrideAction::Revoked => "revoked",
+        };
+        write!(f, "{}", action_str)
+    }
+}
@@ -0,0 +1,135 @@
+use sqlx::{
+    PgExecutor, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// Seats in an offering held for students of a program and/or enrollment year until `release_at`.
+/// After the release time the reservation no longer applies and its unfilled seats are open to everyone.
+#[derive(Debug)]
+pub struct SeatReservation {
+    pub id: Option<i32>,
+    pub offering_id: Uuid,
+    pub seats: i32,
+    pub program_id: Option<i32>,
+    pub enrollment_year: Option<i32>,
+    pub release_at: DateTime<Utc>,
+}
+
+impl SeatReservation {
+    fn new(
+        offering_id: Uuid,
+        seats: i32,
+        program_id: Option<i32>,
+        enrollment_year: Option<i32>,
+        release_at: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        if seats <= 0 {
+            return Err("A reservation must hold at least one seat!".to_string());
+        }
+        if program_id.is_none() && enrollment_year.is_none() {
+            return Err("A reservation must be for a program or an enrollment year!".to_string());
+        }
+        Ok(SeatReservation {
+            id: None,
+            offering_id,
+            seats,
+            program_id,
+            enrollment_year,
+            release_at,
+        })
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<SeatReservation, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO seat_reservations (offering_id, seats, program_id, enrollment_year, release_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, offering_id, seats, program_id, enrollment_year, release_at
+            "#,
+            self.offering_id,
+            self.seats,
+            self.program_id,
+            self.enrollment_year,
+            self.release_at
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(SeatReservation {
+            id: Some(row.id),
+            offering_id: row.offering_id,
+            seats: row.seats,
+            program_id: row.program_id,
+            enrollment_year: row.enrollment_year,
+            release_at: row.release_at,
+        })
+    }
+
+    pub async fn create<'e>(
+        offering_id: Uuid,
+        seats: i32,
+        program_id: Option<i32>,
+        enrollment_year: Option<i32>,
+        release_at: DateTime<Utc>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<SeatReservation, Error> {
+        let reservation =
+            SeatReservation::new(offering_id, seats, program_id, enrollment_year, release_at)
+                .map_err(Error::Validation)?;
+        Ok(reservation.insert(executor).await?)
+    }
+
+    /// Returns every reservation on an offering, including released ones.
+    pub async fn for_offering(
+        offering_id: Uuid,
+        pool: &PgPool,
+    ) -> Result<Vec<SeatReservation>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, offering_id, seats, program_id, enrollment_year, release_at
+            FROM seat_reservations
+            WHERE offering_id = $1
+            ORDER BY release_at
+            "#,
+            offering_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SeatReservation {
+                id: Some(row.id),
+                offering_id: row.offering_id,
+                seats: row.seats,
+                program_id: row.program_id,
+                enrollment_year: row.enrollment_year,
+                release_at: row.release_at,
+            })
+            .collect())
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM seat_reservations WHERE id = $1
+            "#,
+            self.id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether the reservation still holds seats at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.release_at > now
+    }
+}
@@ -0,0 +1,96 @@
+use sqlx::{FromRow, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use super::course_offering::SectionType;
+
+/// A linking rule between sections: registering in `offering_id` requires registering in exactly one of the rule's linked offerings,
+/// which are all sections of type `section_type`. For example, lecture 001 requires one of labs L01-L04.
+#[derive(Debug, FromRow)]
+pub struct SectionLink {
+    pub id: Option<i32>,
+    pub offering_id: Uuid,
+    pub section_type: SectionType,
+}
+
+impl SectionLink {
+    fn new(offering_id: Uuid, section_type: SectionType) -> SectionLink {
+        SectionLink {
+            id: None,
+            offering_id,
+            section_type,
+        }
+    }
+
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<SectionLink, sqlx::Error> {
+        let link = sqlx::query_as!(
+            SectionLink,
+            r#"
+            INSERT INTO section_link_groups (offering_id, section_type)
+            VALUES ($1, $2)
+            RETURNING id, offering_id, section_type AS "section_type: SectionType"
+            "#,
+            self.offering_id,
+            self.section_type.to_string()
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(link)
+    }
+
+    pub async fn create<'e>(
+        offering_id: Uuid,
+        section_type: SectionType,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<SectionLink, sqlx::Error> {
+        Self::new(offering_id, section_type).insert(executor).await
+    }
+
+    /// Adds an offering to the sections that satisfy this rule.
+    pub async fn add_option<'e>(
+        &self,
+        linked_offering_id: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO section_link_options (group_id, linked_offering_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+            self.id,
+            linked_offering_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the IDs of the offerings that satisfy this rule.
+    pub async fn options(&self, pool: &PgPool) -> Result<Vec<Uuid>, sqlx::Error> {
+        let options = sqlx::query_scalar!(
+            r#"
+            SELECT linked_offering_id FROM section_link_options WHERE group_id = $1
+            "#,
+            self.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(options)
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM section_link_groups WHERE id = $1
+            "#,
+            self.id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
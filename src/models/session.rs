@@ -0,0 +1,65 @@
+use chrono::Duration;
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// How long a session lasts after sign-in, no matter how active it is.
+pub const SESSION_LIFETIME: Duration = Duration::hours(12);
+/// How long a session may go unused before it ends.
+pub const IDLE_TIMEOUT: Duration = Duration::minutes(30);
+/// How long a session pending its second factor has to complete it.
+pub const MFA_CHALLENGE_LIFETIME: Duration = Duration::minutes(10);
+
+/// A signed-in user. The token identifying the session is only given to the client; the database stores its hash.
+#[derive(Debug, Clone, FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Set while the user still has to complete the second login step. Pending sessions cannot be used to act.
+    pub mfa_pending: bool,
+}
+
+impl Session {
+    pub async fn create<'e>(
+        user_id: Uuid,
+        token_hash: &str,
+        mfa_pending: bool,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Session, sqlx::Error> {
+        let now = Utc::now();
+        let lifetime = if mfa_pending {
+            MFA_CHALLENGE_LIFETIME
+        } else {
+            SESSION_LIFETIME
+        };
+        let session = sqlx::query_as!(
+            Session,
+            r#"
+            INSERT INTO sessions (id, user_id, token_hash, created_at, expires_at, last_seen_at, mfa_pending)
+            VALUES ($1, $2, $3, $4, $5, $4, $6)
+            RETURNING id, user_id, created_at, expires_at, last_seen_at, revoked_at, mfa_pending
+            "#,
+            Uuid::new_v4(),
+            user_id,
+            token_hash,
+            now,
+            now + lifetime,
+            mfa_pending
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// A session is valid until it is revoked, reaches its expiry, or goes unused for longer than the idle timeout.
+    pub fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && now < self.expires_at && now < self.last_seen_at + IDLE_TIMEOUT
+    }
+}
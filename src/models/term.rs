@@ -1,8 +1,14 @@
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use chrono::Datelike;
-use sqlx::PgPool;
 use sqlx::types::chrono::NaiveDate;
+use sqlx::{
+    PgExecutor, Postgres,
+    error::BoxDynError,
+    postgres::{PgTypeInfo, PgValueRef},
+};
+
+use crate::error::Error;
 
 #[derive(sqlx::FromRow)]
 pub struct Term {
@@ -13,19 +19,19 @@ pub struct Term {
 }
 
 impl Term {
-    async fn insert(self, pool: &PgPool) -> Result<Term, sqlx::Error> {
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<Term, sqlx::Error> {
         let term = sqlx::query_as!(
             Term,
             r#"
             INSERT INTO terms (name, start_date, end_date)
             VALUES ($1, $2, $3)
-            RETURNING id, name, start_date, end_date
+            RETURNING id, name AS "name: TermName", start_date, end_date
             "#,
             self.name.to_string(),
             self.start_date,
             self.end_date
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(term)
@@ -45,14 +51,38 @@ impl Term {
     }
 
     /// Creates and inserts a new Term into the database. Using only `start_date` and `end_date` as parameters ensures we only ever have valid terms inserted into the database.
-    pub async fn create_term(
+    pub async fn create_term<'e>(
         start_date: NaiveDate,
         end_date: NaiveDate,
-        pool: &sqlx::PgPool,
-    ) -> Result<Term, sqlx::Error> {
+        executor: impl PgExecutor<'e>,
+    ) -> Result<Term, Error> {
         let term = Term::new(Term::generate_name(start_date), start_date, end_date)
-            .map_err(|err| sqlx::Error::Protocol(err))?;
-        term.insert(pool).await
+            .map_err(Error::Validation)?;
+        Ok(term.insert(executor).await?)
+    }
+
+    /// Sets the most credits a student may be registered for in a term.
+    pub async fn set_max_credits<'e>(
+        term_id: i32,
+        max_credits: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<(), Error> {
+        if max_credits <= 0 {
+            return Err(Error::Validation(
+                "Credit limit must be positive!".to_string(),
+            ));
+        }
+        sqlx::query!(
+            r#"
+            UPDATE terms SET max_credits = $2 WHERE id = $1
+            "#,
+            term_id,
+            max_credits
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
     }
 
     /// Generates a TermName for the term.
@@ -81,23 +111,35 @@ impl fmt::Display for TermName {
     }
 }
 
-impl From<String> for TermName {
-    fn from(value: String) -> Self {
-        let parts: Vec<&str> = value.trim().split_whitespace().collect();
+impl FromStr for TermName {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = value.split_whitespace().collect();
         if parts.len() != 2 {
-            panic!("Invalid term name format. Expected format: 'SEMESTER YEAR'");
+            return Err(format!(
+                "Invalid term name '{}'. Expected format: 'SEMESTER YEAR'",
+                value
+            ));
         }
 
-        let semester = match parts[0].to_lowercase().as_str() {
-            "summer" => Semester::Summer,
-            "winter" => Semester::Winter,
-            _ => panic!("Invalid semester value in term name"),
-        };
-
-        TermName {
-            semester,
+        Ok(TermName {
+            semester: parts[0].parse()?,
             year: parts[1].to_string(),
-        }
+        })
+    }
+}
+
+impl sqlx::Type<Postgres> for TermName {
+    fn type_info() -> PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for TermName {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let name = <&str as sqlx::Decode<Postgres>>::decode(value)?;
+        Ok(name.parse()?)
     }
 }
 
@@ -116,12 +158,14 @@ impl fmt::Display for Semester {
     }
 }
 
-impl From<String> for Semester {
-    fn from(value: String) -> Self {
-        match value.trim().to_lowercase().as_str() {
-            "summer" => Semester::Summer,
-            "winter" => Semester::Winter,
-            _ => panic!("Invalid semester value: {}", value),
+impl FromStr for Semester {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "summer" => Ok(Semester::Summer),
+            "winter" => Ok(Semester::Winter),
+            _ => Err(format!("Invalid semester '{}'", value)),
         }
     }
 }
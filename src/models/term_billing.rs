@@ -0,0 +1,151 @@
+use sqlx::{FromRow, PgExecutor, types::chrono::NaiveDate};
+
+use crate::error::Error;
+
+/// The per-credit tuition rate for a term, and the date by which the term's charges must be paid.
+#[derive(Debug, FromRow)]
+pub struct TermBillingRate {
+    pub term_id: i32,
+    pub per_credit_cents: i64,
+    pub payment_due: NaiveDate,
+}
+
+impl TermBillingRate {
+    /// Sets the billing rate for a term, replacing any existing rate. Charges that were already posted are not affected.
+    pub async fn set<'e>(
+        term_id: i32,
+        per_credit_cents: i64,
+        payment_due: NaiveDate,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<TermBillingRate, Error> {
+        if per_credit_cents < 0 {
+            return Err(Error::Validation(
+                "Tuition rate cannot be negative!".to_string(),
+            ));
+        }
+        let rate = sqlx::query_as!(
+            TermBillingRate,
+            r#"
+            INSERT INTO term_billing_rates (term_id, per_credit_cents, payment_due)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (term_id) DO UPDATE SET per_credit_cents = $2, payment_due = $3
+            RETURNING term_id, per_credit_cents, payment_due
+            "#,
+            term_id,
+            per_credit_cents,
+            payment_due
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(rate)
+    }
+}
+
+/// A flat fee charged once per term to every student registered in at least one offering.
+#[derive(Debug, FromRow)]
+pub struct TermFee {
+    pub id: Option<i32>,
+    pub term_id: i32,
+    pub name: String,
+    pub amount_cents: i64,
+}
+
+impl TermFee {
+    fn new(term_id: i32, name: String, amount_cents: i64) -> Result<Self, String> {
+        if amount_cents < 0 {
+            return Err("Fee amount cannot be negative!".to_string());
+        }
+        Ok(TermFee {
+            id: None,
+            term_id,
+            name,
+            amount_cents,
+        })
+    }
+
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<TermFee, sqlx::Error> {
+        let fee = sqlx::query_as!(
+            TermFee,
+            r#"
+            INSERT INTO term_fees (term_id, name, amount_cents)
+            VALUES ($1, $2, $3)
+            RETURNING id, term_id, name, amount_cents
+            "#,
+            self.term_id,
+            self.name,
+            self.amount_cents
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(fee)
+    }
+
+    pub async fn create<'e>(
+        term_id: i32,
+        name: String,
+        amount_cents: i64,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<TermFee, Error> {
+        let fee = TermFee::new(term_id, name, amount_cents).map_err(Error::Validation)?;
+        Ok(fee.insert(executor).await?)
+    }
+}
+
+/// One step of a term's refund schedule: a drop on or before `deadline` refunds `refund_percent` of what was charged.
+/// When several rules apply, the one with the earliest deadline wins.
+#[derive(Debug, FromRow)]
+pub struct RefundRule {
+    pub id: Option<i32>,
+    pub term_id: i32,
+    pub deadline: NaiveDate,
+    pub refund_percent: i32,
+}
+
+impl RefundRule {
+    fn new(term_id: i32, deadline: NaiveDate, refund_percent: i32) -> Result<Self, String> {
+        if !(0..=100).contains(&refund_percent) {
+            return Err("Refund percent must be between 0 and 100!".to_string());
+        }
+        Ok(RefundRule {
+            id: None,
+            term_id,
+            deadline,
+            refund_percent,
+        })
+    }
+
+    async fn insert<'e>(self, executor: impl PgExecutor<'e>) -> Result<RefundRule, sqlx::Error> {
+        let rule = sqlx::query_as!(
+            RefundRule,
+            r#"
+            INSERT INTO refund_rules (term_id, deadline, refund_percent)
+            VALUES ($1, $2, $3)
+            RETURNING id, term_id, deadline, refund_percent
+            "#,
+            self.term_id,
+            self.deadline,
+            self.refund_percent
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(rule)
+    }
+
+    pub async fn create<'e>(
+        term_id: i32,
+        deadline: NaiveDate,
+        refund_percent: i32,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<RefundRule, Error> {
+        let rule = RefundRule::new(term_id, deadline, refund_percent).map_err(Error::Validation)?;
+        Ok(rule.insert(executor).await?)
+    }
+
+    /// Applies the refund percentage to an amount, rounding down to the nearest cent.
+    pub fn refund_of(&self, amount_cents: i64) -> i64 {
+        amount_cents * self.refund_percent as i64 / 100
+    }
+}
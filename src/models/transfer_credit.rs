@@ -0,0 +1,82 @@
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+/// Credit on a student's record for a course taken at another institution. The credit value and what it counts as come from the equivalency.
+#[derive(Debug, FromRow)]
+pub struct TransferCredit {
+    pub id: Uuid,
+    pub student_id: Uuid,
+    pub equivalency_id: i32,
+    pub external_grade: Option<String>,
+    pub term_taken: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl TransferCredit {
+    fn new(
+        student_id: Uuid,
+        equivalency_id: i32,
+        external_grade: Option<String>,
+        term_taken: Option<String>,
+    ) -> TransferCredit {
+        TransferCredit {
+            id: Uuid::new_v4(),
+            student_id,
+            equivalency_id,
+            external_grade,
+            term_taken,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    async fn insert<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<TransferCredit, sqlx::Error> {
+        let transfer_credit = sqlx::query_as!(
+            TransferCredit,
+            r#"
+            INSERT INTO transfer_credits (id, student_id, equivalency_id, external_grade, term_taken)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, student_id, equivalency_id, external_grade, term_taken, recorded_at
+            "#,
+            self.id,
+            self.student_id,
+            self.equivalency_id,
+            self.external_grade,
+            self.term_taken
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(transfer_credit)
+    }
+
+    pub async fn create<'e>(
+        student_id: Uuid,
+        equivalency_id: i32,
+        external_grade: Option<String>,
+        term_taken: Option<String>,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<TransferCredit, sqlx::Error> {
+        Self::new(student_id, equivalency_id, external_grade, term_taken)
+            .insert(executor)
+            .await
+    }
+
+    pub async fn delete<'e>(&self, executor: impl PgExecutor<'e>) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM transfer_credits WHERE id = $1
+            "#,
+            self.id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,13 @@
 use std::fmt::Display;
 
 use sqlx::{
-    FromRow,
+    FromRow, PgExecutor,
     types::chrono::{DateTime, Utc},
 };
 use uuid::Uuid;
 
 use super::course::Course;
+use crate::error::Error;
 
 #[derive(Debug, FromRow)]
 pub struct User {
@@ -17,6 +18,12 @@ pub struct User {
     pub last_name: String,
     pub role: Role,
     pub created_at: Option<DateTime<Utc>>,
+    /// `None` while the account is pending email verification.
+    pub email_verified_at: Option<DateTime<Utc>>,
+    /// `None` while the account is active.
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// The admin who deactivated the account.
+    pub deactivated_by: Option<Uuid>,
 }
 
 impl User {
@@ -31,55 +38,57 @@ impl User {
             last_name,
             role,
             created_at: None,
+            email_verified_at: None,
+            deactivated_at: None,
+            deactivated_by: None,
         }
     }
 
-    pub async fn create_user(
+    pub async fn create_user<'e>(
         email: String,
         hashed_password: String,
         name: FullName,
         role: Role,
-        pool: &sqlx::PgPool,
-    ) -> Result<User, sqlx::Error> {
+        executor: impl PgExecutor<'e>,
+    ) -> Result<User, Error> {
+        let email = normalize_email(&email);
+        validate_email(&email).map_err(Error::Validation)?;
         let user = Self::new(Uuid::new_v4(), email, hashed_password, name, role);
-        user.insert(pool).await?;
+        user.insert(executor).await?;
         Ok(user)
     }
 
-    async fn insert(&self, pool: &sqlx::PgPool) -> Result<User, sqlx::Error> {
+    /// New accounts are pending until their email is verified.
+    pub fn is_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    /// Deactivated accounts keep their records but cannot sign in.
+    pub fn is_active(&self) -> bool {
+        self.deactivated_at.is_none()
+    }
+
+    async fn insert<'e>(&self, executor: impl PgExecutor<'e>) -> Result<User, sqlx::Error> {
         let record = sqlx::query_as!(
             User,
             r#"
             INSERT INTO users (id, email, hashed_password, first_name, last_name, role)
             VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, email, hashed_password, first_name, last_name, role, created_at
+            RETURNING id, email, hashed_password, first_name, last_name, role AS "role: Role", created_at, email_verified_at, deactivated_at, deactivated_by
             "#,
             self.id,
             self.email,
             self.hashed_password,
             self.first_name,
             self.last_name,
-            self.role.to_string()
+            self.role as Role
         )
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(record)
     }
 
-    pub async fn delete(&self, pool: &sqlx::PgPool) -> Result<(), sqlx::Error> {
-        sqlx::query_as!(
-            self,
-            r#"
-            DELETE FROM users WHERE id=$1
-            "#,
-            self.id
-        )
-        .execute(pool)
-        .await?;
-        Ok(())
-    }
-
     /// Given a user, get all the courses the are registered in. Note that this means actually registered, not waitlisted or dropped.
     pub async fn get_registered_courses(
         &self,
@@ -94,7 +103,8 @@ impl User {
             c.course_number, 
             c.title, 
             c.description, 
-            c.credits
+            c.credits,
+            c.requires_consent
             FROM registrations r
             JOIN course_offerings co ON r.offering_id = co.id
             JOIN courses c ON co.course_id = c.id
@@ -120,7 +130,8 @@ impl User {
             c.course_number, 
             c.title, 
             c.description, 
-            c.credits
+            c.credits,
+            c.requires_consent
             FROM registrations r
             JOIN course_offerings co ON r.offering_id = co.id
             JOIN courses c ON co.course_id = c.id
@@ -133,32 +144,55 @@ impl User {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "user_role", rename_all = "lowercase")]
 pub enum Role {
     Student,
+    Instructor,
     Admin,
 }
 
-impl From<String> for Role {
-    fn from(value: String) -> Self {
-        match value.trim() {
-            "student" => Role::Student,
-            "admin" => Role::Admin,
-            _ => panic!("Nonexistant role found in DB!"),
-        }
-    }
-}
-
 impl Display for Role {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let role_str = match self {
             Role::Student => "student",
+            Role::Instructor => "instructor",
             Role::Admin => "admin",
         };
         write!(f, "{}", role_str)
     }
 }
 
+/// Trims and lowercases an email so addresses differing only by case or surrounding whitespace are treated as the same.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Checks that a normalized email is plausibly deliverable: one `@`, a non-empty local part, and a domain with at least two labels.
+pub fn validate_email(email: &str) -> Result<(), String> {
+    let invalid = || Err(format!("\"{}\" is not a valid email address.", email));
+    let Some((local, domain)) = email.split_once('@') else {
+        return invalid();
+    };
+    if local.is_empty() || local.len() > 64 || email.len() > 254 {
+        return invalid();
+    }
+    if email.chars().any(|c| c.is_whitespace() || c.is_control()) || domain.contains('@') {
+        return invalid();
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid_label = |label: &&str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    };
+    if labels.len() < 2 || !labels.iter().all(valid_label) {
+        return invalid();
+    }
+    Ok(())
+}
+
 pub struct FullName {
     pub first: String,
     pub last: String,
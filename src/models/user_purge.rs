@@ -0,0 +1,70 @@
+use sqlx::{
+    FromRow, PgExecutor,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use super::user::{Role, User};
+use crate::error::Error;
+
+/// A record of an account that was permanently deleted. It outlives the account, so it keeps the account's email and role.
+#[derive(Debug, FromRow)]
+pub struct UserPurge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: Role,
+    pub reason: String,
+    /// `None` once the admin who purged the account has been purged as well.
+    pub purged_by: Option<Uuid>,
+    pub purged_at: DateTime<Utc>,
+}
+
+impl UserPurge {
+    pub async fn record<'e>(
+        user: &User,
+        reason: &str,
+        purged_by: Uuid,
+        executor: impl PgExecutor<'e>,
+    ) -> Result<UserPurge, Error> {
+        if reason.trim().is_empty() {
+            return Err(Error::Validation(
+                "A reason is required to purge an account.".to_string(),
+            ));
+        }
+        let purge = sqlx::query_as!(
+            UserPurge,
+            r#"
+            INSERT INTO user_purges (id, user_id, email, role, reason, purged_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, email, role AS "role: Role", reason, purged_by, purged_at
+            "#,
+            Uuid::new_v4(),
+            user.id,
+            user.email,
+            user.role as Role,
+            reason.trim(),
+            purged_by
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(purge)
+    }
+
+    /// Returns every recorded purge, newest first.
+    pub async fn all<'e>(executor: impl PgExecutor<'e>) -> Result<Vec<UserPurge>, sqlx::Error> {
+        let purges = sqlx::query_as!(
+            UserPurge,
+            r#"
+            SELECT id, user_id, email, role AS "role: Role", reason, purged_by, purged_at
+            FROM user_purges
+            ORDER BY purged_at DESC
+            "#
+        )
+        .fetch_all(executor)
+        .await?;
+
+        Ok(purges)
+    }
+}
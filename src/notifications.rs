@@ -0,0 +1,109 @@
+use std::{fmt, fs::OpenOptions, io::Write, path::PathBuf};
+
+use sqlx::types::chrono::{DateTime, Utc};
+
+/// A message to deliver to a user outside the app.
+#[derive(Debug, Clone)]
+pub enum Notification {
+    PasswordReset {
+        email: String,
+        token: String,
+        expires_at: DateTime<Utc>,
+    },
+    EmailVerification {
+        email: String,
+        token: String,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+impl fmt::Display for Notification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Notification::PasswordReset {
+                email,
+                token,
+                expires_at,
+            } => write!(
+                f,
+                "To: {}\nUse this token to reset your password before {}: {}",
+                email,
+                expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                token
+            ),
+            Notification::EmailVerification {
+                email,
+                token,
+                expires_at,
+            } => write!(
+                f,
+                "To: {}\nUse this token to verify your email before {}: {}",
+                email,
+                expires_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                token
+            ),
+        }
+    }
+}
+
+pub type NotifyError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Delivers notifications, e.g. by email. Implementations must not keep the contents of password reset notifications.
+pub trait Notifier: Send + Sync {
+    fn send(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+/// Appends notifications to a local file instead of delivering them. For development and testing.
+pub struct FileNotifier {
+    pub path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> FileNotifier {
+        FileNotifier { path: path.into() }
+    }
+
+    /// A notifier writing to `NOTIFICATION_LOG`, or `notifications.log` in the working directory if it is not set.
+    pub fn from_env() -> FileNotifier {
+        FileNotifier::new(
+            std::env::var("NOTIFICATION_LOG").unwrap_or_else(|_| "notifications.log".to_string()),
+        )
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "[{}] {}\n", Utc::now().to_rfc3339(), notification)?;
+        Ok(())
+    }
+}
+
+/// Keeps sent notifications in memory so tests can read the tokens they contain.
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingNotifier {
+    pub sent: std::sync::Mutex<Vec<Notification>>,
+}
+
+#[cfg(test)]
+impl RecordingNotifier {
+    /// The token from the most recently sent notification.
+    pub fn last_token(&self) -> Option<String> {
+        match self.sent.lock().unwrap().last()? {
+            Notification::PasswordReset { token, .. }
+            | Notification::EmailVerification { token, .. } => Some(token.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Notifier for RecordingNotifier {
+    fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        self.sent.lock().unwrap().push(notification.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::LazyLock;
+
+use rand::{Rng, distr::Alphanumeric};
+
+use crate::security::password::{self, PasswordError};
+
+/// A hash of a random password, verified against when there is no usable stored hash so that every login costs one Argon2 verification.
+static DUMMY_HASH: LazyLock<String> = LazyLock::new(|| {
+    let plaintext: String = rand::rng()
+        .sample_iter(Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    // The current parameters are always valid, so hashing with them cannot fail.
+    password::hash_password(&plaintext).expect("Hashing with validated parameters should not fail")
+});
+
+/// Checks a password against an account's stored hash, or against a dummy hash when no account matched, so that unknown accounts
+/// take as long to refuse as wrong passwords. A malformed stored hash is also verified against the dummy before its error is returned.
+pub fn verify_credentials(
+    plaintext: &str,
+    stored_hash: Option<&str>,
+) -> Result<bool, PasswordError> {
+    let Some(stored_hash) = stored_hash else {
+        password::validate_password(plaintext, &DUMMY_HASH)?;
+        return Ok(false);
+    };
+    match password::validate_password(plaintext, stored_hash) {
+        Err(PasswordError::MalformedHash(err)) => {
+            password::validate_password(plaintext, &DUMMY_HASH)?;
+            Err(PasswordError::MalformedHash(err))
+        }
+        result => result,
+    }
+}
+
+/// Warms up the dummy hash so the first login to an unknown account is not slower than later ones.
+pub fn init() {
+    LazyLock::force(&DUMMY_HASH);
+}
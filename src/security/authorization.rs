@@ -0,0 +1,227 @@
+//! Who is acting, and what they are allowed to do.
+//!
+//! Services are the authorization boundary: every service function that changes data takes the `Actor` performing it and
+//! checks their permissions before doing anything. Model methods are persistence primitives for services (and tests) to build on.
+
+use std::fmt;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{impersonation::Impersonation, user::Role};
+
+/// Something a user may be allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ManageUsers,
+    ManageDepartments,
+    /// Create programs and decide students' major change requests. Can be scoped to a department.
+    ManagePrograms,
+    /// Create, change and delete courses. Can be scoped to a department.
+    ManageCourses,
+    /// Create and configure offerings and their sections. Can be scoped to a department.
+    ManageOfferings,
+    /// Register and drop students other than oneself.
+    ManageRegistrations,
+    ManageHolds,
+    ManageBilling,
+    /// Record external institutions, course equivalencies and students' transfer credit.
+    ManageTransferCredit,
+    /// Issue registration overrides. Instructors may only issue them for offerings they teach.
+    IssueOverrides,
+    /// Register in and drop offerings for oneself.
+    RegisterSelf,
+    /// Act as a student through `impersonation_service` to see what they see.
+    Impersonate,
+    ViewAuditLog,
+}
+
+impl Permission {
+    /// Whether an admin scoped to a department holds this permission within that department.
+    fn is_department_scoped(&self) -> bool {
+        matches!(
+            self,
+            Permission::ManagePrograms
+                | Permission::ManageCourses
+                | Permission::ManageOfferings
+                | Permission::IssueOverrides
+        )
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let permission_str = match self {
+            Permission::ManageUsers => "manage users",
+            Permission::ManageDepartments => "manage departments",
+            Permission::ManagePrograms => "manage programs",
+            Permission::ManageCourses => "manage courses",
+            Permission::ManageOfferings => "manage offerings",
+            Permission::ManageRegistrations => "manage registrations",
+            Permission::ManageHolds => "manage holds",
+            Permission::ManageBilling => "manage billing",
+            Permission::ManageTransferCredit => "manage transfer credit",
+            Permission::IssueOverrides => "issue overrides",
+            Permission::RegisterSelf => "register in courses",
+            Permission::Impersonate => "impersonate students",
+            Permission::ViewAuditLog => "view the audit log",
+        };
+        write!(f, "{}", permission_str)
+    }
+}
+
+impl Role {
+    /// The permissions every user with this role holds.
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Student => &[Permission::RegisterSelf],
+            Role::Instructor => &[Permission::IssueOverrides],
+            Role::Admin => &[
+                Permission::ManageUsers,
+                Permission::ManageDepartments,
+                Permission::ManagePrograms,
+                Permission::ManageCourses,
+                Permission::ManageOfferings,
+                Permission::ManageRegistrations,
+                Permission::ManageHolds,
+                Permission::ManageBilling,
+                Permission::ManageTransferCredit,
+                Permission::IssueOverrides,
+                Permission::Impersonate,
+                Permission::ViewAuditLog,
+            ],
+        }
+    }
+}
+
+/// An authenticated user performing an operation.
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub user_id: Uuid,
+    pub role: Role,
+    /// The department an admin is limited to, or `None` for admins of the whole institution.
+    pub department_id: Option<i32>,
+    /// Set when an admin is acting as this user. The actor then only holds the user's permissions, and none at all if the
+    /// impersonation is read-only.
+    pub impersonation: Option<Impersonation>,
+}
+
+impl Actor {
+    /// Loads the actor for a user. Returns `None` if the user does not exist or has been deactivated.
+    pub async fn load(user_id: Uuid, pool: &PgPool) -> Result<Option<Actor>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            SELECT u.id, u.role AS "role: Role", s.department_id AS "department_id?"
+            FROM users u
+            LEFT JOIN admin_scopes s ON s.user_id = u.id
+            WHERE u.id = $1 AND u.deactivated_at IS NULL
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(|row| Actor {
+            user_id: row.id,
+            role: row.role,
+            department_id: row.department_id,
+            impersonation: None,
+        }))
+    }
+
+    fn holds(&self, permission: Permission) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+        self.role.permissions().contains(&permission)
+    }
+
+    /// Whether the actor is an admin impersonating this user without permission to change anything.
+    pub fn is_read_only(&self) -> bool {
+        self.impersonation
+            .as_ref()
+            .is_some_and(|impersonation| impersonation.read_only)
+    }
+
+    /// Requires a permission across the whole institution. Admins scoped to a department do not pass.
+    pub fn require(&self, permission: Permission) -> Result<(), Forbidden> {
+        if self.holds(permission) && self.department_id.is_none() {
+            return Ok(());
+        }
+        Err(self.forbidden(permission, None))
+    }
+
+    /// Requires a permission within a department. Admins of that department pass, as well as institution-wide admins.
+    pub fn require_in_department(
+        &self,
+        permission: Permission,
+        department_id: i32,
+    ) -> Result<(), Forbidden> {
+        let in_scope = match self.department_id {
+            None => true,
+            Some(scope) => scope == department_id && permission.is_department_scoped(),
+        };
+        if self.holds(permission) && in_scope {
+            return Ok(());
+        }
+        Err(self.forbidden(permission, Some(department_id)))
+    }
+
+    /// Requires that the actor is `student_id` registering for themselves, or may manage other students' registrations.
+    pub fn require_for_student(&self, student_id: Uuid) -> Result<(), Forbidden> {
+        if self.user_id == student_id && self.holds(Permission::RegisterSelf) {
+            return Ok(());
+        }
+        self.require(Permission::ManageRegistrations)
+    }
+
+    /// Like `require_for_student`, but for looking at a student's registrations without changing them, which read-only impersonations of the
+    /// student may do too.
+    pub fn require_view_student(&self, student_id: Uuid) -> Result<(), Forbidden> {
+        if self.user_id == student_id && self.role.permissions().contains(&Permission::RegisterSelf)
+        {
+            return Ok(());
+        }
+        self.require(Permission::ManageRegistrations)
+    }
+
+    /// Requires that the actor is `user_id` changing their own account, or may manage users. Impersonations never pass as the user here, so
+    /// an admin cannot change a student's sign-in or security settings by acting as them.
+    pub fn require_for_account(&self, user_id: Uuid) -> Result<(), Forbidden> {
+        if self.user_id == user_id && self.impersonation.is_none() {
+            return Ok(());
+        }
+        self.require(Permission::ManageUsers)
+    }
+
+    pub fn forbidden(&self, permission: Permission, department_id: Option<i32>) -> Forbidden {
+        Forbidden {
+            user_id: self.user_id,
+            permission,
+            department_id,
+        }
+    }
+}
+
+/// Returned when an actor attempts an operation they do not have permission for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forbidden {
+    pub user_id: Uuid,
+    pub permission: Permission,
+    pub department_id: Option<i32>,
+}
+
+impl fmt::Display for Forbidden {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.department_id {
+            Some(department_id) => write!(
+                f,
+                "You do not have permission to {} in department {}.",
+                self.permission, department_id
+            ),
+            None => write!(f, "You do not have permission to {}.", self.permission),
+        }
+    }
+}
+
+impl std::error::Error for Forbidden {}
@@ -1 +1,7 @@
+pub mod authentication;
+pub mod authorization;
 pub mod password;
+pub mod password_policy;
+pub mod throttling;
+pub mod tokens;
+pub mod totp;
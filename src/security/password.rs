@@ -1,27 +1,194 @@
+use std::{
+    fmt,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
 use argon2::{
-    Argon2, PasswordHash, PasswordVerifier,
+    Algorithm, Argon2, Params, PasswordHash, PasswordVerifier, Version,
     password_hash::{PasswordHasher, SaltString, rand_core::OsRng},
 };
 
+/// The parameters new hashes are made with, read from the environment once. Invalid settings fall back to the defaults here, but `main`
+/// refuses to start with them, so this only happens where `main` does not run.
+static CURRENT_PARAMS: LazyLock<PasswordParams> =
+    LazyLock::new(|| PasswordParams::from_env().unwrap_or_default());
+
+/// Argon2id cost parameters. Raising them makes each hash slower to compute, for us and for anyone guessing passwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordParams {
+    /// Memory used per hash, in KiB.
+    pub memory_kib: u32,
+    /// Number of passes over the memory.
+    pub iterations: u32,
+    /// Number of lanes computed in parallel.
+    pub parallelism: u32,
+}
+
+impl Default for PasswordParams {
+    /// The argon2 crate's defaults, which follow the OWASP minimum recommendation.
+    fn default() -> Self {
+        PasswordParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordParams {
+    /// The default parameters, each overridden by `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` or `ARGON2_PARALLELISM` if it is set.
+    /// Fails if a setting is not a whole number or the parameters are out of the range Argon2 accepts.
+    pub fn from_env() -> Result<PasswordParams, InvalidPasswordParams> {
+        let var = |name: &str, default: u32| match std::env::var(name) {
+            Ok(value) => value.trim().parse().map_err(|_| {
+                InvalidPasswordParams(format!("{} must be a whole number, not '{}'", name, value))
+            }),
+            Err(_) => Ok(default),
+        };
+        let defaults = PasswordParams::default();
+        let params = PasswordParams {
+            memory_kib: var("ARGON2_MEMORY_KIB", defaults.memory_kib)?,
+            iterations: var("ARGON2_ITERATIONS", defaults.iterations)?,
+            parallelism: var("ARGON2_PARALLELISM", defaults.parallelism)?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Checks that Argon2 accepts the parameters, e.g. that there is at least 8 KiB of memory per lane.
+    pub fn validate(&self) -> Result<(), InvalidPasswordParams> {
+        self.argon2().map(|_| ()).map_err(|err| {
+            InvalidPasswordParams(format!(
+                "ARGON2_MEMORY_KIB={}, ARGON2_ITERATIONS={}, ARGON2_PARALLELISM={}: {}",
+                self.memory_kib, self.iterations, self.parallelism, err
+            ))
+        })
+    }
+
+    /// The parameters new hashes are made with.
+    pub fn current() -> PasswordParams {
+        *CURRENT_PARAMS
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>, argon2::password_hash::Error> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+/// Returned by `PasswordParams::from_env` when the environment sets Argon2 parameters that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidPasswordParams(String);
+
+impl fmt::Display for InvalidPasswordParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid Argon2 parameters: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPasswordParams {}
+
+/// Hashes a password with the current parameters.
 pub fn hash_password(plaintext: &str) -> Result<String, argon2::password_hash::Error> {
+    hash_password_with(plaintext, &PasswordParams::current())
+}
+
+pub fn hash_password_with(
+    plaintext: &str,
+    params: &PasswordParams,
+) -> Result<String, argon2::password_hash::Error> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    let hashed = argon2
+    let hashed = params
+        .argon2()?
         .hash_password(plaintext.as_bytes(), &salt)?
         .to_string();
     Ok(hashed)
 }
 
+/// Whether a hash was made with anything other than Argon2id under `params`, and should be replaced the next time the password is known.
+pub fn needs_rehash(hash: &str, params: &PasswordParams) -> Result<bool, PasswordError> {
+    let parsed = PasswordHash::new(hash).map_err(PasswordError::MalformedHash)?;
+    let hash_params = Params::try_from(&parsed).map_err(PasswordError::MalformedHash)?;
+    Ok(parsed.algorithm != Algorithm::Argon2id.ident()
+        || parsed.version != Some(Version::V0x13.into())
+        || hash_params.m_cost() != params.memory_kib
+        || hash_params.t_cost() != params.iterations
+        || hash_params.p_cost() != params.parallelism)
+}
+
+/// The result of `suggest_params`.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamsSuggestion {
+    pub params: PasswordParams,
+    /// How long one hash took with `params` on this machine.
+    pub hash_time: Duration,
+}
+
+/// Benchmarks hashing on this machine and suggests the most expensive parameters that still hash within `target`. Memory is doubled from
+/// the default up to `max_memory_kib`, then iterations are added, following the OWASP advice to prefer memory over iterations.
+/// Never suggests less than the defaults, even if they are slower than `target`.
+pub fn suggest_params(target: Duration, max_memory_kib: u32, parallelism: u32) -> ParamsSuggestion {
+    let time = |params: &PasswordParams| {
+        let start = Instant::now();
+        hash_password_with("benchmark password", params)
+            .expect("Benchmark parameters should be valid");
+        start.elapsed()
+    };
+
+    let mut best = PasswordParams {
+        parallelism,
+        ..PasswordParams::default()
+    };
+    let mut best_time = time(&best);
+    let mut candidate = best;
+    loop {
+        if candidate.memory_kib.saturating_mul(2) <= max_memory_kib {
+            candidate.memory_kib *= 2;
+        } else {
+            candidate.iterations += 1;
+        }
+        let candidate_time = time(&candidate);
+        if candidate_time > target {
+            break;
+        }
+        best = candidate;
+        best_time = candidate_time;
+    }
+
+    ParamsSuggestion {
+        params: best,
+        hash_time: best_time,
+    }
+}
+
 /// Hashes a provided plaintext password and compares it to a known hash. Returns `true` or `false` based on correct/incorrect passwords.
-pub fn validate_password(
-    plaintext: &str,
-    hash: &str,
-) -> Result<bool, argon2::password_hash::Error> {
-    let parsed = PasswordHash::new(hash).expect("Failed to hash password.");
+pub fn validate_password(plaintext: &str, hash: &str) -> Result<bool, PasswordError> {
+    let parsed = PasswordHash::new(hash).map_err(PasswordError::MalformedHash)?;
     let argon2 = Argon2::default();
     match argon2.verify_password(plaintext.as_bytes(), &parsed) {
         Ok(_) => Ok(true),
         Err(argon2::password_hash::Error::Password) => Ok(false),
-        Err(e) => Err(e),
+        Err(e) => Err(PasswordError::Argon2(e)),
+    }
+}
+
+/// Errors from checking a password against a stored hash.
+#[derive(Debug)]
+pub enum PasswordError {
+    /// The stored hash could not be parsed, e.g. because it was not produced by `hash_password`.
+    MalformedHash(argon2::password_hash::Error),
+    /// Argon2 failed while verifying the password.
+    Argon2(argon2::password_hash::Error),
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::MalformedHash(err) => write!(f, "Malformed password hash: {}", err),
+            PasswordError::Argon2(err) => write!(f, "Password verification failed: {}", err),
+        }
     }
 }
+
+impl std::error::Error for PasswordError {}
@@ -0,0 +1,132 @@
+use std::{collections::HashSet, fmt, sync::LazyLock};
+
+use crate::models::user::FullName;
+
+/// Commonly used and breached passwords, one per line, bundled so checks work offline.
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("common_passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+});
+
+/// Parts of a name or email shorter than this are too common to reject passwords for containing them.
+const MIN_PERSONAL_PART_LENGTH: usize = 3;
+
+/// The rules a new password must follow.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Rejects passwords on the bundled list of common and breached passwords.
+    pub reject_common: bool,
+    /// Rejects passwords containing the user's first name, last name or the local part of their email.
+    pub reject_personal_info: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 10,
+            max_length: 128,
+            reject_common: true,
+            reject_personal_info: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// The default policy, with the minimum length overridden by `PASSWORD_MIN_LENGTH` if it is set.
+    pub fn from_env() -> PasswordPolicy {
+        let mut policy = PasswordPolicy::default();
+        if let Some(min_length) = std::env::var("PASSWORD_MIN_LENGTH")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            policy.min_length = min_length;
+        }
+        policy
+    }
+
+    /// Checks a password for a user with the given name and email, returning every rule it breaks.
+    pub fn check(&self, plaintext: &str, name: &FullName, email: &str) -> Result<(), WeakPassword> {
+        let mut violations = Vec::new();
+        let length = plaintext.chars().count();
+        if length < self.min_length {
+            violations.push(PasswordViolation::TooShort {
+                min_length: self.min_length,
+            });
+        }
+        if length > self.max_length {
+            violations.push(PasswordViolation::TooLong {
+                max_length: self.max_length,
+            });
+        }
+
+        let lowered = plaintext.to_lowercase();
+        if self.reject_common && COMMON_PASSWORDS.contains(lowered.as_str()) {
+            violations.push(PasswordViolation::Common);
+        }
+        if self.reject_personal_info {
+            let contains = |part: &str| {
+                let part = part.trim().to_lowercase();
+                part.chars().count() >= MIN_PERSONAL_PART_LENGTH && lowered.contains(&part)
+            };
+            if contains(&name.first) || contains(&name.last) {
+                violations.push(PasswordViolation::ContainsName);
+            }
+            let local_part = email.split('@').next().unwrap_or_default();
+            if contains(local_part) {
+                violations.push(PasswordViolation::ContainsEmail);
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(WeakPassword { violations })
+        }
+    }
+}
+
+/// A rule a password breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasswordViolation {
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    Common,
+    ContainsName,
+    ContainsEmail,
+}
+
+impl fmt::Display for PasswordViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordViolation::TooShort { min_length } => {
+                write!(f, "Password must be at least {} characters.", min_length)
+            }
+            PasswordViolation::TooLong { max_length } => {
+                write!(f, "Password must be at most {} characters.", max_length)
+            }
+            PasswordViolation::Common => write!(f, "Password is too common."),
+            PasswordViolation::ContainsName => write!(f, "Password must not contain your name."),
+            PasswordViolation::ContainsEmail => write!(f, "Password must not contain your email."),
+        }
+    }
+}
+
+/// Returned when a new password does not follow the password policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeakPassword {
+    pub violations: Vec<PasswordViolation>,
+}
+
+impl fmt::Display for WeakPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "{}", messages.join(" "))
+    }
+}
+
+impl std::error::Error for WeakPassword {}
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use chrono::Duration;
+use sqlx::types::chrono::{DateTime, Utc};
+
+/// Consecutive failures on an account before each further attempt must wait out a backoff.
+pub const BACKOFF_THRESHOLD: i32 = 3;
+/// Consecutive failures on an account before it is locked.
+pub const LOCKOUT_THRESHOLD: i32 = 5;
+/// How long an account stays locked unless an administrator unlocks it.
+pub const LOCKOUT_DURATION: Duration = Duration::minutes(15);
+/// Failed attempts allowed from one source within `SOURCE_WINDOW`, across all accounts.
+pub const SOURCE_FAILURE_LIMIT: i64 = 20;
+pub const SOURCE_WINDOW: Duration = Duration::minutes(15);
+
+/// The wait required after the last failure before an account may try again. The wait starts at one second once
+/// `BACKOFF_THRESHOLD` is reached and doubles with each further failure.
+pub fn backoff(failed_count: i32) -> Option<Duration> {
+    if failed_count < BACKOFF_THRESHOLD {
+        return None;
+    }
+    Some(Duration::seconds(
+        1 << (failed_count - BACKOFF_THRESHOLD).min(16),
+    ))
+}
+
+/// Returned when a login attempt is refused because of too many recent failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throttled {
+    /// The earliest time another attempt will be considered.
+    pub retry_after: DateTime<Utc>,
+}
+
+impl fmt::Display for Throttled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Too many failed login attempts. Try again after {}.",
+            self.retry_after.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+impl std::error::Error for Throttled {}
@@ -0,0 +1,13 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// Generates a random bearer token, such as a session or password reset token.
+pub fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::rng().random();
+    hex::encode(bytes)
+}
+
+/// Hashes a token for storage. Tokens are random, so a fast hash is enough to make a leaked table useless.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,52 @@
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+use crate::{
+    error::Error,
+    models::audit_entry::{AuditEntry, AuditFilter},
+    security::authorization::{Actor, Permission},
+};
+
+/// The most entries a single search of the audit log returns.
+pub const MAX_SEARCH_RESULTS: i64 = 500;
+
+/// Starts a transaction whose changes are attributed to `actor` in the audit log. Services that change data on behalf of an actor make
+/// their changes through one; changes made any other way, such as self-registration and the bookkeeping of signing in, are logged
+/// as made by the system.
+pub async fn begin(
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    attribute(actor, &mut tx).await?;
+    Ok(tx)
+}
+
+/// Attributes the rest of the current transaction's changes to `actor`, for callers that already have a transaction open.
+pub async fn attribute(actor: &Actor, conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    let impersonator_id = actor
+        .impersonation
+        .as_ref()
+        .map(|impersonation| impersonation.admin_id.to_string());
+    sqlx::query!(
+        r#"
+        SELECT set_config('audit.actor_id', $1, true) AS actor_id, set_config('audit.impersonator_id', $2, true) AS impersonator_id
+        "#,
+        actor.user_id.to_string(),
+        impersonator_id.unwrap_or_default()
+    )
+    .fetch_one(conn)
+    .await?;
+    Ok(())
+}
+
+/// Searches the audit log, newest first. At most `MAX_SEARCH_RESULTS` entries are returned; page through more by setting the filter's
+/// `before_id` to the last entry's id.
+pub async fn search(
+    filter: &AuditFilter,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<AuditEntry>, Error> {
+    actor.require(Permission::ViewAuditLog)?;
+    let entries = AuditEntry::search(filter, MAX_SEARCH_RESULTS, pool).await?;
+    Ok(entries)
+}
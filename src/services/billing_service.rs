@@ -0,0 +1,512 @@
+use sqlx::{PgConnection, PgPool, types::chrono::NaiveDate};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        hold::{Hold, HoldType},
+        ledger_entry::{EntryReference, EntryType, LedgerAccount, LedgerEntry, format_cents},
+        term_billing::{RefundRule, TermBillingRate, TermFee},
+    },
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
+
+/// A student's account activity for a single term.
+#[derive(Debug)]
+pub struct Statement {
+    pub student_id: Uuid,
+    pub term_id: i32,
+    pub payment_due: Option<NaiveDate>,
+    pub entries: Vec<LedgerEntry>,
+    /// Total of tuition and fee charges.
+    pub charges_cents: i64,
+    /// Total of refunds and payments.
+    pub credits_cents: i64,
+    pub balance_cents: i64,
+}
+
+/// Charges tuition for a registration, and the term's flat fees if they have not yet been charged to the student.
+/// Tuition is owed once per course and term, so a lab or tutorial linked to an already charged lecture is not charged again. Only the
+/// difference from what is already owed is charged, so re-registering after a drop does not double bill.
+/// Terms without a billing rate are not charged.
+pub async fn charge_registration(
+    conn: &mut PgConnection,
+    registration_id: Uuid,
+) -> Result<Vec<LedgerEntry>, Error> {
+    let registration = sqlx::query!(
+        r#"
+        SELECT r.student_id AS "student_id!", co.term_id, c.id AS course_id, c.course_number, c.credits, br.per_credit_cents AS "per_credit_cents?"
+        FROM registrations r
+        JOIN course_offerings co ON r.offering_id = co.id
+        JOIN courses c ON co.course_id = c.id
+        LEFT JOIN term_billing_rates br ON br.term_id = co.term_id
+        WHERE r.id = $1
+        "#,
+        registration_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let Some(per_credit_cents) = registration.per_credit_cents else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries = Vec::new();
+    let tuition = registration.credits as i64 * per_credit_cents;
+    let owing = tuition
+        - net_charged_for_course(
+            conn,
+            registration.student_id,
+            registration.course_id,
+            registration.term_id,
+        )
+        .await?;
+    if owing > 0 {
+        entries.push(
+            LedgerEntry::post(
+                registration.student_id,
+                registration.term_id,
+                EntryType::Tuition,
+                owing,
+                format!(
+                    "Tuition: {} ({} credits)",
+                    registration.course_number, registration.credits
+                ),
+                EntryReference::Registration(registration_id),
+                &mut *conn,
+            )
+            .await?,
+        );
+    }
+
+    let fees = sqlx::query!(
+        r#"
+        SELECT id, name, amount_cents FROM term_fees WHERE term_id = $1
+        "#,
+        registration.term_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for fee in fees {
+        let owing =
+            fee.amount_cents - net_charged_fee(conn, registration.student_id, fee.id).await?;
+        if owing > 0 {
+            entries.push(
+                LedgerEntry::post(
+                    registration.student_id,
+                    registration.term_id,
+                    EntryType::Fee,
+                    owing,
+                    format!("Fee: {}", fee.name),
+                    EntryReference::TermFee(fee.id),
+                    &mut *conn,
+                )
+                .await?,
+            );
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Credits a refund for a dropped registration according to the term's refund schedule on `dropped_on`.
+/// Tuition is only refunded once the student has no registrations left in the course, since it is charged once per course.
+/// If the student has no registrations left in the term, the term's fees are refunded on the same schedule.
+pub async fn refund_registration(
+    conn: &mut PgConnection,
+    registration_id: Uuid,
+    dropped_on: NaiveDate,
+) -> Result<Vec<LedgerEntry>, Error> {
+    let registration = sqlx::query!(
+        r#"
+        SELECT r.student_id AS "student_id!", co.term_id, c.id AS course_id, c.course_number
+        FROM registrations r
+        JOIN course_offerings co ON r.offering_id = co.id
+        JOIN courses c ON co.course_id = c.id
+        WHERE r.id = $1
+        "#,
+        registration_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let Some(rule) = get_refund_rule(conn, registration.term_id, dropped_on).await? else {
+        return Ok(Vec::new());
+    };
+
+    let still_in_course = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM registrations r
+            JOIN course_offerings co ON r.offering_id = co.id
+            WHERE r.student_id = $1 AND co.course_id = $2 AND co.term_id = $3 AND r.status = 'registered'
+        ) AS "exists!"
+        "#,
+        registration.student_id,
+        registration.course_id,
+        registration.term_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let mut entries = Vec::new();
+    let refund = if still_in_course {
+        0
+    } else {
+        rule.refund_of(
+            net_charged_for_course(
+                conn,
+                registration.student_id,
+                registration.course_id,
+                registration.term_id,
+            )
+            .await?,
+        )
+    };
+    if refund > 0 {
+        entries.push(
+            LedgerEntry::post(
+                registration.student_id,
+                registration.term_id,
+                EntryType::Refund,
+                refund,
+                format!(
+                    "Refund ({}%): {}",
+                    rule.refund_percent, registration.course_number
+                ),
+                EntryReference::Registration(registration_id),
+                &mut *conn,
+            )
+            .await?,
+        );
+    }
+
+    let still_registered = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM registrations r
+            JOIN course_offerings co ON r.offering_id = co.id
+            WHERE r.student_id = $1 AND co.term_id = $2 AND r.status = 'registered'
+        ) AS "exists!"
+        "#,
+        registration.student_id,
+        registration.term_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !still_registered {
+        let fees = sqlx::query!(
+            r#"
+            SELECT id, name FROM term_fees WHERE term_id = $1
+            "#,
+            registration.term_id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for fee in fees {
+            let refund =
+                rule.refund_of(net_charged_fee(conn, registration.student_id, fee.id).await?);
+            if refund > 0 {
+                entries.push(
+                    LedgerEntry::post(
+                        registration.student_id,
+                        registration.term_id,
+                        EntryType::Refund,
+                        refund,
+                        format!("Refund ({}%): {}", rule.refund_percent, fee.name),
+                        EntryReference::TermFee(fee.id),
+                        &mut *conn,
+                    )
+                    .await?,
+                );
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Records a payment made by a student towards a term.
+pub async fn record_payment(
+    student_id: Uuid,
+    term_id: i32,
+    amount_cents: i64,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<LedgerEntry, Error> {
+    actor.require(Permission::ManageBilling)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let entry = LedgerEntry::post(
+        student_id,
+        term_id,
+        EntryType::Payment,
+        amount_cents,
+        "Payment received".to_string(),
+        EntryReference::None,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(entry)
+}
+
+/// Sets a term's per-credit tuition rate and payment due date.
+pub async fn set_billing_rate(
+    term_id: i32,
+    per_credit_cents: i64,
+    payment_due: NaiveDate,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<TermBillingRate, Error> {
+    actor.require(Permission::ManageBilling)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let rate = TermBillingRate::set(term_id, per_credit_cents, payment_due, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(rate)
+}
+
+/// Adds a flat fee to a term.
+pub async fn add_term_fee(
+    term_id: i32,
+    name: String,
+    amount_cents: i64,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<TermFee, Error> {
+    actor.require(Permission::ManageBilling)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let fee = TermFee::create(term_id, name, amount_cents, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(fee)
+}
+
+/// Adds a refund deadline to a term's refund schedule.
+pub async fn add_refund_rule(
+    term_id: i32,
+    deadline: NaiveDate,
+    refund_percent: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<RefundRule, Error> {
+    actor.require(Permission::ManageBilling)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let rule = RefundRule::create(term_id, deadline, refund_percent, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(rule)
+}
+
+/// Returns what a student currently owes across all terms. A negative balance means the student is in credit.
+pub async fn get_balance(student_id: Uuid, pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let balance = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(
+            CASE
+                WHEN debit_account = 'receivable' THEN amount_cents
+                WHEN credit_account = 'receivable' THEN -amount_cents
+                ELSE 0
+            END
+        ), 0)::BIGINT AS "balance!"
+        FROM ledger_entries
+        WHERE student_id = $1
+        "#,
+        student_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(balance)
+}
+
+/// Builds a student's statement for a term.
+pub async fn get_statement(
+    student_id: Uuid,
+    term_id: i32,
+    pool: &PgPool,
+) -> Result<Statement, sqlx::Error> {
+    let entries = sqlx::query_as!(
+        LedgerEntry,
+        r#"
+        SELECT id, student_id, term_id, registration_id, term_fee_id, entry_type AS "entry_type: EntryType", debit_account AS "debit_account: LedgerAccount", credit_account AS "credit_account: LedgerAccount", amount_cents, description, posted_at
+        FROM ledger_entries
+        WHERE student_id = $1 AND term_id = $2
+        ORDER BY posted_at
+        "#,
+        student_id,
+        term_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let payment_due = sqlx::query_scalar!(
+        r#"
+        SELECT payment_due FROM term_billing_rates WHERE term_id = $1
+        "#,
+        term_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let charges_cents = entries
+        .iter()
+        .map(LedgerEntry::balance_effect)
+        .filter(|amount| *amount > 0)
+        .sum();
+    let credits_cents = -entries
+        .iter()
+        .map(LedgerEntry::balance_effect)
+        .filter(|amount| *amount < 0)
+        .sum::<i64>();
+
+    Ok(Statement {
+        student_id,
+        term_id,
+        payment_due,
+        entries,
+        charges_cents,
+        credits_cents,
+        balance_cents: charges_cents - credits_cents,
+    })
+}
+
+/// Places a financial hold on every student with an unpaid balance for a term whose payment due date is before `as_of`.
+/// Students who already have an active financial hold are skipped. Returns the holds that were placed.
+/// The holds are recorded as placed by the system rather than by `actor`.
+pub async fn place_overdue_holds(
+    as_of: NaiveDate,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<Hold>, Error> {
+    actor.require(Permission::ManageBilling)?;
+    let overdue = sqlx::query!(
+        r#"
+        SELECT le.student_id, SUM(
+            CASE
+                WHEN le.debit_account = 'receivable' THEN le.amount_cents
+                WHEN le.credit_account = 'receivable' THEN -le.amount_cents
+                ELSE 0
+            END
+        )::BIGINT AS "balance!"
+        FROM ledger_entries le
+        JOIN term_billing_rates br ON br.term_id = le.term_id
+        WHERE br.payment_due < $1
+        AND NOT EXISTS (
+            SELECT 1 FROM student_holds h
+            WHERE h.student_id = le.student_id
+            AND h.hold_type = 'financial'
+            AND h.released_at IS NULL
+            AND (h.expires_at IS NULL OR h.expires_at > now())
+        )
+        GROUP BY le.student_id
+        HAVING SUM(
+            CASE
+                WHEN le.debit_account = 'receivable' THEN le.amount_cents
+                WHEN le.credit_account = 'receivable' THEN -le.amount_cents
+                ELSE 0
+            END
+        ) > 0
+        "#,
+        as_of
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let mut holds = Vec::with_capacity(overdue.len());
+    for student in overdue {
+        holds.push(
+            Hold::create(
+                student.student_id,
+                HoldType::Financial,
+                format!(
+                    "Unpaid balance of {} is past due",
+                    format_cents(student.balance)
+                ),
+                None,
+                None,
+                &mut *tx,
+            )
+            .await?,
+        );
+    }
+    tx.commit().await?;
+    Ok(holds)
+}
+
+/// The earliest refund rule whose deadline has not yet passed on `dropped_on`.
+async fn get_refund_rule(
+    conn: &mut PgConnection,
+    term_id: i32,
+    dropped_on: NaiveDate,
+) -> Result<Option<RefundRule>, sqlx::Error> {
+    let rule = sqlx::query_as!(
+        RefundRule,
+        r#"
+        SELECT id, term_id, deadline, refund_percent
+        FROM refund_rules
+        WHERE term_id = $1 AND deadline >= $2
+        ORDER BY deadline
+        LIMIT 1
+        "#,
+        term_id,
+        dropped_on
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(rule)
+}
+
+/// Net tuition currently charged to a student for a course in a term, across all of their registrations in its offerings:
+/// tuition charged less tuition refunded.
+async fn net_charged_for_course(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    course_id: Uuid,
+    term_id: i32,
+) -> Result<i64, sqlx::Error> {
+    let net = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(
+            CASE WHEN le.entry_type = 'refund' THEN -le.amount_cents ELSE le.amount_cents END
+        ), 0)::BIGINT AS "net!"
+        FROM ledger_entries le
+        JOIN registrations r ON r.id = le.registration_id
+        JOIN course_offerings co ON co.id = r.offering_id
+        WHERE le.student_id = $1 AND co.course_id = $2 AND co.term_id = $3 AND le.entry_type IN ('tuition', 'refund')
+        "#,
+        student_id,
+        course_id,
+        term_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(net)
+}
+
+/// Net amount currently charged to a student for a term fee: fee charged less fee refunded.
+async fn net_charged_fee(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    term_fee_id: i32,
+) -> Result<i64, sqlx::Error> {
+    let net = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(
+            CASE WHEN entry_type = 'refund' THEN -amount_cents ELSE amount_cents END
+        ), 0)::BIGINT AS "net!"
+        FROM ledger_entries
+        WHERE student_id = $1 AND term_fee_id = $2 AND entry_type IN ('fee', 'refund')
+        "#,
+        student_id,
+        term_fee_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(net)
+}
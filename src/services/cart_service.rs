@@ -0,0 +1,261 @@
+use sqlx::{Connection, PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::cart::{Cart, CartItem},
+    security::authorization::Actor,
+    services::{audit_service, impersonation_service, registration_service},
+};
+
+/// The outcome of one offering in a cart. `error` is the reason the offering was refused, or `None` if it can be (or was) registered.
+#[derive(Debug)]
+pub struct CartItemResult {
+    pub offering_id: Uuid,
+    pub course_number: String,
+    pub section_number: String,
+    pub error: Option<String>,
+}
+
+impl CartItemResult {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Returns the offerings in a student's cart for a term. Looking at a cart never creates one, so read-only impersonations change nothing.
+pub async fn get_cart_items(
+    student_id: Uuid,
+    term_id: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<CartItem>, Error> {
+    let action = format!("View cart for term {}", term_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_view_student(student_id)?;
+        let items = match Cart::find(student_id, term_id, pool).await? {
+            Some(cart) => cart.items(pool).await?,
+            None => Vec::new(),
+        };
+        impersonation_service::record_action(actor, &action, pool).await?;
+        Ok(items)
+    })
+    .await
+}
+
+/// Adds an offering to a student's cart for a term.
+pub async fn add_to_cart(
+    student_id: Uuid,
+    term_id: i32,
+    offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let action = format!("Add offering {} to cart for term {}", offering_id, term_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let cart = Cart::get_or_create(student_id, term_id, &mut *tx).await?;
+        cart.add_item(offering_id, &mut *tx).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    })
+    .await
+}
+
+/// Removes an offering from a student's cart for a term. Returns `false` if it was not in the cart.
+pub async fn remove_from_cart(
+    student_id: Uuid,
+    term_id: i32,
+    offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<bool, Error> {
+    let action = format!(
+        "Remove offering {} from cart for term {}",
+        offering_id, term_id
+    );
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let cart = Cart::get_or_create(student_id, term_id, &mut *tx).await?;
+        let removed = cart.remove_item(offering_id, &mut *tx).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(removed)
+    })
+    .await
+}
+
+/// Checks every offering in a student's cart for a term without registering anything.
+/// Items are checked in the order they were added, each against the ones before it, so conflicts within the cart and the credit limit are reported
+/// exactly as `submit_cart` would encounter them. Seats are as of the time of validation and may be gone by submission.
+pub async fn validate_cart(
+    student_id: Uuid,
+    term_id: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<CartItemResult>, Error> {
+    let action = format!("Validate cart for term {}", term_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_view_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let cart = Cart::get_or_create(student_id, term_id, &mut *tx).await?;
+        let results = register_cart(&mut tx, &cart, actor.user_id).await?;
+        tx.rollback().await?;
+        impersonation_service::record_action(actor, &action, pool).await?;
+        Ok(results)
+    })
+    .await
+}
+
+/// Registers the student in every offering in their cart for a term that passes registration checks, and reports each item's outcome.
+/// Linked sections in the cart, such as a lecture and its lab, succeed or fail together. Registered offerings are removed from the cart;
+/// refused ones stay so the student can fix them and submit again.
+pub async fn submit_cart(
+    student_id: Uuid,
+    term_id: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<CartItemResult>, Error> {
+    let action = format!("Submit cart for term {}", term_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let cart = Cart::get_or_create(student_id, term_id, &mut *tx).await?;
+        let results = register_cart(&mut tx, &cart, actor.user_id).await?;
+
+        let registered: Vec<Uuid> = results
+            .iter()
+            .filter(|result| result.is_ok())
+            .map(|result| result.offering_id)
+            .collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM cart_items WHERE cart_id = $1 AND offering_id = ANY($2)
+            "#,
+            cart.id,
+            &registered
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(results)
+    })
+    .await
+}
+
+/// Attempts to register each unit of linked sections in the cart inside its own savepoint, rolling back only the units that fail.
+/// `actor_id` is the user registering the student.
+async fn register_cart(
+    conn: &mut PgConnection,
+    cart: &Cart,
+    actor_id: Uuid,
+) -> Result<Vec<CartItemResult>, Error> {
+    let items = sqlx::query!(
+        r#"
+        SELECT ci.offering_id, c.course_number, co.section_number
+        FROM cart_items ci
+        JOIN course_offerings co ON co.id = ci.offering_id
+        JOIN courses c ON c.id = co.course_id
+        WHERE ci.cart_id = $1
+        ORDER BY ci.added_at, ci.offering_id
+        "#,
+        cart.id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let offering_ids: Vec<Uuid> = items.iter().map(|item| item.offering_id).collect();
+
+    let mut outcomes: Vec<Option<Option<String>>> = vec![None; items.len()];
+    for unit in group_linked_sections(conn, &offering_ids).await? {
+        let unit_ids: Vec<Uuid> = unit.iter().map(|index| offering_ids[*index]).collect();
+        let mut savepoint = conn.begin().await?;
+        let error = match registration_service::enroll_linked(
+            &mut savepoint,
+            cart.student_id,
+            &unit_ids,
+            actor_id,
+        )
+        .await
+        {
+            Ok(_) => {
+                savepoint.commit().await?;
+                None
+            }
+            Err(Error::Validation(reason) | Error::NotFound(reason) | Error::Conflict(reason)) => {
+                savepoint.rollback().await?;
+                Some(reason)
+            }
+            Err(e) => return Err(e),
+        };
+        for index in unit {
+            outcomes[index] = Some(error.clone());
+        }
+    }
+
+    let results = items
+        .into_iter()
+        .zip(outcomes)
+        .map(|(item, outcome)| CartItemResult {
+            offering_id: item.offering_id,
+            course_number: item.course_number,
+            section_number: item.section_number,
+            error: outcome.flatten(),
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Splits cart items (by index) into units that must be registered together: each section with the cart items chosen for its linked components.
+/// Items that are not linked to anything else in the cart form their own unit.
+async fn group_linked_sections(
+    conn: &mut PgConnection,
+    offering_ids: &[Uuid],
+) -> Result<Vec<Vec<usize>>, sqlx::Error> {
+    let links = sqlx::query!(
+        r#"
+        SELECT g.offering_id, o.linked_offering_id
+        FROM section_link_groups g
+        JOIN section_link_options o ON o.group_id = g.id
+        WHERE g.offering_id = ANY($1) AND o.linked_offering_id = ANY($1)
+        "#,
+        offering_ids
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let position = |id: Uuid| {
+        offering_ids
+            .iter()
+            .position(|offering_id| *offering_id == id)
+    };
+    let mut claimed = vec![false; offering_ids.len()];
+    let mut units = Vec::new();
+    for (index, offering_id) in offering_ids.iter().enumerate() {
+        if claimed[index] || !links.iter().any(|link| link.offering_id == *offering_id) {
+            continue;
+        }
+        claimed[index] = true;
+        let mut unit = vec![index];
+        for link in links.iter().filter(|link| link.offering_id == *offering_id) {
+            if let Some(component) = position(link.linked_offering_id)
+                && !claimed[component]
+            {
+                claimed[component] = true;
+                unit.push(component);
+            }
+        }
+        units.push(unit);
+    }
+    for (index, is_claimed) in claimed.iter().enumerate() {
+        if !is_claimed {
+            units.push(vec![index]);
+        }
+    }
+    units.sort_by_key(|unit| unit[0]);
+    Ok(units)
+}
@@ -1,6 +1,23 @@
+use sqlx::{
+    PgExecutor, PgPool,
+    types::chrono::{DateTime, NaiveTime, Utc},
+};
 use uuid::Uuid;
 
-use crate::models::{course::Course, course_meeting_time::CourseMeetingTime};
+use crate::{
+    error::Error,
+    models::{
+        course::{Course, create_course as new_course},
+        course_meeting_time::{CourseMeetingTime, Weekday},
+        course_offering::{CourseOffering, Section, SectionType},
+        course_prerequisite::CoursePrerequisite,
+        offering_listing::OfferingListing,
+        seat_reservation::SeatReservation,
+        section_link::SectionLink,
+    },
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
 
 pub async fn get_course_by_id(
     id: Uuid,
@@ -9,7 +26,7 @@ pub async fn get_course_by_id(
     let course = sqlx::query_as!(
         Course,
         r#"
-        SELECT id, department_id, course_number, title, description, credits FROM courses WHERE id=$1
+        SELECT id, department_id, course_number, title, description, credits, requires_consent FROM courses WHERE id=$1
         "#,
         id
     ).fetch_optional(pool).await?;
@@ -23,10 +40,303 @@ pub async fn get_meeting_times_by_id(
     Ok(sqlx::query_as!(
         CourseMeetingTime,
         r#"
-        SELECT * FROM course_meeting_times WHERE offering_id=$1
+        SELECT id, offering_id, day_of_week AS "day_of_week: Weekday", start_time, end_time
+        FROM course_meeting_times WHERE offering_id=$1
         "#,
         course_id
     )
     .fetch_all(pool)
     .await?)
 }
+
+/// Returns every offering visible under a course, including offerings of other courses that are cross-listed under it.
+pub async fn get_offerings_for_course(
+    course_id: Uuid,
+    pool: &sqlx::PgPool,
+) -> Result<Vec<CourseOffering>, sqlx::Error> {
+    let offerings = sqlx::query_as!(
+        CourseOffering,
+        r#"
+        SELECT co.id, co.course_id, co.term_id, co.instructor_id, co.capacity, co.location, co.section_type AS "section_type: SectionType", co.section_number
+        FROM course_offerings co
+        JOIN offering_courses oc ON oc.offering_id = co.id
+        WHERE oc.course_id = $1
+        "#,
+        course_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(offerings)
+}
+
+/// Links sections of a course so that registering in `offering_id` requires exactly one of `linked_offering_ids`.
+/// Every linked offering must be a `section_type` section in the same term as `offering_id`.
+pub async fn link_sections(
+    offering_id: Uuid,
+    section_type: SectionType,
+    linked_offering_ids: &[Uuid],
+    actor: &Actor,
+    pool: &sqlx::PgPool,
+) -> Result<SectionLink, Error> {
+    let department_id = get_offering_department(offering_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, department_id)?;
+    if linked_offering_ids.is_empty() {
+        return Err(Error::Validation(
+            "A section link needs at least one linked section.".to_string(),
+        ));
+    }
+
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let mismatched = sqlx::query_scalar!(
+        r#"
+        SELECT linked.section_number
+        FROM course_offerings linked
+        JOIN course_offerings co ON co.id = $1
+        WHERE linked.id = ANY($2)
+        AND (linked.section_type <> $3 OR linked.term_id <> co.term_id OR linked.id = co.id)
+        "#,
+        offering_id,
+        linked_offering_ids,
+        section_type.to_string()
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    if !mismatched.is_empty() {
+        return Err(Error::Validation(format!(
+            "Sections {} are not {} sections of the same term.",
+            mismatched.join(", "),
+            section_type
+        )));
+    }
+
+    let link = SectionLink::create(offering_id, section_type, &mut *tx).await?;
+    for linked_offering_id in linked_offering_ids {
+        link.add_option(*linked_offering_id, &mut *tx).await?;
+    }
+    tx.commit().await?;
+    Ok(link)
+}
+
+/// Creates a course in a department.
+pub async fn create_course(
+    department_id: i32,
+    course_number: String,
+    title: String,
+    description: Option<String>,
+    credits: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Course, Error> {
+    actor.require_in_department(Permission::ManageCourses, department_id)?;
+    let course = new_course(
+        Uuid::new_v4(),
+        department_id,
+        course_number,
+        title,
+        description,
+        credits,
+    );
+    let mut tx = audit_service::begin(actor, pool).await?;
+    course.insert(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(course)
+}
+
+pub async fn delete_course(course: &Course, actor: &Actor, pool: &PgPool) -> Result<(), Error> {
+    actor.require_in_department(Permission::ManageCourses, course.department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    course.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Sets whether registering in a course requires instructor consent.
+pub async fn set_requires_consent(
+    course: &mut Course,
+    requires_consent: bool,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require_in_department(Permission::ManageCourses, course.department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    course
+        .set_requires_consent(requires_consent, &mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Requires credit for `prerequisite_id` before registering in `course`. The prerequisite may belong to any department.
+pub async fn add_prerequisite(
+    course: &Course,
+    prerequisite_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require_in_department(Permission::ManageCourses, course.department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    course
+        .add_prerequisite(
+            &mut *tx,
+            &CoursePrerequisite::new(course.id, prerequisite_id),
+        )
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn remove_prerequisite(
+    course: &Course,
+    prerequisite_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require_in_department(Permission::ManageCourses, course.department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    course
+        .remove_prerequisite(
+            &mut *tx,
+            &CoursePrerequisite::new(course.id, prerequisite_id),
+        )
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Creates an offering of a course in a term, taught by `instructor_id`.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_offering(
+    course_id: Uuid,
+    term_id: i32,
+    instructor_id: Uuid,
+    section: Section,
+    capacity: i32,
+    location: String,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<CourseOffering, Error> {
+    let department_id = get_course_department(course_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let offering = CourseOffering::create(
+        course_id,
+        term_id,
+        instructor_id,
+        section,
+        capacity,
+        location,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(offering)
+}
+
+/// Cross-lists an offering under another course. The actor must be able to manage offerings of both departments.
+pub async fn cross_list_offering(
+    offering: &CourseOffering,
+    course_id: Uuid,
+    seat_cap: Option<i32>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<OfferingListing, Error> {
+    let own_department_id = get_course_department(offering.course_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, own_department_id)?;
+    let listed_department_id = get_course_department(course_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, listed_department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let listing = offering.cross_list(course_id, seat_cap, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(listing)
+}
+
+/// Adds a weekly meeting time to an offering.
+pub async fn add_meeting_time(
+    offering_id: Uuid,
+    day_of_week: Weekday,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<CourseMeetingTime, Error> {
+    let department_id = get_offering_department(offering_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let meeting_time =
+        CourseMeetingTime::create(offering_id, day_of_week, start_time, end_time, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(meeting_time)
+}
+
+pub async fn remove_meeting_time(
+    meeting_time: &CourseMeetingTime,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let department_id = get_offering_department(meeting_time.offering_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    meeting_time.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Holds seats in an offering for students of a program and/or enrollment year until `release_at`.
+pub async fn reserve_seats(
+    offering_id: Uuid,
+    seats: i32,
+    program_id: Option<i32>,
+    enrollment_year: Option<i32>,
+    release_at: DateTime<Utc>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<SeatReservation, Error> {
+    let department_id = get_offering_department(offering_id, pool).await?;
+    actor.require_in_department(Permission::ManageOfferings, department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let reservation = SeatReservation::create(
+        offering_id,
+        seats,
+        program_id,
+        enrollment_year,
+        release_at,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(reservation)
+}
+
+async fn get_course_department<'e>(
+    course_id: Uuid,
+    executor: impl PgExecutor<'e>,
+) -> Result<i32, Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT department_id FROM courses WHERE id = $1
+        "#,
+        course_id
+    )
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| Error::NotFound("Course does not exist.".to_string()))
+}
+
+async fn get_offering_department<'e>(
+    offering_id: Uuid,
+    executor: impl PgExecutor<'e>,
+) -> Result<i32, Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT c.department_id
+        FROM course_offerings co
+        JOIN courses c ON co.course_id = c.id
+        WHERE co.id = $1
+        "#,
+        offering_id
+    )
+    .fetch_optional(executor)
+    .await?
+    .ok_or_else(|| Error::NotFound("Course offering does not exist.".to_string()))
+}
@@ -1,4 +1,11 @@
-use crate::models::department::Department;
+use sqlx::PgPool;
+
+use crate::{
+    error::Error,
+    models::department::{Department, create_department as new_department},
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
 
 pub async fn get_department_by_code(
     code: &str,
@@ -16,3 +23,28 @@ pub async fn get_department_by_code(
 
     Ok(department)
 }
+
+pub async fn create_department(
+    code: String,
+    name: String,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Department, Error> {
+    actor.require(Permission::ManageDepartments)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let department = new_department(code, name, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(department)
+}
+
+pub async fn delete_department(
+    department: &Department,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require(Permission::ManageDepartments)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    department.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use sqlx::{PgPool, types::chrono::Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{email_verification::EmailVerificationToken, user::normalize_email},
+    notifications::{Notification, Notifier},
+    security::tokens::{generate_token, hash_token},
+};
+
+/// Issues an email verification token for a pending account and sends it through `notifier`. Any earlier unused tokens for the account
+/// stop working. Does nothing if the account is already verified.
+pub async fn send_verification(
+    user_id: Uuid,
+    notifier: &dyn Notifier,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let user = sqlx::query!(
+        "SELECT email, email_verified_at FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound("User not found.".to_string()))?;
+    if user.email_verified_at.is_some() {
+        return Ok(());
+    }
+
+    let token = generate_token();
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        UPDATE email_verification_tokens SET used_at = now() WHERE user_id = $1 AND used_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    let verification =
+        EmailVerificationToken::create(user_id, &hash_token(&token), &mut *tx).await?;
+
+    // Only keep the token if it was actually delivered.
+    notifier
+        .send(&Notification::EmailVerification {
+            email: user.email,
+            token,
+            expires_at: verification.expires_at,
+        })
+        .map_err(Error::Notification)?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Sends a new verification token to the pending account with `email`. Succeeds without sending anything if no pending account has that
+/// email, so the result does not reveal which emails exist.
+pub async fn resend_verification(
+    email: &str,
+    notifier: &dyn Notifier,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let Some(user_id) = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE email = $1 AND email_verified_at IS NULL AND deactivated_at IS NULL",
+        normalize_email(email)
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+    send_verification(user_id, notifier, pool).await
+}
+
+/// Redeems an email verification token, activating the account it was issued for.
+pub async fn verify_email(token: &str, pool: &PgPool) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+    let verification = sqlx::query_as!(
+        EmailVerificationToken,
+        r#"
+        SELECT id, user_id, created_at, expires_at, used_at
+        FROM email_verification_tokens
+        WHERE token_hash = $1
+        FOR UPDATE
+        "#,
+        hash_token(token)
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .filter(|verification| verification.is_redeemable(Utc::now()))
+    .ok_or_else(|| Error::Unauthenticated("Invalid or expired verification token.".to_string()))?;
+
+    sqlx::query!(
+        "UPDATE users SET email_verified_at = COALESCE(email_verified_at, now()) WHERE id = $1",
+        verification.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "UPDATE email_verification_tokens SET used_at = now() WHERE id = $1",
+        verification.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
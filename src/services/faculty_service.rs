@@ -0,0 +1,137 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        course_meeting_time::{CourseMeetingTime, Weekday},
+        course_offering::{CourseOffering, SectionType},
+        faculty_profile::{FacultyProfile, FacultyTitle},
+    },
+    security::authorization::Actor,
+    services::audit_service,
+};
+
+/// A section an instructor teaches, with the course it belongs to and when it meets.
+#[derive(Debug)]
+pub struct TeachingAssignment {
+    pub offering: CourseOffering,
+    pub course_number: String,
+    pub course_title: String,
+    pub meeting_times: Vec<CourseMeetingTime>,
+}
+
+pub async fn get_faculty_profile(
+    user_id: Uuid,
+    pool: &PgPool,
+) -> Result<Option<FacultyProfile>, sqlx::Error> {
+    let faculty_profile = sqlx::query_as!(
+        FacultyProfile,
+        r#"
+        SELECT user_id, department_id, title AS "title: FacultyTitle", office, office_hours, max_teaching_load
+        FROM faculty_profiles
+        WHERE user_id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(faculty_profile)
+}
+
+/// Sets where and when students can find an instructor. Instructors may set their own office details.
+pub async fn set_office(
+    user_id: Uuid,
+    office: Option<String>,
+    office_hours: Option<String>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<FacultyProfile>, Error> {
+    actor.require_for_account(user_id)?;
+    let Some(mut faculty_profile) = get_faculty_profile(user_id, pool).await? else {
+        return Ok(None);
+    };
+    let mut tx = audit_service::begin(actor, pool).await?;
+    faculty_profile
+        .set_office(office, office_hours, &mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(faculty_profile))
+}
+
+/// Returns the faculty profiles of every active instructor in a department.
+pub async fn get_faculty_for_department(
+    department_id: i32,
+    pool: &PgPool,
+) -> Result<Vec<FacultyProfile>, sqlx::Error> {
+    let faculty = sqlx::query_as!(
+        FacultyProfile,
+        r#"
+        SELECT fp.user_id, fp.department_id, fp.title AS "title: FacultyTitle", fp.office, fp.office_hours, fp.max_teaching_load
+        FROM faculty_profiles fp
+        JOIN users u ON u.id = fp.user_id
+        WHERE fp.department_id = $1 AND u.deactivated_at IS NULL
+        "#,
+        department_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(faculty)
+}
+
+/// Returns every section an instructor teaches in a term, ordered by course number and section.
+pub async fn get_teaching_schedule(
+    instructor_id: Uuid,
+    term_id: i32,
+    pool: &PgPool,
+) -> Result<Vec<TeachingAssignment>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT co.id, co.course_id, co.term_id, co.instructor_id, co.capacity, co.location, co.section_type AS "section_type: SectionType", co.section_number,
+               c.course_number, c.title
+        FROM course_offerings co
+        JOIN courses c ON c.id = co.course_id
+        WHERE co.instructor_id = $1 AND co.term_id = $2
+        ORDER BY c.course_number, co.section_number
+        "#,
+        instructor_id,
+        term_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut schedule = Vec::with_capacity(rows.len());
+    for row in rows {
+        let meeting_times = sqlx::query_as!(
+            CourseMeetingTime,
+            r#"
+            SELECT id, offering_id, day_of_week AS "day_of_week: Weekday", start_time, end_time
+            FROM course_meeting_times
+            WHERE offering_id = $1
+            ORDER BY start_time
+            "#,
+            row.id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        schedule.push(TeachingAssignment {
+            offering: CourseOffering {
+                id: row.id,
+                course_id: row.course_id,
+                term_id: row.term_id,
+                instructor_id: row.instructor_id,
+                capacity: row.capacity,
+                location: row.location,
+                section_type: row.section_type,
+                section_number: row.section_number,
+            },
+            course_number: row.course_number,
+            course_title: row.title,
+            meeting_times,
+        });
+    }
+    Ok(schedule)
+}
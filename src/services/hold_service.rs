@@ -0,0 +1,110 @@
+use sqlx::{
+    PgExecutor, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::hold::{Hold, HoldType},
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
+
+/// Lists every hold ever placed on a student, including released and expired ones, newest first.
+pub async fn get_holds_for_student(
+    student_id: Uuid,
+    pool: &PgPool,
+) -> Result<Vec<Hold>, sqlx::Error> {
+    let holds = sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT id, student_id, hold_type AS "hold_type: HoldType", reason, placed_by, placed_at, expires_at, released_by, released_at
+        FROM student_holds
+        WHERE student_id = $1
+        ORDER BY placed_at DESC
+        "#,
+        student_id
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(holds)
+}
+
+/// Lists the holds currently blocking a student, i.e. those that are neither released nor expired.
+/// Accepts any executor so that it can be used inside a registration transaction.
+pub async fn get_active_holds<'e>(
+    student_id: Uuid,
+    executor: impl PgExecutor<'e>,
+) -> Result<Vec<Hold>, sqlx::Error> {
+    let holds = sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT id, student_id, hold_type AS "hold_type: HoldType", reason, placed_by, placed_at, expires_at, released_by, released_at
+        FROM student_holds
+        WHERE student_id = $1
+        AND released_at IS NULL
+        AND (expires_at IS NULL OR expires_at > now())
+        ORDER BY placed_at
+        "#,
+        student_id
+    )
+    .fetch_all(executor)
+    .await?;
+    Ok(holds)
+}
+
+pub async fn get_hold_by_id(id: Uuid, pool: &PgPool) -> Result<Option<Hold>, sqlx::Error> {
+    let hold = sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT id, student_id, hold_type AS "hold_type: HoldType", reason, placed_by, placed_at, expires_at, released_by, released_at
+        FROM student_holds
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(hold)
+}
+
+/// Places a hold on a student on behalf of `actor`, who is recorded as responsible for it. `expires_at` may be `None` for holds that last until they are released.
+pub async fn place_hold(
+    student_id: Uuid,
+    hold_type: HoldType,
+    reason: String,
+    expires_at: Option<DateTime<Utc>>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Hold, Error> {
+    actor.require(Permission::ManageHolds)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let hold = Hold::create(
+        student_id,
+        hold_type,
+        reason,
+        Some(actor.user_id),
+        expires_at,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(hold)
+}
+
+/// Releases a hold on behalf of `actor`. Returns `None` if no hold exists with the given ID.
+pub async fn release_hold(
+    hold_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<Hold>, Error> {
+    actor.require(Permission::ManageHolds)?;
+    let Some(mut hold) = get_hold_by_id(hold_id, pool).await? else {
+        return Ok(None);
+    };
+    let mut tx = audit_service::begin(actor, pool).await?;
+    hold.release(actor.user_id, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(Some(hold))
+}
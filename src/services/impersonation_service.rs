@@ -0,0 +1,189 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        impersonation::{
+            ActionOutcome, IMPERSONATION_LIFETIME, Impersonation, ImpersonationEvent,
+            ImpersonationEventKind,
+        },
+        session::Session,
+        user::Role,
+    },
+    security::{
+        authorization::{Actor, Permission},
+        tokens::{generate_token, hash_token},
+    },
+    services::user_service,
+};
+
+/// Starts impersonating a student, so an admin can see exactly what they see. Returns the impersonation and the token of a new session
+/// of the student's, which runs the student-facing services as the student until `stop_impersonation` or `IMPERSONATION_LIFETIME`.
+///
+/// Impersonations are read-only unless `read_only` is `false`, which also requires managing registrations. The reason is kept with the
+/// impersonation, and its start, stop and every action through it are recorded with the admin's identity.
+pub async fn start_impersonation(
+    target_id: Uuid,
+    reason: &str,
+    read_only: bool,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(Impersonation, String), Error> {
+    actor.require(Permission::Impersonate)?;
+    if !read_only {
+        actor.require(Permission::ManageRegistrations)?;
+    }
+    user_service::get_user_by_id(target_id, pool)
+        .await?
+        .filter(|user| user.role == Role::Student && user.is_active())
+        .ok_or_else(|| {
+            Error::Validation("Only active students can be impersonated.".to_string())
+        })?;
+
+    let token = generate_token();
+    let mut tx = pool.begin().await?;
+    let session = Session::create(target_id, &hash_token(&token), false, &mut *tx).await?;
+    sqlx::query!(
+        "UPDATE sessions SET expires_at = $2 WHERE id = $1",
+        session.id,
+        session.created_at + IMPERSONATION_LIFETIME
+    )
+    .execute(&mut *tx)
+    .await?;
+    let impersonation = Impersonation::create(
+        actor.user_id,
+        target_id,
+        session.id,
+        reason,
+        read_only,
+        &mut *tx,
+    )
+    .await?;
+    let mode = if read_only { "read-only" } else { "read-write" };
+    ImpersonationEvent::record(
+        &impersonation,
+        ImpersonationEventKind::Start,
+        &format!("Started {} impersonation: {}", mode, impersonation.reason),
+        None,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok((impersonation, token))
+}
+
+/// Ends the impersonation carried out through the session identified by a token, signing out of the session.
+/// Returns `false` if the token does not belong to an impersonation that is still going.
+pub async fn stop_impersonation(token: &str, pool: &PgPool) -> Result<bool, Error> {
+    let mut tx = pool.begin().await?;
+    let Some(impersonation) = sqlx::query_as!(
+        Impersonation,
+        r#"
+        SELECT i.id, i.admin_id, i.target_id, i.session_id, i.reason, i.read_only, i.started_at, i.ended_at
+        FROM impersonations i
+        JOIN sessions s ON s.id = i.session_id
+        WHERE s.token_hash = $1 AND i.ended_at IS NULL
+        FOR UPDATE OF i
+        "#,
+        hash_token(token)
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(false);
+    };
+
+    sqlx::query!(
+        "UPDATE impersonations SET ended_at = now() WHERE id = $1",
+        impersonation.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "UPDATE sessions SET revoked_at = now() WHERE id = $1 AND revoked_at IS NULL",
+        impersonation.session_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    ImpersonationEvent::record(
+        &impersonation,
+        ImpersonationEventKind::Stop,
+        "Stopped impersonation",
+        None,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(true)
+}
+
+/// Runs a student-facing action and, if it did not go through, records it as refused or failed in the audit trail of the impersonation the
+/// actor is acting through. Actions that go through record themselves with `record_action`.
+pub async fn track_action<T>(
+    actor: &Actor,
+    action: &str,
+    pool: &PgPool,
+    operation: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let result = operation.await;
+    if let (Some(impersonation), Err(error)) = (&actor.impersonation, &result) {
+        let outcome = match error {
+            Error::Forbidden(_) => ActionOutcome::Refused,
+            _ => ActionOutcome::Failed,
+        };
+        ImpersonationEvent::record(
+            impersonation,
+            ImpersonationEventKind::Action,
+            action,
+            Some(outcome),
+            pool,
+        )
+        .await?;
+    }
+    result
+}
+
+/// Records an action that went through in the audit trail of the impersonation the actor is acting through, and does nothing for everyone
+/// else. Services that write call this inside the action's transaction just before committing, so the event is kept only if the action is.
+pub async fn record_action<'e>(
+    actor: &Actor,
+    action: &str,
+    executor: impl PgExecutor<'e>,
+) -> Result<(), sqlx::Error> {
+    if let Some(impersonation) = &actor.impersonation {
+        ImpersonationEvent::record(
+            impersonation,
+            ImpersonationEventKind::Action,
+            action,
+            Some(ActionOutcome::Succeeded),
+            executor,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Returns every impersonation of a student, newest first.
+pub async fn get_impersonations(
+    target_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<Impersonation>, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let impersonations = Impersonation::for_target(target_id, pool).await?;
+    Ok(impersonations)
+}
+
+/// Returns the audit trail of an impersonation in the order it happened.
+pub async fn get_impersonation_events(
+    impersonation_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<ImpersonationEvent>, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let events = ImpersonationEvent::for_impersonation(impersonation_id, pool).await?;
+    Ok(events)
+}
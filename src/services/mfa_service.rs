@@ -0,0 +1,330 @@
+use sqlx::{
+    PgConnection, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{mfa_enrollment::MfaEnrollment, session::SESSION_LIFETIME, user::Role},
+    security::{
+        authorization::{Actor, Permission},
+        throttling::{self, Throttled},
+        tokens::{generate_token, hash_token},
+        totp,
+    },
+    services::session_service,
+};
+
+/// The issuer shown next to the account in authenticator apps.
+const ISSUER: &str = "SchoolRedux";
+pub const RECOVERY_CODE_COUNT: usize = 10;
+/// Wrong codes allowed in a row across a user's pending sessions. Reaching it revokes them and refuses second steps for
+/// `throttling::LOCKOUT_DURATION`, so logging in again does not buy more guesses.
+pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// What a user needs to add their account to an authenticator app. The recovery codes are only shown once.
+#[derive(Debug, Clone)]
+pub struct MfaSetup {
+    /// The base32 secret, for entering by hand.
+    pub secret: String,
+    pub provisioning_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+/// Whether a user must complete a second login step. Admins always must; other users once they have set up an authenticator.
+pub async fn is_mfa_required(user_id: Uuid, pool: &PgPool) -> Result<bool, Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT u.role AS "role: Role", e.confirmed_at AS "confirmed_at?"
+        FROM users u
+        LEFT JOIN mfa_enrollments e ON e.user_id = u.id
+        WHERE u.id = $1
+        "#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound("User not found.".to_string()))?;
+    Ok(row.role == Role::Admin || row.confirmed_at.is_some())
+}
+
+/// Whether a user has a confirmed authenticator.
+pub async fn is_enrolled(user_id: Uuid, pool: &PgPool) -> Result<bool, Error> {
+    let enrolled = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM mfa_enrollments WHERE user_id = $1 AND confirmed_at IS NOT NULL
+        ) AS "enrolled!"
+        "#,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(enrolled)
+}
+
+/// Starts setting up an authenticator for the actor. Any earlier unconfirmed setup is replaced. The setup takes effect once a code from
+/// the authenticator is entered, through `confirm_enrollment` or, on a pending session, `complete_login`.
+pub async fn begin_enrollment(
+    user_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<MfaSetup, Error> {
+    if actor.user_id != user_id || actor.impersonation.is_some() {
+        return Err(actor.forbidden(Permission::ManageUsers, None).into());
+    }
+    let mut tx = pool.begin().await?;
+    if get_enrollment(&mut tx, user_id)
+        .await?
+        .is_some_and(|enrollment| enrollment.is_confirmed())
+    {
+        return Err(Error::Conflict(
+            "Two-factor authentication is already set up.".to_string(),
+        ));
+    }
+
+    let email = sqlx::query_scalar!("SELECT email FROM users WHERE id = $1", user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let secret = totp::generate_secret();
+    sqlx::query!(
+        r#"
+        INSERT INTO mfa_enrollments (user_id, secret) VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET secret = $2, created_at = now(), last_used_step = NULL
+        "#,
+        user_id,
+        totp::base32_encode(&secret)
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+    sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    for code in &recovery_codes {
+        sqlx::query!(
+            "INSERT INTO mfa_recovery_codes (user_id, code_hash) VALUES ($1, $2)",
+            user_id,
+            hash_token(code)
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(MfaSetup {
+        secret: totp::base32_encode(&secret),
+        provisioning_uri: totp::provisioning_uri(&secret, ISSUER, &email),
+        recovery_codes,
+    })
+}
+
+/// Confirms the actor's authenticator setup with a code from it, turning on two-factor authentication for their future logins.
+pub async fn confirm_enrollment(
+    user_id: Uuid,
+    code: &str,
+    now: DateTime<Utc>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    if actor.user_id != user_id || actor.impersonation.is_some() {
+        return Err(actor.forbidden(Permission::ManageUsers, None).into());
+    }
+    let mut tx = pool.begin().await?;
+    let enrollment = get_enrollment(&mut tx, user_id).await?.ok_or_else(|| {
+        Error::Conflict("Two-factor authentication has not been set up.".to_string())
+    })?;
+    if !verify_totp(&mut tx, &enrollment, code, now).await? {
+        return Err(Error::Unauthenticated(
+            "Invalid two-factor code.".to_string(),
+        ));
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Completes the second login step of a pending session with a code from the user's authenticator or an unused recovery code. Users
+/// finishing their first setup on this session confirm it with the code. Wrong codes count against the user rather than the session; after
+/// `MAX_FAILED_ATTEMPTS` of them the user's pending sessions are revoked and second steps are locked for a while.
+pub async fn complete_login(
+    session_token: &str,
+    code: &str,
+    now: DateTime<Utc>,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let (session, _actor) = session_service::authenticate_pending(session_token, pool)
+        .await?
+        .ok_or_else(|| {
+            Error::Unauthenticated("The login has expired. Please log in again.".to_string())
+        })?;
+
+    let mut tx = pool.begin().await?;
+    let enrollment = get_enrollment(&mut tx, session.user_id)
+        .await?
+        .ok_or_else(|| {
+            Error::Conflict("Two-factor authentication must be set up first.".to_string())
+        })?;
+
+    if let Some(locked_until) = enrollment.locked_until.filter(|until| *until > now) {
+        return Err(Throttled {
+            retry_after: locked_until,
+        }
+        .into());
+    }
+
+    let valid = verify_totp(&mut tx, &enrollment, code, now).await?
+        || (enrollment.is_confirmed()
+            && redeem_recovery_code(&mut tx, session.user_id, code, now).await?);
+    if !valid {
+        // Reaching the limit locks the second step and starts the count over for when the lock ends.
+        let failed_attempts = enrollment.failed_attempts + 1;
+        let (failed_attempts, locked_until) = if failed_attempts >= MAX_FAILED_ATTEMPTS {
+            (0, Some(now + throttling::LOCKOUT_DURATION))
+        } else {
+            (failed_attempts, None)
+        };
+        sqlx::query!(
+            r#"
+            UPDATE mfa_enrollments SET failed_attempts = $2, locked_until = $3 WHERE user_id = $1
+            "#,
+            session.user_id,
+            failed_attempts,
+            locked_until
+        )
+        .execute(&mut *tx)
+        .await?;
+        if locked_until.is_some() {
+            sqlx::query!(
+                r#"
+                UPDATE sessions SET revoked_at = $2
+                WHERE user_id = $1 AND mfa_pending AND revoked_at IS NULL
+                "#,
+                session.user_id,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        return Err(Error::Unauthenticated(
+            "Invalid two-factor code.".to_string(),
+        ));
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE mfa_enrollments SET failed_attempts = 0, locked_until = NULL WHERE user_id = $1
+        "#,
+        session.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r#"
+        UPDATE sessions SET mfa_pending = false, expires_at = $2, last_seen_at = $3 WHERE id = $1
+        "#,
+        session.id,
+        now + SESSION_LIFETIME,
+        now
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Removes a user's authenticator and recovery codes. Users may turn off their own two-factor authentication unless they are admins, who
+/// are required to use it; resetting another user's, e.g. after a lost device, requires managing users.
+pub async fn disable_mfa(user_id: Uuid, actor: &Actor, pool: &PgPool) -> Result<(), Error> {
+    actor.require_for_account(user_id)?;
+    if actor.user_id == user_id && actor.role == Role::Admin {
+        return Err(Error::Conflict(
+            "Administrators must use two-factor authentication.".to_string(),
+        ));
+    }
+    let mut tx = pool.begin().await?;
+    sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM mfa_enrollments WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn get_enrollment(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<Option<MfaEnrollment>, sqlx::Error> {
+    let enrollment = sqlx::query_as!(
+        MfaEnrollment,
+        r#"
+        SELECT user_id, secret, created_at, confirmed_at, last_used_step, failed_attempts, locked_until
+        FROM mfa_enrollments
+        WHERE user_id = $1
+        FOR UPDATE
+        "#,
+        user_id
+    )
+    .fetch_optional(conn)
+    .await?;
+    Ok(enrollment)
+}
+
+/// Checks a TOTP code and, if it is valid, records its step so it cannot be replayed and confirms the enrollment.
+async fn verify_totp(
+    conn: &mut PgConnection,
+    enrollment: &MfaEnrollment,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, Error> {
+    let secret = totp::base32_decode(&enrollment.secret)
+        .ok_or_else(|| Error::Internal("Stored two-factor secret is malformed.".to_string()))?;
+    let Some(step) = totp::verify(&secret, code, now, enrollment.last_used_step) else {
+        return Ok(false);
+    };
+    sqlx::query!(
+        r#"
+        UPDATE mfa_enrollments
+        SET last_used_step = $2, confirmed_at = COALESCE(confirmed_at, $3)
+        WHERE user_id = $1
+        "#,
+        enrollment.user_id,
+        step,
+        now
+    )
+    .execute(conn)
+    .await?;
+    Ok(true)
+}
+
+async fn redeem_recovery_code(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE mfa_recovery_codes SET used_at = $3
+        WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+        "#,
+        user_id,
+        hash_token(&code.trim().to_lowercase()),
+        now
+    )
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// A recovery code like `3f9a1-c07be`.
+fn generate_recovery_code() -> String {
+    let token = generate_token();
+    format!("{}-{}", &token[..5], &token[5..10])
+}
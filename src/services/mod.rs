@@ -1,3 +1,19 @@
+pub mod audit_service;
+pub mod billing_service;
+pub mod cart_service;
 pub mod course_service;
 pub mod department_service;
+pub mod email_verification_service;
+pub mod faculty_service;
+pub mod hold_service;
+pub mod impersonation_service;
+pub mod mfa_service;
+pub mod override_service;
+pub mod password_reset_service;
+pub mod program_service;
+pub mod registration_service;
+pub mod session_service;
+pub mod term_service;
+pub mod transcript_service;
+pub mod transfer_credit_service;
 pub mod user_service;
@@ -0,0 +1,251 @@
+use sqlx::{
+    PgConnection, PgPool,
+    types::chrono::{DateTime, Utc},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        registration_override::{
+            OverrideAction, OverrideAuditEntry, OverrideRule, RegistrationOverride,
+        },
+        user::Role,
+    },
+    security::{
+        authorization::{Actor, Permission},
+        tokens::hash_token,
+    },
+    services::audit_service,
+};
+
+/// Issues a one-time permission number for an offering. Any student given the number can use it once to bypass `rule`.
+/// Only the offering's instructor, or an admin of its department, may issue permission numbers.
+pub async fn issue_permission_number(
+    offering_id: Uuid,
+    rule: OverrideRule,
+    expires_at: Option<DateTime<Utc>>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<RegistrationOverride, Error> {
+    issue(offering_id, None, rule, expires_at, actor, pool).await
+}
+
+/// Issues an override letting a specific student bypass `rule` once when registering in an offering.
+/// Only the offering's instructor, or an admin of its department, may issue overrides.
+pub async fn issue_override(
+    offering_id: Uuid,
+    student_id: Uuid,
+    rule: OverrideRule,
+    expires_at: Option<DateTime<Utc>>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<RegistrationOverride, Error> {
+    issue(offering_id, Some(student_id), rule, expires_at, actor, pool).await
+}
+
+async fn issue(
+    offering_id: Uuid,
+    student_id: Option<Uuid>,
+    rule: OverrideRule,
+    expires_at: Option<DateTime<Utc>>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<RegistrationOverride, Error> {
+    let mut tx = audit_service::begin(actor, pool).await?;
+    authorize_for_offering(&mut tx, offering_id, actor).await?;
+    let registration_override = RegistrationOverride::create(
+        offering_id,
+        student_id,
+        rule,
+        actor.user_id,
+        expires_at,
+        &mut *tx,
+    )
+    .await?;
+    OverrideAuditEntry::record(
+        registration_override.id,
+        OverrideAction::Issued,
+        actor.user_id,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(registration_override)
+}
+
+/// Revokes an unused override. Only the offering's instructor, or an admin of its department, may revoke its overrides.
+/// Returns `None` if the override does not exist or has already been used or revoked.
+pub async fn revoke_override(
+    override_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<RegistrationOverride>, Error> {
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let Some(offering_id) = sqlx::query_scalar!(
+        r#"
+        SELECT offering_id FROM registration_overrides WHERE id = $1
+        "#,
+        override_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+    authorize_for_offering(&mut tx, offering_id, actor).await?;
+
+    let revoked = sqlx::query_as!(
+        RegistrationOverride,
+        r#"
+        UPDATE registration_overrides
+        SET revoked_at = now()
+        WHERE id = $1 AND used_at IS NULL AND revoked_at IS NULL
+        RETURNING id, offering_id, student_id, rule AS "rule: OverrideRule", NULL::TEXT AS "permission_number?", issued_by, issued_at, expires_at, used_by, used_at, revoked_at
+        "#,
+        override_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if revoked.is_some() {
+        OverrideAuditEntry::record(
+            override_id,
+            OverrideAction::Revoked,
+            actor.user_id,
+            &mut *tx,
+        )
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(revoked)
+}
+
+/// Uses up an override that lets `student_id` bypass `rule` for an offering, recording `actor_id`, the user registering the student, in the
+/// audit log. A direct override for the student is preferred over a permission number. Returns `false` if no usable override exists.
+pub async fn consume_override(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+    rule: OverrideRule,
+    permission_number: Option<&str>,
+    actor_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let consumed = sqlx::query_scalar!(
+        r#"
+        UPDATE registration_overrides
+        SET used_by = $2, used_at = now()
+        WHERE id = (
+            SELECT id FROM registration_overrides
+            WHERE offering_id = $1
+            AND rule = $3
+            AND used_at IS NULL
+            AND revoked_at IS NULL
+            AND (expires_at IS NULL OR expires_at > now())
+            AND (
+                (student_id = $2 AND permission_number_hash IS NULL)
+                OR (permission_number_hash = $4 AND (student_id IS NULL OR student_id = $2))
+            )
+            ORDER BY permission_number_hash NULLS FIRST
+            LIMIT 1
+            FOR UPDATE
+        )
+        RETURNING id
+        "#,
+        offering_id,
+        student_id,
+        rule.to_string(),
+        permission_number.map(hash_token)
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(override_id) = consumed else {
+        return Ok(false);
+    };
+    OverrideAuditEntry::record(override_id, OverrideAction::Consumed, actor_id, &mut *conn).await?;
+    Ok(true)
+}
+
+/// Returns every override issued for an offering, oldest first. Only the offering's instructor, or an admin of its department, may list them.
+pub async fn get_overrides_for_offering(
+    offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<RegistrationOverride>, Error> {
+    authorize_for_offering(&mut *pool.acquire().await?, offering_id, actor).await?;
+    let overrides = sqlx::query_as!(
+        RegistrationOverride,
+        r#"
+        SELECT id, offering_id, student_id, rule AS "rule: OverrideRule", NULL::TEXT AS "permission_number?", issued_by, issued_at, expires_at, used_by, used_at, revoked_at
+        FROM registration_overrides
+        WHERE offering_id = $1
+        ORDER BY issued_at
+        "#,
+        offering_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(overrides)
+}
+
+/// Returns the audit trail of an override, oldest first. Only the offering's instructor, or an admin of its department, may view it.
+pub async fn get_audit_log(
+    override_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<OverrideAuditEntry>, Error> {
+    let offering_id = sqlx::query_scalar!(
+        r#"
+        SELECT offering_id FROM registration_overrides WHERE id = $1
+        "#,
+        override_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| Error::NotFound("Override does not exist.".to_string()))?;
+    authorize_for_offering(&mut *pool.acquire().await?, offering_id, actor).await?;
+    let entries = sqlx::query_as!(
+        OverrideAuditEntry,
+        r#"
+        SELECT id, override_id, action AS "action: OverrideAction", actor_id, occurred_at
+        FROM override_audit_log
+        WHERE override_id = $1
+        ORDER BY id
+        "#,
+        override_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Instructors may only manage overrides for offerings they teach; admins may manage overrides for offerings in their department.
+async fn authorize_for_offering(
+    conn: &mut PgConnection,
+    offering_id: Uuid,
+    actor: &Actor,
+) -> Result<(), Error> {
+    let offering = sqlx::query!(
+        r#"
+        SELECT co.instructor_id, c.department_id
+        FROM course_offerings co
+        JOIN courses c ON co.course_id = c.id
+        WHERE co.id = $1
+        "#,
+        offering_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| Error::NotFound("Course offering does not exist.".to_string()))?;
+
+    actor.require_in_department(Permission::IssueOverrides, offering.department_id)?;
+    if actor.role == Role::Instructor && actor.user_id != offering.instructor_id {
+        return Err(actor
+            .forbidden(Permission::IssueOverrides, Some(offering.department_id))
+            .into());
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use sqlx::{PgPool, types::chrono::Utc};
+
+use crate::{
+    error::Error,
+    models::{
+        password_reset::PasswordResetToken,
+        user::{FullName, normalize_email},
+    },
+    notifications::{Notification, Notifier},
+    security::{
+        password,
+        password_policy::PasswordPolicy,
+        tokens::{generate_token, hash_token},
+    },
+};
+
+/// Issues a password reset token for the account with `email` and sends it through `notifier`. Any earlier unused tokens for the
+/// account stop working. Succeeds without sending anything if no active account has that email, so the result does not reveal which emails
+/// exist.
+pub async fn request_password_reset(
+    email: &str,
+    notifier: &dyn Notifier,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    let email = &normalize_email(email);
+    let Some(user_id) = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE email = $1 AND deactivated_at IS NULL",
+        email
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let token = generate_token();
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        UPDATE password_reset_tokens SET used_at = now() WHERE user_id = $1 AND used_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    let reset = PasswordResetToken::create(user_id, &hash_token(&token), &mut *tx).await?;
+
+    // Only keep the token if it was actually delivered.
+    notifier
+        .send(&Notification::PasswordReset {
+            email: email.to_string(),
+            token,
+            expires_at: reset.expires_at,
+        })
+        .map_err(Error::Notification)?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Redeems a password reset token, setting a new password that must follow the password policy. The token is used up, every session
+/// of the user is signed out and any login lockout is lifted. Since the token was delivered to the user's email, this also verifies it.
+pub async fn reset_password(token: &str, new_password: &str, pool: &PgPool) -> Result<(), Error> {
+    let mut tx = pool.begin().await?;
+    let reset = sqlx::query_as!(
+        PasswordResetToken,
+        r#"
+        SELECT id, user_id, created_at, expires_at, used_at
+        FROM password_reset_tokens
+        WHERE token_hash = $1
+        FOR UPDATE
+        "#,
+        hash_token(token)
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .filter(|reset| reset.is_redeemable(Utc::now()))
+    .ok_or_else(|| {
+        Error::Unauthenticated("Invalid or expired password reset token.".to_string())
+    })?;
+
+    let user = sqlx::query!(
+        "SELECT email, first_name, last_name FROM users WHERE id = $1",
+        reset.user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let name = FullName::new(&user.first_name, &user.last_name);
+    PasswordPolicy::from_env().check(new_password, &name, &user.email)?;
+    let hashed_password = password::hash_password(new_password)?;
+
+    sqlx::query!(
+        "UPDATE users SET hashed_password = $2, email_verified_at = COALESCE(email_verified_at, now()) WHERE id = $1",
+        reset.user_id,
+        hashed_password
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "UPDATE password_reset_tokens SET used_at = now() WHERE id = $1",
+        reset.id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        "UPDATE sessions SET revoked_at = now() WHERE user_id = $1 AND revoked_at IS NULL",
+        reset.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!("DELETE FROM login_throttles WHERE email = $1", user.email)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,419 @@
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        major_change_request::{MajorChangeRequest, MajorChangeStatus},
+        program::{Program, ProgramDeclaration, ProgramKind},
+    },
+    security::authorization::{Actor, Permission},
+    services::{audit_service, impersonation_service},
+};
+
+/// Creates a program of a department. Admins of the department may create its programs.
+pub async fn create_program(
+    department_id: i32,
+    name: String,
+    kind: ProgramKind,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Program, Error> {
+    actor.require_in_department(Permission::ManagePrograms, department_id)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let program = Program::create(department_id, name, kind, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(program)
+}
+
+/// Lists every program, by name.
+pub async fn get_programs(pool: &PgPool) -> Result<Vec<Program>, sqlx::Error> {
+    let programs = sqlx::query_as!(
+        Program,
+        r#"
+        SELECT id, department_id, name, kind AS "kind: ProgramKind"
+        FROM programs
+        ORDER BY name, kind
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(programs)
+}
+
+pub async fn get_program_by_id<'e>(
+    id: i32,
+    executor: impl PgExecutor<'e>,
+) -> Result<Option<Program>, sqlx::Error> {
+    let program = sqlx::query_as!(
+        Program,
+        r#"
+        SELECT id, department_id, name, kind AS "kind: ProgramKind"
+        FROM programs
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(executor)
+    .await?;
+    Ok(program)
+}
+
+/// Returns the programs a student has declared and not ended, oldest declaration first.
+pub async fn get_declared_programs(
+    student_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<ProgramDeclaration>, Error> {
+    let action = "View declared programs";
+    impersonation_service::track_action(actor, action, pool, async {
+        actor.require_view_student(student_id)?;
+        let declarations = sqlx::query_as!(
+            ProgramDeclaration,
+            r#"
+            SELECT id, student_id, program_id, declared_on, ended_on
+            FROM program_declarations
+            WHERE student_id = $1 AND ended_on IS NULL
+            ORDER BY declared_on, id
+            "#,
+            student_id
+        )
+        .fetch_all(pool)
+        .await?;
+        impersonation_service::record_action(actor, action, pool).await?;
+        Ok(declarations)
+    })
+    .await
+}
+
+/// Returns every program a student has ever declared, including ended declarations, oldest first.
+pub async fn get_program_history(
+    student_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<ProgramDeclaration>, Error> {
+    let action = "View program history";
+    impersonation_service::track_action(actor, action, pool, async {
+        actor.require_view_student(student_id)?;
+        let declarations = sqlx::query_as!(
+            ProgramDeclaration,
+            r#"
+            SELECT id, student_id, program_id, declared_on, ended_on
+            FROM program_declarations
+            WHERE student_id = $1
+            ORDER BY declared_on, id
+            "#,
+            student_id
+        )
+        .fetch_all(pool)
+        .await?;
+        impersonation_service::record_action(actor, action, pool).await?;
+        Ok(declarations)
+    })
+    .await
+}
+
+/// Declares a program for a student as of today, on behalf of an admin of the program's department. Students ask for majors through
+/// `request_major_change` instead. Minors, honours programs and certificates can be declared at any time, but a major only while the
+/// student has none; after that, majors are changed through major change requests too.
+pub async fn declare_program(
+    student_id: Uuid,
+    program_id: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<ProgramDeclaration, Error> {
+    let action = format!("Declare program {}", program_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        let mut tx = audit_service::begin(actor, pool).await?;
+        lock_student(&mut tx, student_id).await?;
+        let program = get_program_by_id(program_id, &mut *tx)
+            .await?
+            .ok_or_else(|| Error::NotFound("Program does not exist.".to_string()))?;
+        actor.require_in_department(Permission::ManagePrograms, program.department_id)?;
+        if is_declared(&mut tx, student_id, program_id).await? {
+            return Err(Error::Conflict(
+                "This program is already declared.".to_string(),
+            ));
+        }
+        if program.kind == ProgramKind::Major && has_major(&mut tx, student_id).await? {
+            return Err(Error::Conflict(
+                "A student with a major must request a major change to declare another."
+                    .to_string(),
+            ));
+        }
+        let declaration = ProgramDeclaration::create(student_id, program_id, &mut *tx).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(declaration)
+    })
+    .await
+}
+
+/// Ends a student's declaration of a minor, honours program or certificate as of today, on behalf of an admin of the program's department.
+/// Majors are changed through `request_major_change` instead. Returns `None` if the program is not currently declared.
+pub async fn end_program(
+    student_id: Uuid,
+    program_id: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<ProgramDeclaration>, Error> {
+    let action = format!("End program {}", program_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let program = get_program_by_id(program_id, &mut *tx)
+            .await?
+            .ok_or_else(|| Error::NotFound("Program does not exist.".to_string()))?;
+        actor.require_in_department(Permission::ManagePrograms, program.department_id)?;
+        if program.kind == ProgramKind::Major {
+            return Err(Error::Conflict(
+                "Majors can only be changed through a major change request.".to_string(),
+            ));
+        }
+        let declaration = ProgramDeclaration::end(student_id, program_id, &mut *tx).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(declaration)
+    })
+    .await
+}
+
+/// Requests a change of major for a student, to be decided by an admin of the new major's department. `from_program_id` is the declared
+/// major the new one replaces, or `None` to add a second major. A student can only have one request waiting for a decision at a time.
+pub async fn request_major_change(
+    student_id: Uuid,
+    from_program_id: Option<i32>,
+    to_program_id: i32,
+    reason: String,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<MajorChangeRequest, Error> {
+    let action = format!("Request a change of major to program {}", to_program_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        lock_student(&mut tx, student_id).await?;
+        let to_program = get_program_by_id(to_program_id, &mut *tx)
+            .await?
+            .ok_or_else(|| Error::NotFound("Program does not exist.".to_string()))?;
+        if to_program.kind != ProgramKind::Major {
+            return Err(Error::Validation(format!("{} is not a major.", to_program)));
+        }
+        if is_declared(&mut tx, student_id, to_program_id).await? {
+            return Err(Error::Conflict(
+                "This major is already declared.".to_string(),
+            ));
+        }
+        if let Some(from_program_id) = from_program_id {
+            let from_program = get_program_by_id(from_program_id, &mut *tx).await?;
+            let replaces_major =
+                from_program.is_some_and(|program| program.kind == ProgramKind::Major);
+            if !replaces_major || !is_declared(&mut tx, student_id, from_program_id).await? {
+                return Err(Error::Validation(
+                    "Only a declared major can be replaced.".to_string(),
+                ));
+            }
+        }
+        let pending = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM major_change_requests WHERE student_id = $1 AND status = 'pending'
+            ) AS "exists!"
+            "#,
+            student_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if pending {
+            return Err(Error::Conflict(
+                "A major change request is already waiting for a decision.".to_string(),
+            ));
+        }
+        let request = MajorChangeRequest::create(
+            student_id,
+            from_program_id,
+            to_program_id,
+            reason,
+            &mut *tx,
+        )
+        .await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(request)
+    })
+    .await
+}
+
+/// Approves a major change request, ending the declaration of the major it replaces and declaring the new one as of today. Admins of the
+/// new major's department may decide its requests. Returns `None` if no request exists with the given ID.
+pub async fn approve_major_change(
+    request_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<MajorChangeRequest>, Error> {
+    decide_major_change(request_id, MajorChangeStatus::Approved, actor, pool).await
+}
+
+/// Rejects a major change request, leaving the student's programs as they are. Returns `None` if no request exists with the given ID.
+pub async fn reject_major_change(
+    request_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<MajorChangeRequest>, Error> {
+    decide_major_change(request_id, MajorChangeStatus::Rejected, actor, pool).await
+}
+
+async fn decide_major_change(
+    request_id: Uuid,
+    status: MajorChangeStatus,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<MajorChangeRequest>, Error> {
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let Some(mut request) = sqlx::query_as!(
+        MajorChangeRequest,
+        r#"
+        SELECT id, student_id, from_program_id, to_program_id, reason, status AS "status: MajorChangeStatus", requested_at, decided_by, decided_at
+        FROM major_change_requests
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        request_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let to_program = get_program_by_id(request.to_program_id, &mut *tx)
+        .await?
+        .ok_or_else(|| Error::NotFound("Program does not exist.".to_string()))?;
+    actor.require_in_department(Permission::ManagePrograms, to_program.department_id)?;
+    if request.status != MajorChangeStatus::Pending {
+        return Err(Error::Conflict(format!(
+            "This major change request has already been {}.",
+            request.status
+        )));
+    }
+
+    if status == MajorChangeStatus::Approved {
+        lock_student(&mut tx, request.student_id).await?;
+        if let Some(from_program_id) = request.from_program_id {
+            ProgramDeclaration::end(request.student_id, from_program_id, &mut *tx)
+                .await?
+                .ok_or_else(|| {
+                    Error::Conflict("The major being replaced is no longer declared.".to_string())
+                })?;
+        }
+        if is_declared(&mut tx, request.student_id, request.to_program_id).await? {
+            return Err(Error::Conflict(
+                "The requested major is already declared.".to_string(),
+            ));
+        }
+        ProgramDeclaration::create(request.student_id, request.to_program_id, &mut *tx).await?;
+    }
+    request.decide(status, actor.user_id, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(Some(request))
+}
+
+/// Returns every major change request of a student, including decided ones, newest first.
+pub async fn get_major_change_requests(
+    student_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<MajorChangeRequest>, Error> {
+    let action = "View major change requests";
+    impersonation_service::track_action(actor, action, pool, async {
+        actor.require_view_student(student_id)?;
+        let requests = sqlx::query_as!(
+            MajorChangeRequest,
+            r#"
+            SELECT id, student_id, from_program_id, to_program_id, reason, status AS "status: MajorChangeStatus", requested_at, decided_by, decided_at
+            FROM major_change_requests
+            WHERE student_id = $1
+            ORDER BY requested_at DESC
+            "#,
+            student_id
+        )
+        .fetch_all(pool)
+        .await?;
+        impersonation_service::record_action(actor, action, pool).await?;
+        Ok(requests)
+    })
+    .await
+}
+
+/// Returns the major change requests waiting for a decision that the actor may decide, oldest first.
+pub async fn get_pending_major_changes(
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<MajorChangeRequest>, Error> {
+    match actor.department_id {
+        Some(department_id) => {
+            actor.require_in_department(Permission::ManagePrograms, department_id)?
+        }
+        None => actor.require(Permission::ManagePrograms)?,
+    }
+    let requests = sqlx::query_as!(
+        MajorChangeRequest,
+        r#"
+        SELECT r.id, r.student_id, r.from_program_id, r.to_program_id, r.reason, r.status AS "status: MajorChangeStatus", r.requested_at,
+               r.decided_by, r.decided_at
+        FROM major_change_requests r
+        JOIN programs p ON p.id = r.to_program_id
+        WHERE r.status = 'pending'
+        AND ($1::INT IS NULL OR p.department_id = $1)
+        ORDER BY r.requested_at
+        "#,
+        actor.department_id
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(requests)
+}
+
+/// Locks a student's profile, so changes to their programs are made one at a time.
+async fn lock_student(conn: &mut PgConnection, student_id: Uuid) -> Result<(), Error> {
+    sqlx::query_scalar!(
+        "SELECT user_id FROM student_profiles WHERE user_id = $1 FOR UPDATE",
+        student_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| Error::NotFound("Student does not exist.".to_string()))?;
+    Ok(())
+}
+
+async fn is_declared(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    program_id: i32,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM program_declarations
+            WHERE student_id = $1 AND program_id = $2 AND ended_on IS NULL
+        ) AS "exists!"
+        "#,
+        student_id,
+        program_id
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+async fn has_major(conn: &mut PgConnection, student_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM program_declarations pd
+            JOIN programs p ON p.id = pd.program_id
+            WHERE pd.student_id = $1 AND pd.ended_on IS NULL AND p.kind = 'major'
+        ) AS "exists!"
+        "#,
+        student_id
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
@@ -0,0 +1,725 @@
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        registration::{Grade, Registration, RegistrationStatus},
+        registration_override::OverrideRule,
+    },
+    security::authorization::Actor,
+    services::{
+        audit_service, billing_service, hold_service::get_active_holds, impersonation_service,
+        override_service, transcript_service,
+    },
+};
+
+/// Enrolls a student in a course offering under its primary course and charges them for it.
+/// The student is refused if they have an active hold, the course requires instructor consent, they are missing a prerequisite,
+/// the offering conflicts with their timetable, they would exceed the term's credit limit, or the offering is full. Any direct override an instructor issued the student is used to bypass a failed rule.
+/// A student who previously dropped the offering is re-registered using their existing registration.
+/// Sections that require linked components, such as a lecture that needs a lab, must be registered with `enroll_with_components`.
+/// Like every registration operation, the actor must be the student themselves or an admin who manages registrations.
+pub async fn enroll(
+    student_id: Uuid,
+    offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Registration, Error> {
+    let action = format!("Enroll in offering {}", offering_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        check_components(&mut tx, &[offering_id]).await?;
+        let registration =
+            enroll_in_transaction(&mut tx, student_id, offering_id, None, None, actor.user_id)
+                .await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(registration)
+    })
+    .await
+}
+
+/// Enrolls a student using a permission number issued by the offering's instructor, which bypasses the rule it was issued for.
+/// The permission number is only used up if it was needed.
+pub async fn enroll_with_permission(
+    student_id: Uuid,
+    offering_id: Uuid,
+    permission_number: &str,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Registration, Error> {
+    let action = format!(
+        "Enroll in offering {} with a permission number",
+        offering_id
+    );
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        check_components(&mut tx, &[offering_id]).await?;
+        let registration = enroll_in_transaction(
+            &mut tx,
+            student_id,
+            offering_id,
+            None,
+            Some(permission_number),
+            actor.user_id,
+        )
+        .await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(registration)
+    })
+    .await
+}
+
+/// Enrolls a student in a cross-listed offering under one of its listings, e.g. MATH 340 rather than COSC 340.
+/// In addition to the checks made by `enroll`, the listing's seat cap is enforced.
+pub async fn enroll_under_listing(
+    student_id: Uuid,
+    offering_id: Uuid,
+    listed_course_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Registration, Error> {
+    let action = format!(
+        "Enroll in offering {} under course {}",
+        offering_id, listed_course_id
+    );
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        check_components(&mut tx, &[offering_id]).await?;
+        let registration = enroll_in_transaction(
+            &mut tx,
+            student_id,
+            offering_id,
+            Some(listed_course_id),
+            None,
+            actor.user_id,
+        )
+        .await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(registration)
+    })
+    .await
+}
+
+/// Enrolls a student in a set of linked sections, e.g. a lecture and one of its labs, as a single unit.
+/// Every section's linking rules must be satisfied by exactly one other section in the set, and the student is registered in all of them or none.
+pub async fn enroll_with_components(
+    student_id: Uuid,
+    offering_ids: &[Uuid],
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<Registration>, Error> {
+    let action = format!("Enroll in linked offerings {:?}", offering_ids);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let registrations = enroll_linked(&mut tx, student_id, offering_ids, actor.user_id).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(registrations)
+    })
+    .await
+}
+
+/// Registers a student in a set of linked sections on an existing connection, for callers that manage their own transaction.
+/// Performs the same checks as `enroll_with_components`; on error the caller must roll back. `actor_id` is the user registering the student.
+pub async fn enroll_linked(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_ids: &[Uuid],
+    actor_id: Uuid,
+) -> Result<Vec<Registration>, Error> {
+    check_components(conn, offering_ids).await?;
+    let mut registrations = Vec::with_capacity(offering_ids.len());
+    for offering_id in offering_ids {
+        registrations.push(
+            enroll_in_transaction(conn, student_id, *offering_id, None, None, actor_id).await?,
+        );
+    }
+    Ok(registrations)
+}
+
+/// Drops a student's registration in a course offering, crediting any refund due under the term's refund schedule.
+/// The student's registrations in sections linked to the offering (e.g. the lab of a dropped lecture) are dropped with it.
+/// A component that another of the student's sections requires cannot be dropped on its own.
+/// Returns `None` if the student was not registered.
+pub async fn drop_course(
+    student_id: Uuid,
+    offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Option<Registration>, Error> {
+    let action = format!("Drop offering {}", offering_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        let mut tx = audit_service::begin(actor, pool).await?;
+        let registration = drop_with_components(&mut tx, student_id, offering_id).await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(registration)
+    })
+    .await
+}
+
+/// Swaps a student's registration in one offering for another in a single transaction.
+/// The dropped offering's seat is only given up if every check for the new offering passes; otherwise the student keeps their original registration.
+/// Because the drop happens first, the new offering may occupy the dropped offering's time slot. Linked components are dropped with the old offering,
+/// so a section that requires components cannot be swapped in directly.
+/// Returns the dropped and the new registration.
+pub async fn swap_registration(
+    student_id: Uuid,
+    drop_offering_id: Uuid,
+    add_offering_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(Registration, Registration), Error> {
+    let action = format!("Swap offering {} for {}", drop_offering_id, add_offering_id);
+    impersonation_service::track_action(actor, &action, pool, async {
+        actor.require_for_student(student_id)?;
+        if drop_offering_id == add_offering_id {
+            return Err(Error::Validation(
+                "Cannot swap an offering for itself.".to_string(),
+            ));
+        }
+
+        let mut tx = audit_service::begin(actor, pool).await?;
+        check_components(&mut tx, &[add_offering_id]).await?;
+        let dropped = drop_with_components(&mut tx, student_id, drop_offering_id)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound("You are not registered in the offering to drop.".to_string())
+            })?;
+        // Returning early rolls back the drop along with everything else.
+        let added = enroll_in_transaction(
+            &mut tx,
+            student_id,
+            add_offering_id,
+            None,
+            None,
+            actor.user_id,
+        )
+        .await?;
+        impersonation_service::record_action(actor, &action, &mut *tx).await?;
+        tx.commit().await?;
+        Ok((dropped, added))
+    })
+    .await
+}
+
+/// Runs every registration check and registers the student. `listed_course_id` is the listing to register under, or `None` for the offering's primary course.
+/// Rules other than holds can be bypassed by an instructor override, which is consumed on behalf of `actor_id` when it is used.
+async fn enroll_in_transaction(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+    listed_course_id: Option<Uuid>,
+    permission_number: Option<&str>,
+    actor_id: Uuid,
+) -> Result<Registration, Error> {
+    let listed_course_id = match listed_course_id {
+        Some(course_id) => course_id,
+        None => sqlx::query_scalar!(
+            r#"
+            SELECT course_id FROM course_offerings WHERE id = $1
+            "#,
+            offering_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| Error::NotFound("Course offering does not exist.".to_string()))?,
+    };
+
+    check_holds(conn, student_id).await?;
+    let consent = check_consent(conn, offering_id).await;
+    allow_override(
+        conn,
+        consent,
+        student_id,
+        offering_id,
+        OverrideRule::Consent,
+        permission_number,
+        actor_id,
+    )
+    .await?;
+    let prerequisites = check_prerequisites(conn, student_id, offering_id).await;
+    allow_override(
+        conn,
+        prerequisites,
+        student_id,
+        offering_id,
+        OverrideRule::Prerequisite,
+        permission_number,
+        actor_id,
+    )
+    .await?;
+    let conflicts = check_time_conflicts(conn, student_id, offering_id).await;
+    allow_override(
+        conn,
+        conflicts,
+        student_id,
+        offering_id,
+        OverrideRule::TimeConflict,
+        permission_number,
+        actor_id,
+    )
+    .await?;
+    check_credit_limit(conn, student_id, offering_id).await?;
+    let capacity = match check_capacity(conn, student_id, offering_id).await {
+        Ok(()) => check_listing_capacity(conn, offering_id, listed_course_id).await,
+        Err(e) => Err(e),
+    };
+    allow_override(
+        conn,
+        capacity,
+        student_id,
+        offering_id,
+        OverrideRule::Capacity,
+        permission_number,
+        actor_id,
+    )
+    .await?;
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO registrations (id, student_id, offering_id, status, listed_course_id)
+        VALUES ($1, $2, $3, 'registered', $4)
+        ON CONFLICT (student_id, offering_id)
+        DO UPDATE SET status = 'registered', registered_at = now(), listed_course_id = $4
+        WHERE registrations.status <> 'registered'
+        RETURNING id, student_id, offering_id, listed_course_id, registered_at, status AS "status: RegistrationStatus", grade AS "grade: Grade"
+        "#,
+        Uuid::new_v4(),
+        student_id,
+        offering_id,
+        listed_course_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| {
+        Error::Conflict("Student is already registered in this offering.".to_string())
+    })?;
+
+    billing_service::charge_registration(conn, row.id).await?;
+
+    Ok(Registration {
+        id: row.id,
+        student_id: row.student_id,
+        offering_id: row.offering_id,
+        listed_course_id: row.listed_course_id,
+        registered_at: row.registered_at,
+        status: row.status,
+        grade: row.grade,
+    })
+}
+
+async fn drop_with_components(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<Option<Registration>, Error> {
+    let required_by = sqlx::query!(
+        r#"
+        SELECT c.course_number, co.section_number
+        FROM section_link_options o
+        JOIN section_link_groups g ON o.group_id = g.id
+        JOIN registrations r ON r.offering_id = g.offering_id
+        JOIN course_offerings co ON g.offering_id = co.id
+        JOIN courses c ON co.course_id = c.id
+        WHERE o.linked_offering_id = $1 AND r.student_id = $2 AND r.status = 'registered'
+        "#,
+        offering_id,
+        student_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(section) = required_by {
+        return Err(Error::Conflict(format!(
+            "This section is required by {} {}; drop that section instead.",
+            section.course_number, section.section_number
+        )));
+    }
+
+    let Some(registration) = drop_in_transaction(conn, student_id, offering_id).await? else {
+        return Ok(None);
+    };
+
+    let components = sqlx::query_scalar!(
+        r#"
+        SELECT r.offering_id AS "offering_id!"
+        FROM section_link_groups g
+        JOIN section_link_options o ON o.group_id = g.id
+        JOIN registrations r ON r.offering_id = o.linked_offering_id
+        WHERE g.offering_id = $1 AND r.student_id = $2 AND r.status = 'registered'
+        "#,
+        offering_id,
+        student_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for component in components {
+        drop_in_transaction(conn, student_id, component).await?;
+    }
+
+    Ok(Some(registration))
+}
+
+async fn drop_in_transaction(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<Option<Registration>, Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE registrations
+        SET status = 'dropped'
+        WHERE student_id = $1 AND offering_id = $2 AND status = 'registered'
+        RETURNING id, student_id, offering_id, listed_course_id, registered_at, status AS "status: RegistrationStatus", grade AS "grade: Grade"
+        "#,
+        student_id,
+        offering_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(ref row) = row {
+        billing_service::refund_registration(conn, row.id, Utc::now().date_naive()).await?;
+    }
+
+    Ok(row.map(|row| Registration {
+        id: row.id,
+        student_id: row.student_id,
+        offering_id: row.offering_id,
+        listed_course_id: row.listed_course_id,
+        registered_at: row.registered_at,
+        status: row.status,
+        grade: row.grade,
+    }))
+}
+
+/// Refuses a set of sections unless every linking rule of every section in the set is satisfied by exactly one other section in the set.
+async fn check_components(conn: &mut PgConnection, offering_ids: &[Uuid]) -> Result<(), Error> {
+    let unsatisfied = sqlx::query!(
+        r#"
+        SELECT c.course_number, co.section_number, g.section_type, COUNT(o.linked_offering_id) AS "chosen!"
+        FROM section_link_groups g
+        JOIN course_offerings co ON g.offering_id = co.id
+        JOIN courses c ON co.course_id = c.id
+        LEFT JOIN section_link_options o ON o.group_id = g.id AND o.linked_offering_id = ANY($1)
+        WHERE g.offering_id = ANY($1)
+        GROUP BY g.id, c.course_number, co.section_number, g.section_type
+        HAVING COUNT(o.linked_offering_id) <> 1
+        ORDER BY c.course_number, co.section_number
+        "#,
+        offering_ids
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match unsatisfied {
+        Some(section) if section.chosen == 0 => Err(Error::Validation(format!(
+            "{} {} requires a {} section.",
+            section.course_number, section.section_number, section.section_type
+        ))),
+        Some(section) => Err(Error::Validation(format!(
+            "{} {} allows only one {} section.",
+            section.course_number, section.section_number, section.section_type
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Passes through the result of a rule check, unless the rule failed and the student has an override for it, in which case the override is consumed.
+/// Database errors are never overridden.
+async fn allow_override(
+    conn: &mut PgConnection,
+    check: Result<(), Error>,
+    student_id: Uuid,
+    offering_id: Uuid,
+    rule: OverrideRule,
+    permission_number: Option<&str>,
+    actor_id: Uuid,
+) -> Result<(), Error> {
+    match check {
+        Err(Error::Conflict(reason)) => {
+            if override_service::consume_override(
+                conn,
+                student_id,
+                offering_id,
+                rule,
+                permission_number,
+                actor_id,
+            )
+            .await?
+            {
+                Ok(())
+            } else {
+                Err(Error::Conflict(reason))
+            }
+        }
+        other => other,
+    }
+}
+
+/// Refuses registration in courses that require instructor consent.
+async fn check_consent(conn: &mut PgConnection, offering_id: Uuid) -> Result<(), Error> {
+    let requires_consent = sqlx::query_scalar!(
+        r#"
+        SELECT c.requires_consent
+        FROM course_offerings co
+        JOIN courses c ON co.course_id = c.id
+        WHERE co.id = $1
+        "#,
+        offering_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if requires_consent {
+        return Err(Error::Conflict(
+            "This course requires instructor consent.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Refuses registration if any meeting time of the offering overlaps a meeting time of another offering the student is registered in that term.
+async fn check_time_conflicts(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<(), Error> {
+    let conflict = sqlx::query!(
+        r#"
+        SELECT c.course_number, co.section_number
+        FROM course_meeting_times new
+        JOIN course_offerings target ON target.id = new.offering_id
+        JOIN registrations r ON r.student_id = $2 AND r.status = 'registered' AND r.offering_id <> $1
+        JOIN course_offerings co ON co.id = r.offering_id AND co.term_id = target.term_id
+        JOIN course_meeting_times existing ON existing.offering_id = co.id
+            AND existing.day_of_week = new.day_of_week
+            AND existing.start_time < new.end_time
+            AND new.start_time < existing.end_time
+        JOIN courses c ON co.course_id = c.id
+        WHERE new.offering_id = $1
+        LIMIT 1
+        "#,
+        offering_id,
+        student_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(conflict) = conflict {
+        return Err(Error::Conflict(format!(
+            "Time conflict with {} {}.",
+            conflict.course_number, conflict.section_number
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses registration if it would take the student over the term's credit limit.
+/// Each course is counted once, so a lecture and its lab only count the course's credits.
+async fn check_credit_limit(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<(), Error> {
+    let load = sqlx::query!(
+        r#"
+        SELECT t.max_credits, COALESCE(SUM(c.credits), 0)::INT AS "credits!"
+        FROM course_offerings target
+        JOIN terms t ON t.id = target.term_id
+        LEFT JOIN courses c ON c.id IN (
+            SELECT co.course_id
+            FROM registrations r
+            JOIN course_offerings co ON co.id = r.offering_id
+            WHERE r.student_id = $2 AND r.status = 'registered' AND co.term_id = target.term_id
+            UNION
+            SELECT target.course_id
+        )
+        WHERE target.id = $1
+        GROUP BY t.max_credits
+        "#,
+        offering_id,
+        student_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if load.credits > load.max_credits {
+        return Err(Error::Conflict(format!(
+            "Registering would exceed the term's limit of {} credits.",
+            load.max_credits
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses registration if the student has any active hold, reporting the reason of the oldest one.
+async fn check_holds(conn: &mut PgConnection, student_id: Uuid) -> Result<(), Error> {
+    let holds = get_active_holds(student_id, &mut *conn).await?;
+    match holds.first() {
+        Some(hold) => Err(Error::Conflict(format!("Registration blocked by {}", hold))),
+        None => Ok(()),
+    }
+}
+
+/// Refuses registration if the student does not have credit for every prerequisite of the offering's course.
+/// Credit may come from a course passed here, from transfer credit, or from any course cross-listed with the prerequisite.
+async fn check_prerequisites(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<(), Error> {
+    let credited = transcript_service::get_credited_course_ids(student_id, &mut *conn).await?;
+    let missing = sqlx::query_scalar!(
+        r#"
+        SELECT c.course_number
+        FROM course_offerings co
+        JOIN course_prerequisites cp ON cp.course_id = co.course_id
+        JOIN courses c ON cp.prerequisite_id = c.id
+        WHERE co.id = $1
+        AND NOT (cp.prerequisite_id = ANY($2))
+        AND NOT EXISTS (
+            SELECT 1 FROM course_cross_listings x
+            WHERE x.course_id = cp.prerequisite_id AND x.equivalent_course_id = ANY($2)
+        )
+        ORDER BY c.course_number
+        "#,
+        offering_id,
+        &credited
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if !missing.is_empty() {
+        return Err(Error::Conflict(format!(
+            "Missing prerequisites: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses registration if the offering has no seats left for this student. Seats held by active reservations the student does not qualify for are not available to them.
+/// The offering row is locked so concurrent registrations cannot overfill it.
+async fn check_capacity(
+    conn: &mut PgConnection,
+    student_id: Uuid,
+    offering_id: Uuid,
+) -> Result<(), Error> {
+    let capacity = sqlx::query_scalar!(
+        r#"
+        SELECT capacity FROM course_offerings WHERE id = $1 FOR UPDATE
+        "#,
+        offering_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| Error::NotFound("Course offering does not exist.".to_string()))?;
+
+    let registered = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM registrations WHERE offering_id = $1 AND status = 'registered'
+        "#,
+        offering_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if registered >= capacity as i64 {
+        return Err(Error::Conflict("Course offering is full.".to_string()));
+    }
+
+    // Unfilled seats of each active reservation the student does not match.
+    let held = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(GREATEST(sr.seats - (
+            SELECT COUNT(*)
+            FROM registrations r
+            JOIN student_profiles sp ON sp.user_id = r.student_id
+            WHERE r.offering_id = sr.offering_id
+            AND r.status = 'registered'
+            AND (sr.program_id IS NULL OR EXISTS (
+                SELECT 1 FROM program_declarations pd
+                WHERE pd.student_id = r.student_id AND pd.program_id = sr.program_id AND pd.ended_on IS NULL
+            ))
+            AND (sr.enrollment_year IS NULL OR sp.enrollment_year = sr.enrollment_year)
+        ), 0)), 0)::BIGINT AS "held!"
+        FROM seat_reservations sr
+        WHERE sr.offering_id = $1
+        AND sr.release_at > now()
+        AND NOT EXISTS (
+            SELECT 1 FROM student_profiles sp
+            WHERE sp.user_id = $2
+            AND (sr.program_id IS NULL OR EXISTS (
+                SELECT 1 FROM program_declarations pd
+                WHERE pd.student_id = sp.user_id AND pd.program_id = sr.program_id AND pd.ended_on IS NULL
+            ))
+            AND (sr.enrollment_year IS NULL OR sp.enrollment_year = sr.enrollment_year)
+        )
+        "#,
+        offering_id,
+        student_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if registered + held >= capacity as i64 {
+        return Err(Error::Conflict(
+            "The remaining seats in this offering are reserved.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Refuses registration under a listing the offering does not have, or whose seat cap has been reached.
+/// Must be called after `check_capacity`, which locks the offering.
+async fn check_listing_capacity(
+    conn: &mut PgConnection,
+    offering_id: Uuid,
+    listed_course_id: Uuid,
+) -> Result<(), Error> {
+    let listing = sqlx::query!(
+        r#"
+        SELECT seat_cap FROM offering_courses WHERE offering_id = $1 AND course_id = $2
+        "#,
+        offering_id,
+        listed_course_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| {
+        Error::NotFound("Course offering is not listed under that course.".to_string())
+    })?;
+
+    let Some(seat_cap) = listing.seat_cap else {
+        return Ok(());
+    };
+
+    let registered = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM registrations
+        WHERE offering_id = $1 AND listed_course_id = $2 AND status = 'registered'
+        "#,
+        offering_id,
+        listed_course_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if registered >= seat_cap as i64 {
+        return Err(Error::Conflict(
+            "No seats are left under this listing.".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use sqlx::{PgPool, types::chrono::Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{impersonation::Impersonation, session::Session},
+    security::{
+        authorization::Actor,
+        tokens::{generate_token, hash_token},
+    },
+    services::mfa_service,
+};
+
+/// Starts a session for a user who has just authenticated. Returns the session and its token, which is the only way to use the session
+/// and cannot be recovered later.
+///
+/// Sessions of users who must use two-factor authentication start pending. They are completed with `mfa_service::complete_login`, and
+/// until then only `authenticate_pending` accepts them.
+pub async fn create_session(user_id: Uuid, pool: &PgPool) -> Result<(Session, String), Error> {
+    let token = generate_token();
+    let mfa_pending = mfa_service::is_mfa_required(user_id, pool).await?;
+    let session = Session::create(user_id, &hash_token(&token), mfa_pending, pool).await?;
+    Ok((session, token))
+}
+
+/// Resolves a session token to the session and the user acting through it, and records the activity for the idle timeout.
+/// Returns `None` if the token is unknown, or the session was revoked, has expired, has been idle too long, or is pending its second
+/// login step. Sessions started by `impersonation_service` resolve to the impersonated user, with the impersonation attached to the actor.
+pub async fn authenticate(token: &str, pool: &PgPool) -> Result<Option<(Session, Actor)>, Error> {
+    resolve(token, false, pool).await
+}
+
+/// Like `authenticate`, but only accepts sessions that are pending their second login step. The actor may only be used to set up
+/// two-factor authentication.
+pub async fn authenticate_pending(
+    token: &str,
+    pool: &PgPool,
+) -> Result<Option<(Session, Actor)>, Error> {
+    resolve(token, true, pool).await
+}
+
+async fn resolve(
+    token: &str,
+    mfa_pending: bool,
+    pool: &PgPool,
+) -> Result<Option<(Session, Actor)>, Error> {
+    let Some(session) = sqlx::query_as!(
+        Session,
+        r#"
+        SELECT id, user_id, created_at, expires_at, last_seen_at, revoked_at, mfa_pending
+        FROM sessions
+        WHERE token_hash = $1
+        "#,
+        hash_token(token)
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let now = Utc::now();
+    if !session.is_valid(now) || session.mfa_pending != mfa_pending {
+        return Ok(None);
+    }
+    let Some(mut actor) = Actor::load(session.user_id, pool).await? else {
+        return Ok(None);
+    };
+    if let Some(impersonation) = Impersonation::active_for_session(session.id, pool).await? {
+        // The impersonation ends with the admin's access.
+        if Actor::load(impersonation.admin_id, pool).await?.is_none() {
+            return Ok(None);
+        }
+        actor.impersonation = Some(impersonation);
+    }
+
+    let session = sqlx::query_as!(
+        Session,
+        r#"
+        UPDATE sessions SET last_seen_at = $2 WHERE id = $1
+        RETURNING id, user_id, created_at, expires_at, last_seen_at, revoked_at, mfa_pending
+        "#,
+        session.id,
+        now
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Some((session, actor)))
+}
+
+/// Ends the session identified by a token, e.g. when the user signs out. Returns `false` if there was no such active session.
+pub async fn revoke_session(token: &str, pool: &PgPool) -> Result<bool, Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE sessions SET revoked_at = now() WHERE token_hash = $1 AND revoked_at IS NULL
+        "#,
+        hash_token(token)
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Ends every session of a user, signing them out everywhere. Users may sign themselves out; doing so for others requires managing users.
+/// Returns the number of sessions revoked.
+pub async fn revoke_all_sessions(
+    user_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<u64, Error> {
+    actor.require_for_account(user_id)?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE sessions SET revoked_at = now() WHERE user_id = $1 AND revoked_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
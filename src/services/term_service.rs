@@ -0,0 +1,36 @@
+use sqlx::{PgPool, types::chrono::NaiveDate};
+
+use crate::{
+    error::Error,
+    models::term::Term,
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
+
+/// Creates a term running from `start_date` to `end_date`. Terms are shared by every department, so department admins may not create them.
+pub async fn create_term(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Term, Error> {
+    actor.require(Permission::ManageOfferings)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let term = Term::create_term(start_date, end_date, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(term)
+}
+
+/// Sets the most credits a student may be registered for in a term.
+pub async fn set_max_credits(
+    term_id: i32,
+    max_credits: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require(Permission::ManageOfferings)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    Term::set_max_credits(term_id, max_credits, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
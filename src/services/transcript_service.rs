@@ -0,0 +1,144 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::models::{
+    course_equivalency::CourseEquivalency, registration::Grade, transfer_credit::TransferCredit,
+};
+
+pub async fn get_transfer_credits(
+    student_id: Uuid,
+    pool: &PgPool,
+) -> Result<Vec<TransferCredit>, sqlx::Error> {
+    let transfer_credits = sqlx::query_as!(
+        TransferCredit,
+        r#"
+        SELECT id, student_id, equivalency_id, external_grade, term_taken, recorded_at
+        FROM transfer_credits
+        WHERE student_id = $1
+        ORDER BY recorded_at
+        "#,
+        student_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(transfer_credits)
+}
+
+pub async fn get_equivalencies_for_institution(
+    institution_id: i32,
+    pool: &PgPool,
+) -> Result<Vec<CourseEquivalency>, sqlx::Error> {
+    let equivalencies = sqlx::query_as!(
+        CourseEquivalency,
+        r#"
+        SELECT id, institution_id, external_course_code, external_title, course_id, elective_department_id, credits
+        FROM course_equivalencies
+        WHERE institution_id = $1
+        ORDER BY external_course_code
+        "#,
+        institution_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(equivalencies)
+}
+
+/// Returns the IDs of every course a student has credit for: courses they passed here, and courses granted through transfer credit.
+/// Generic elective transfer credit does not correspond to a course and is not included.
+pub async fn get_credited_course_ids<'e>(
+    student_id: Uuid,
+    executor: impl PgExecutor<'e>,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let course_ids = sqlx::query_scalar!(
+        r#"
+        SELECT co.course_id AS "course_id!"
+        FROM registrations r
+        JOIN course_offerings co ON r.offering_id = co.id
+        WHERE r.student_id = $1 AND r.status = 'registered' AND r.grade IN ('A', 'B', 'C', 'D')
+        UNION
+        SELECT ce.course_id AS "course_id!"
+        FROM transfer_credits tc
+        JOIN course_equivalencies ce ON tc.equivalency_id = ce.id
+        WHERE tc.student_id = $1 AND ce.course_id IS NOT NULL
+        "#,
+        student_id
+    )
+    .fetch_all(executor)
+    .await?;
+
+    Ok(course_ids)
+}
+
+/// Total credits a student has earned, counting each passed course once however many of its sections were taken, and transfer credit.
+/// Transfer credit for a course counts once however many equivalencies grant it, and not at all if the course was also passed here.
+pub async fn get_earned_credits(student_id: Uuid, pool: &PgPool) -> Result<i64, sqlx::Error> {
+    let credits = sqlx::query_scalar!(
+        r#"
+        WITH passed AS (
+            SELECT co.course_id
+            FROM registrations r
+            JOIN course_offerings co ON r.offering_id = co.id
+            WHERE r.student_id = $1 AND r.status = 'registered' AND r.grade IN ('A', 'B', 'C', 'D')
+        ),
+        transferred AS (
+            SELECT ce.course_id, ce.credits
+            FROM transfer_credits tc
+            JOIN course_equivalencies ce ON tc.equivalency_id = ce.id
+            WHERE tc.student_id = $1
+        )
+        SELECT (
+            COALESCE((
+                SELECT SUM(c.credits) FROM courses c WHERE c.id IN (SELECT course_id FROM passed)
+            ), 0)
+            + COALESCE((
+                SELECT SUM(credits) FROM transferred WHERE course_id IS NULL
+            ), 0)
+            + COALESCE((
+                SELECT SUM(credits) FROM (
+                    SELECT MAX(t.credits) AS credits
+                    FROM transferred t
+                    WHERE t.course_id IS NOT NULL
+                    AND NOT EXISTS (SELECT 1 FROM passed p WHERE p.course_id = t.course_id)
+                    GROUP BY t.course_id
+                ) per_course
+            ), 0)
+        )::BIGINT AS "credits!"
+        "#,
+        student_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(credits)
+}
+
+/// Credit-weighted grade point average over graded courses taken here. Each course counts once per term it was taken in, with the grade
+/// of its lecture if that was graded, so a graded lab does not weigh the course twice. Transfer credit never counts toward GPA.
+/// Returns `None` if the student has no graded courses.
+pub async fn get_gpa(student_id: Uuid, pool: &PgPool) -> Result<Option<f64>, sqlx::Error> {
+    let graded = sqlx::query!(
+        r#"
+        SELECT DISTINCT ON (co.course_id, co.term_id) r.grade AS "grade!: Grade", c.credits
+        FROM registrations r
+        JOIN course_offerings co ON r.offering_id = co.id
+        JOIN courses c ON co.course_id = c.id
+        WHERE r.student_id = $1 AND r.status = 'registered' AND r.grade IS NOT NULL
+        ORDER BY co.course_id, co.term_id, co.section_type <> 'lecture', co.section_number
+        "#,
+        student_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let credits: i32 = graded.iter().map(|row| row.credits).sum();
+    if credits == 0 {
+        return Ok(None);
+    }
+    let points: f64 = graded
+        .into_iter()
+        .map(|row| row.grade.points() * row.credits as f64)
+        .sum();
+    Ok(Some(points / credits as f64))
+}
@@ -0,0 +1,115 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    error::Error,
+    models::{
+        course_equivalency::{CourseEquivalency, Equivalent},
+        external_institution::ExternalInstitution,
+        transfer_credit::TransferCredit,
+    },
+    security::authorization::{Actor, Permission},
+    services::audit_service,
+};
+
+/// Adds an institution that students may bring transfer credit from.
+pub async fn create_institution(
+    name: String,
+    location: Option<String>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<ExternalInstitution, Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let institution = ExternalInstitution::create(name, location, &mut *tx).await?;
+    tx.commit().await?;
+    Ok(institution)
+}
+
+/// Deletes an institution along with its course equivalencies. Fails while any student has transfer credit from it.
+pub async fn delete_institution(
+    institution: &ExternalInstitution,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    institution.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Maps a course at an external institution to one of our courses, or to generic elective credit.
+pub async fn create_equivalency(
+    institution_id: i32,
+    external_course_code: String,
+    external_title: String,
+    equivalent: Equivalent,
+    credits: i32,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<CourseEquivalency, Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let equivalency = CourseEquivalency::create(
+        institution_id,
+        external_course_code,
+        external_title,
+        equivalent,
+        credits,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(equivalency)
+}
+
+/// Deletes a course equivalency. Fails while any student has transfer credit through it.
+pub async fn delete_equivalency(
+    equivalency: &CourseEquivalency,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    equivalency.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Records transfer credit on a student's record for a course they took elsewhere. `external_grade` is kept as reported and never
+/// counts toward GPA.
+pub async fn record_transfer_credit(
+    student_id: Uuid,
+    equivalency_id: i32,
+    external_grade: Option<String>,
+    term_taken: Option<String>,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<TransferCredit, Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let transfer_credit = TransferCredit::create(
+        student_id,
+        equivalency_id,
+        external_grade,
+        term_taken,
+        &mut *tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(transfer_credit)
+}
+
+/// Removes transfer credit from a student's record.
+pub async fn delete_transfer_credit(
+    transfer_credit: &TransferCredit,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(), Error> {
+    actor.require(Permission::ManageTransferCredit)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    transfer_credit.delete(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
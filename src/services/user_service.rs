@@ -1,19 +1,32 @@
-use sqlx::{PgPool, query_scalar};
+use sqlx::{PgPool, types::chrono::Utc};
 use uuid::Uuid;
 
 use crate::{
+    error::Error,
     models::{
-        student_profile::{StudentMajor, StudentProfile},
-        user::{FullName, Role, User},
+        faculty_profile::{FacultyProfile, FacultyTitle},
+        login_attempt::{LoginAttempt, LoginOutcome},
+        student_profile::StudentProfile,
+        user::{FullName, Role, User, normalize_email},
+        user_purge::UserPurge,
     },
-    security::password::{self, validate_password},
+    notifications::Notifier,
+    security::{
+        authentication,
+        authorization::{Actor, Permission},
+        password::{self, PasswordParams},
+        password_policy::PasswordPolicy,
+        throttling::{self, Throttled},
+    },
+    services::{audit_service, email_verification_service},
 };
 
 pub async fn get_user_by_id(id: Uuid, pool: &sqlx::PgPool) -> Result<Option<User>, sqlx::Error> {
     let user = sqlx::query_as!(
         User,
         r#"
-        SELECT id, email, hashed_password, first_name, last_name, role, created_at FROM users WHERE id=$1
+        SELECT id, email, hashed_password, first_name, last_name, role AS "role: Role", created_at, email_verified_at, deactivated_at, deactivated_by
+        FROM users WHERE id=$1
         "#,
         id
     )
@@ -28,7 +41,8 @@ async fn get_user_by_email(email: String, pool: &PgPool) -> Result<Option<User>,
     let user = sqlx::query_as!(
         User,
         r#"
-        SELECT * FROM users WHERE email = $1 LIMIT 1
+        SELECT id, email, hashed_password, first_name, last_name, role AS "role: Role", created_at, email_verified_at, deactivated_at, deactivated_by
+        FROM users WHERE email = $1 LIMIT 1
         "#,
         email
     )
@@ -37,67 +51,416 @@ async fn get_user_by_email(email: String, pool: &PgPool) -> Result<Option<User>,
     Ok(user)
 }
 
-/// The same as `get_user_by_email`, but slightly faster and returns only a String.
-async fn get_user_hash_by_email(
-    email: String,
+/// Looks up an account by email, e.g. to find a student to impersonate. Requires managing users.
+pub async fn find_user_by_email(
+    email: &str,
+    actor: &Actor,
     pool: &PgPool,
-) -> Result<Option<String>, sqlx::Error> {
-    let hash = query_scalar!(
-        r#"
-        SELECT hashed_password FROM users WHERE email = $1 LIMIT 1
-        "#,
-        email
-    )
-    .fetch_optional(pool)
-    .await?;
-
-    Ok(hash)
+) -> Result<Option<User>, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let user = get_user_by_email(normalize_email(email), pool).await?;
+    Ok(user)
 }
+
 /// Registers a new user. This inserts an entry into both the `users` and `student_profiles` tables at the same time.
+/// The password must follow the password policy. The account is pending until the email verification token sent through `notifier`
+/// is redeemed. New students have no declared programs; they declare them with `program_service`.
 pub async fn register_student(
     email: String,
     plaintext_password: String,
     name: FullName,
     enrollment_year: i32,
-    major: StudentMajor,
+    notifier: &dyn Notifier,
     pool: &PgPool,
-) -> Result<(User, StudentProfile), sqlx::Error> {
-    let hashed_password = password::hash_password(&plaintext_password)
-        .expect("Password hashing function should not fail!");
+) -> Result<(User, StudentProfile), Error> {
+    PasswordPolicy::from_env().check(&plaintext_password, &name, &email)?;
+    let hashed_password = password::hash_password(&plaintext_password)?;
     let user = User::create_user(email, hashed_password, name, Role::Student, pool).await?;
-    let student_profile = StudentProfile::create(user.id, enrollment_year, major, pool).await?;
+    let student_profile = StudentProfile::create(user.id, enrollment_year, pool).await?;
+    email_verification_service::send_verification(user.id, notifier, pool).await?;
     Ok((user, student_profile))
 }
 
-/// Registers a new admin. Since admins are only referenced in the `users` table, this just hashes the plaintext and wraps the `create_user` function.
+/// Registers a new admin. Admins are only referenced in the `users` table, unless `department_id` limits them to administering one department.
+/// Like students, new admins are pending until they verify their email.
+#[allow(clippy::too_many_arguments)]
 pub async fn register_admin(
     email: String,
     plaintext_password: String,
     name: FullName,
+    department_id: Option<i32>,
+    notifier: &dyn Notifier,
+    actor: &Actor,
     pool: &PgPool,
-) -> Result<User, sqlx::Error> {
-    let hashed_password = password::hash_password(&plaintext_password)
-        .expect("Password hashing function should not fail!");
-    let user = User::create_user(email, hashed_password, name, Role::Admin, pool).await?;
+) -> Result<User, Error> {
+    actor.require(Permission::ManageUsers)?;
+    PasswordPolicy::from_env().check(&plaintext_password, &name, &email)?;
+    let hashed_password = password::hash_password(&plaintext_password)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let user = User::create_user(email, hashed_password, name, Role::Admin, &mut *tx).await?;
+    if let Some(department_id) = department_id {
+        sqlx::query!(
+            r#"
+            INSERT INTO admin_scopes (user_id, department_id) VALUES ($1, $2)
+            "#,
+            user.id,
+            department_id
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    email_verification_service::send_verification(user.id, notifier, pool).await?;
     Ok(user)
 }
 
-/// Attempts to login a user based on email and plaintext password. Will return `false` if the user does not exist or the password was incorrect.
+/// Registers a new instructor. This inserts an entry into both the `users` and `faculty_profiles` tables.
+/// Office and office hours can be set afterwards with `faculty_service::set_office`. Like students, new instructors are pending until they
+/// verify their email.
+#[allow(clippy::too_many_arguments)]
+pub async fn register_instructor(
+    email: String,
+    plaintext_password: String,
+    name: FullName,
+    department_id: i32,
+    title: FacultyTitle,
+    max_teaching_load: i32,
+    notifier: &dyn Notifier,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<(User, FacultyProfile), Error> {
+    actor.require(Permission::ManageUsers)?;
+    PasswordPolicy::from_env().check(&plaintext_password, &name, &email)?;
+    let hashed_password = password::hash_password(&plaintext_password)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let user = User::create_user(email, hashed_password, name, Role::Instructor, &mut *tx).await?;
+    let faculty_profile =
+        FacultyProfile::create(user.id, department_id, title, max_teaching_load, &mut *tx).await?;
+    tx.commit().await?;
+    email_verification_service::send_verification(user.id, notifier, pool).await?;
+    Ok((user, faculty_profile))
+}
+
+/// Attempts to login a user based on email and plaintext password from `source`, e.g. the client's address. Returns the user if the password
+/// is correct, or `None` otherwise. Callers start a session for the user with `session_service::create_session`.
+///
+/// Unknown emails, wrong passwords and accounts whose stored hash is malformed all give the same result after the same amount of work, so
+/// the response does not reveal which emails have accounts. Given the right password, deactivated accounts fail with
+/// `Error::AccountDeactivated` and accounts pending email verification fail with `Error::EmailNotVerified`.
+///
+/// Every attempt is recorded in the login history. Repeated failures for an email make further attempts wait out an exponential backoff
+/// and eventually lock it; repeated failures from one source throttle that source across all emails. Throttled attempts fail with
+/// `Error::Throttled` without checking the password.
 pub async fn try_login(
-    email: &String,
-    plaintext: &String,
+    email: &str,
+    plaintext: &str,
+    source: &str,
     pool: &sqlx::PgPool,
-) -> Result<bool, sqlx::Error> {
-    let hash = match get_user_hash_by_email(email.to_string(), pool).await? {
-        Some(hash) => hash,
-        None => return Ok(false),
+) -> Result<Option<User>, Error> {
+    let email = &normalize_email(email);
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, email, hashed_password, first_name, last_name, role AS "role: Role", created_at, email_verified_at, deactivated_at, deactivated_by
+        FROM users WHERE email = $1 LIMIT 1
+        "#,
+        email
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let user_id = user.as_ref().map(|user| user.id);
+
+    let source_failures = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!", MIN(attempted_at) AS oldest
+        FROM login_attempts
+        WHERE source = $1 AND outcome = 'invalid' AND attempted_at > $2
+        "#,
+        source,
+        now - throttling::SOURCE_WINDOW
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if source_failures.count >= throttling::SOURCE_FAILURE_LIMIT {
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Throttled, &mut *tx).await?;
+        tx.commit().await?;
+        let oldest = source_failures.oldest.unwrap_or(now);
+        return Err(Throttled {
+            retry_after: oldest + throttling::SOURCE_WINDOW,
+        }
+        .into());
+    }
+
+    // Throttle rows are kept for every email tried, including ones with no account, so rows that have neither failed nor been locked for a
+    // lockout's length are forgotten. Rows other attempts are using are left for a later sweep.
+    sqlx::query!(
+        r#"
+        DELETE FROM login_throttles
+        WHERE email IN (
+            SELECT email FROM login_throttles
+            WHERE (last_failed_at IS NULL OR last_failed_at < $1)
+            AND (locked_until IS NULL OR locked_until < $2)
+            FOR UPDATE SKIP LOCKED
+        )
+        "#,
+        now - throttling::LOCKOUT_DURATION,
+        now
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Lock the email's throttle row so concurrent attempts are counted one at a time.
+    sqlx::query!(
+        r#"
+        INSERT INTO login_throttles (email) VALUES ($1) ON CONFLICT (email) DO NOTHING
+        "#,
+        email
+    )
+    .execute(&mut *tx)
+    .await?;
+    let throttle = sqlx::query!(
+        r#"
+        SELECT failed_count, last_failed_at, locked_until
+        FROM login_throttles
+        WHERE email = $1
+        FOR UPDATE
+        "#,
+        email
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if let Some(locked_until) = throttle.locked_until.filter(|until| *until > now) {
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Locked, &mut *tx).await?;
+        tx.commit().await?;
+        return Err(Throttled {
+            retry_after: locked_until,
+        }
+        .into());
+    }
+    if let (Some(backoff), Some(last_failed_at)) = (
+        throttling::backoff(throttle.failed_count),
+        throttle.last_failed_at,
+    ) && now < last_failed_at + backoff
+    {
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Throttled, &mut *tx).await?;
+        tx.commit().await?;
+        return Err(Throttled {
+            retry_after: last_failed_at + backoff,
+        }
+        .into());
+    }
+
+    // A malformed stored hash can never match, so the account is refused like any other failure.
+    let valid = authentication::verify_credentials(
+        plaintext,
+        user.as_ref().map(|user| user.hashed_password.as_str()),
+    )
+    .unwrap_or(false);
+
+    let Some(user) = user.filter(|_| valid) else {
+        // Reaching the lockout threshold locks the email and starts the count over for when the lock ends.
+        let failed_count = throttle.failed_count + 1;
+        let (failed_count, locked_until) = if failed_count >= throttling::LOCKOUT_THRESHOLD {
+            (0, Some(now + throttling::LOCKOUT_DURATION))
+        } else {
+            (failed_count, None)
+        };
+        sqlx::query!(
+            r#"
+            UPDATE login_throttles
+            SET failed_count = $2, last_failed_at = $3, locked_until = $4
+            WHERE email = $1
+            "#,
+            email,
+            failed_count,
+            now,
+            locked_until
+        )
+        .execute(&mut *tx)
+        .await?;
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Invalid, &mut *tx).await?;
+        tx.commit().await?;
+        return Ok(None);
     };
 
-    let valid = validate_password(&plaintext, &hash).unwrap_or(false);
+    sqlx::query!(
+        r#"
+        DELETE FROM login_throttles WHERE email = $1
+        "#,
+        email
+    )
+    .execute(&mut *tx)
+    .await?;
+    if !user.is_active() {
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Deactivated, &mut *tx).await?;
+        tx.commit().await?;
+        return Err(Error::AccountDeactivated);
+    }
+    // Upgrade hashes made under older parameters while the password is at hand.
+    let params = PasswordParams::current();
+    if password::needs_rehash(&user.hashed_password, &params).unwrap_or(false) {
+        let rehashed = password::hash_password_with(plaintext, &params)?;
+        sqlx::query!(
+            "UPDATE users SET hashed_password = $2 WHERE id = $1",
+            user.id,
+            rehashed
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    if !user.is_verified() {
+        LoginAttempt::record(email, user_id, source, LoginOutcome::Unverified, &mut *tx).await?;
+        tx.commit().await?;
+        return Err(Error::EmailNotVerified);
+    }
+    LoginAttempt::record(email, user_id, source, LoginOutcome::Success, &mut *tx).await?;
+    tx.commit().await?;
+
+    Ok(Some(user))
+}
 
-    if !valid {
+/// Clears an account's failed login attempts, including wrong two-factor codes, and lifts any lockout.
+pub async fn unlock_account(user_id: Uuid, actor: &Actor, pool: &PgPool) -> Result<(), Error> {
+    actor.require(Permission::ManageUsers)?;
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM login_throttles
+        WHERE email = (SELECT email FROM users WHERE id = $1)
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query!(
+        r#"
+        UPDATE mfa_enrollments SET failed_attempts = 0, locked_until = NULL WHERE user_id = $1
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Returns a user's login history, newest first. Users may view their own history; viewing others' requires managing users.
+pub async fn get_login_history(
+    user_id: Uuid,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<Vec<LoginAttempt>, Error> {
+    if actor.user_id != user_id {
+        actor.require(Permission::ManageUsers)?;
+    }
+    let history = LoginAttempt::for_user(user_id, pool).await?;
+    Ok(history)
+}
+
+/// Deactivates an account instead of deleting it. The user keeps their records, such as registrations and grades, but is signed out
+/// everywhere, can no longer sign in and no longer appears among active users. Admins cannot deactivate their own account.
+/// Returns `false` if there is no such active account.
+pub async fn deactivate_user(user_id: Uuid, actor: &Actor, pool: &PgPool) -> Result<bool, Error> {
+    actor.require(Permission::ManageUsers)?;
+    if actor.user_id == user_id {
+        return Err(Error::Conflict(
+            "You cannot deactivate your own account.".to_string(),
+        ));
+    }
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET deactivated_at = now(), deactivated_by = $2
+        WHERE id = $1 AND deactivated_at IS NULL
+        "#,
+        user_id,
+        actor.user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
         return Ok(false);
     }
+    sqlx::query!(
+        r#"
+        UPDATE sessions SET revoked_at = now() WHERE user_id = $1 AND revoked_at IS NULL
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
 
     Ok(true)
 }
+
+/// Lets a deactivated user sign in again. Returns `false` if there is no such deactivated account.
+pub async fn reactivate_user(user_id: Uuid, actor: &Actor, pool: &PgPool) -> Result<bool, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET deactivated_at = NULL, deactivated_by = NULL
+        WHERE id = $1 AND deactivated_at IS NOT NULL
+        "#,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Permanently deletes a deactivated account along with its academic history, such as registrations, holds and ledger entries.
+/// This cannot be undone, so it needs a reason, which is recorded with the purge. Fails while other records still refer to the user,
+/// e.g. sections they teach or holds they placed.
+pub async fn purge_user(
+    user_id: Uuid,
+    reason: &str,
+    actor: &Actor,
+    pool: &PgPool,
+) -> Result<UserPurge, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let mut tx = audit_service::begin(actor, pool).await?;
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, email, hashed_password, first_name, last_name, role AS "role: Role", created_at, email_verified_at, deactivated_at, deactivated_by
+        FROM users WHERE id = $1
+        FOR UPDATE
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| Error::NotFound("User does not exist.".to_string()))?;
+    if user.is_active() {
+        return Err(Error::Conflict(
+            "Only deactivated accounts can be purged.".to_string(),
+        ));
+    }
+
+    let purge = UserPurge::record(&user, reason, actor.user_id, &mut *tx).await?;
+    // Student profiles are the only records of a user that do not cascade.
+    sqlx::query!("DELETE FROM student_profiles WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM users WHERE id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(purge)
+}
+
+/// Returns the record of every purged account, newest first.
+pub async fn get_purges(actor: &Actor, pool: &PgPool) -> Result<Vec<UserPurge>, Error> {
+    actor.require(Permission::ManageUsers)?;
+    let purges = UserPurge::all(pool).await?;
+    Ok(purges)
+}
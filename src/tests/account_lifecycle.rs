@@ -0,0 +1,168 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_deactivate_and_reactivate(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::login_attempt::LoginOutcome;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::services::{email_verification_service, session_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let (user, _profile) = user_service::register_student(
+        "dana@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Dana", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+    let student = Actor::load(user.id, &pool)
+        .await?
+        .expect("Registered student should exist");
+    let (_session, token) = session_service::create_session(user.id, &pool).await?;
+
+    let refused = user_service::deactivate_user(user.id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let own = user_service::deactivate_user(admin_id, &admin, &pool).await;
+    assert!(own.is_err(), "Admins should not deactivate themselves");
+
+    assert!(user_service::deactivate_user(user.id, &admin, &pool).await?);
+    assert!(!user_service::deactivate_user(user.id, &admin, &pool).await?);
+
+    // The account is kept, but signed out and unable to sign in again.
+    let kept = user_service::get_user_by_id(user.id, &pool)
+        .await?
+        .expect("Deactivated accounts should be kept");
+    assert!(!kept.is_active());
+    assert_eq!(kept.deactivated_by, Some(admin_id));
+    assert!(
+        session_service::authenticate(&token, &pool)
+            .await?
+            .is_none()
+    );
+    assert!(Actor::load(user.id, &pool).await?.is_none());
+    let login = user_service::try_login("dana@example.edu", "correct horse", "test", &pool).await;
+    assert!(matches!(login, Err(Error::AccountDeactivated)));
+    let wrong = user_service::try_login("dana@example.edu", "wrong", "test", &pool).await?;
+    assert!(wrong.is_none());
+    let history = user_service::get_login_history(user.id, &admin, &pool).await?;
+    assert!(
+        history
+            .iter()
+            .any(|attempt| attempt.outcome == LoginOutcome::Deactivated)
+    );
+
+    assert!(user_service::reactivate_user(user.id, &admin, &pool).await?);
+    assert!(!user_service::reactivate_user(user.id, &admin, &pool).await?);
+    let login = user_service::try_login("dana@example.edu", "correct horse", "test", &pool)
+        .await?
+        .expect("A reactivated account should log in");
+    assert!(login.is_active());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_deactivated_instructors_leave_active_lists(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::security::authorization::Actor;
+    use crate::services::{faculty_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let instructor = sqlx::query!(
+        "SELECT user_id, department_id FROM faculty_profiles fp JOIN users u ON u.id = fp.user_id WHERE u.email = 'instructor@example.edu'"
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let listed = |faculty: &[crate::models::faculty_profile::FacultyProfile]| {
+        faculty
+            .iter()
+            .any(|profile| profile.user_id == instructor.user_id)
+    };
+    let faculty =
+        faculty_service::get_faculty_for_department(instructor.department_id, &pool).await?;
+    assert!(listed(&faculty));
+
+    user_service::deactivate_user(instructor.user_id, &admin, &pool).await?;
+    let faculty =
+        faculty_service::get_faculty_for_department(instructor.department_id, &pool).await?;
+    assert!(!listed(&faculty));
+    // Their profile is kept for when they are reactivated.
+    assert!(
+        faculty_service::get_faculty_profile(instructor.user_id, &pool)
+            .await?
+            .is_some()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_purge_is_audited(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::security::authorization::Actor;
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+
+    // Active accounts must be deactivated first, and a purge needs a reason.
+    assert!(
+        user_service::purge_user(student_id, "Requested erasure", &admin, &pool)
+            .await
+            .is_err()
+    );
+    user_service::deactivate_user(student_id, &admin, &pool).await?;
+    assert!(
+        user_service::purge_user(student_id, "  ", &admin, &pool)
+            .await
+            .is_err()
+    );
+
+    let purge = user_service::purge_user(student_id, "Requested erasure", &admin, &pool).await?;
+    assert_eq!(purge.user_id, student_id);
+    assert_eq!(purge.email, "student@example.edu");
+    assert_eq!(purge.purged_by, Some(admin_id));
+    assert!(
+        user_service::get_user_by_id(student_id, &pool)
+            .await?
+            .is_none()
+    );
+
+    let purges = user_service::get_purges(&admin, &pool).await?;
+    assert_eq!(purges.len(), 1);
+    assert_eq!(purges[0].reason, "Requested erasure");
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_changes_are_logged_with_their_actor(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::audit_entry::{AuditAction, AuditFilter};
+    use crate::security::authorization::Actor;
+    use crate::services::{audit_service, course_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let mut course = course_service::create_course(
+        1,
+        "COSC404".to_string(),
+        "Database Systems".to_string(),
+        None,
+        3,
+        &admin,
+        &pool,
+    )
+    .await?;
+    course_service::set_requires_consent(&mut course, true, &admin, &pool).await?;
+    course_service::delete_course(&course, &admin, &pool).await?;
+
+    let filter = AuditFilter {
+        entity: Some("courses".to_string()),
+        entity_id: Some(course.id.to_string()),
+        ..Default::default()
+    };
+    let entries = audit_service::search(&filter, &admin, &pool).await?;
+    let actions: Vec<AuditAction> = entries.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::Delete,
+            AuditAction::Update,
+            AuditAction::Create
+        ]
+    );
+    assert!(entries.iter().all(|entry| entry.actor_id == Some(admin_id)));
+    assert!(entries.iter().all(|entry| entry.impersonator_id.is_none()));
+
+    let (deleted, updated, created) = (&entries[0], &entries[1], &entries[2]);
+    assert!(created.before.is_none());
+    assert!(created.after.as_ref().unwrap().contains("COSC404"));
+    assert!(
+        updated
+            .before
+            .as_ref()
+            .unwrap()
+            .contains(r#""requires_consent": false"#)
+    );
+    assert!(
+        updated
+            .after
+            .as_ref()
+            .unwrap()
+            .contains(r#""requires_consent": true"#)
+    );
+    assert!(deleted.before.is_some());
+    assert!(deleted.after.is_none());
+
+    // Filtering by actor finds the admin's changes and nobody else's.
+    let by_admin = AuditFilter {
+        actor_id: Some(admin_id),
+        ..Default::default()
+    };
+    let admin_entries = audit_service::search(&by_admin, &admin, &pool).await?;
+    assert!(admin_entries.iter().any(|entry| entry.id == created.id));
+    assert!(
+        admin_entries
+            .iter()
+            .all(|entry| entry.actor_id == Some(admin_id))
+    );
+    let older = AuditFilter {
+        before_id: Some(updated.id),
+        ..filter
+    };
+    let older_entries = audit_service::search(&older, &admin, &pool).await?;
+    assert_eq!(older_entries.len(), 1);
+    assert_eq!(older_entries[0].id, created.id);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_audit_log_is_append_only_and_restricted(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::audit_entry::{AuditAction, AuditFilter};
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::services::{audit_service, email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let (user, _profile) = user_service::register_student(
+        "erin@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Erin", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+    let student = Actor::load(user.id, &pool)
+        .await?
+        .expect("Registered student should exist");
+
+    let refused = audit_service::search(&AuditFilter::default(), &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+
+    // Self-registration has no actor, and password hashes never reach the log.
+    let filter = AuditFilter {
+        entity: Some("users".to_string()),
+        entity_id: Some(user.id.to_string()),
+        ..Default::default()
+    };
+    let entries = audit_service::search(&filter, &admin, &pool).await?;
+    let created = entries
+        .iter()
+        .find(|entry| entry.action == AuditAction::Create)
+        .expect("Registration should be logged");
+    assert!(created.actor_id.is_none());
+    assert!(created.after.as_ref().unwrap().contains("erin@example.edu"));
+    assert!(entries.iter().all(|entry| {
+        [&entry.before, &entry.after]
+            .into_iter()
+            .flatten()
+            .all(|row| !row.contains("hashed_password"))
+    }));
+
+    let update = sqlx::query!("UPDATE audit_log SET actor_id = $1", admin_id)
+        .execute(&pool)
+        .await;
+    assert!(update.is_err());
+    let delete = sqlx::query!("DELETE FROM audit_log").execute(&pool).await;
+    assert!(delete.is_err());
+    assert_eq!(
+        audit_service::search(&filter, &admin, &pool).await?.len(),
+        entries.len()
+    );
+
+    Ok(())
+}
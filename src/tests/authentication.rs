@@ -0,0 +1,58 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[test]
+fn test_verify_credentials_with_unknown_and_malformed_hashes() {
+    use crate::security::authentication::verify_credentials;
+    use crate::security::password::{PasswordError, hash_password};
+
+    let hash = hash_password("correct horse").expect("Hashing should succeed");
+    assert!(verify_credentials("correct horse", Some(&hash)).expect("Hash is well formed"));
+    assert!(!verify_credentials("battery staple", Some(&hash)).expect("Hash is well formed"));
+    assert!(!verify_credentials("correct horse", None).expect("Dummy hash is well formed"));
+    assert!(matches!(
+        verify_credentials("correct horse", Some("not a hash")),
+        Err(PasswordError::MalformedHash(_))
+    ));
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_malformed_stored_hash_refuses_login(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    // The seed accounts store placeholder strings rather than real hashes.
+    let refused =
+        user_service::try_login("student@example.edu", "hashed_student_pw", "test", &pool).await?;
+    assert!(refused.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_unknown_email_is_throttled_like_an_account(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::login_attempt::LoginOutcome;
+    use crate::security::throttling::BACKOFF_THRESHOLD;
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    for _ in 0..BACKOFF_THRESHOLD {
+        let attempt = user_service::try_login("nobody@example.edu", "wrong", "test", &pool).await?;
+        assert!(attempt.is_none());
+    }
+    let backed_off = user_service::try_login("nobody@example.edu", "wrong", "test", &pool).await;
+    assert!(matches!(backed_off, Err(Error::Throttled(_))));
+
+    let outcomes = sqlx::query_scalar!(
+        r#"SELECT outcome AS "outcome: LoginOutcome" FROM login_attempts WHERE email = 'nobody@example.edu' ORDER BY attempted_at"#
+    )
+    .fetch_all(&pool)
+    .await?;
+    assert_eq!(outcomes.len(), 4);
+    assert_eq!(outcomes[3], LoginOutcome::Throttled);
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_department_scoped_admin(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::course_meeting_time::Weekday;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::{Actor, Permission};
+    use crate::services::{course_service, department_service, term_service, user_service};
+    use chrono::{NaiveDate, NaiveTime};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let math_admin = user_service::register_admin(
+        "math.admin@example.edu".to_string(),
+        "correct horse battery staple".to_string(),
+        FullName::new("Maya", "Admin"),
+        Some(2),
+        &notifier,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let math_admin = Actor::load(math_admin.id, &pool)
+        .await?
+        .expect("Math admin should exist");
+    assert_eq!(math_admin.department_id, Some(2));
+
+    course_service::create_course(
+        2,
+        "MATH201".to_string(),
+        "Linear Algebra".to_string(),
+        None,
+        3,
+        &math_admin,
+        &pool,
+    )
+    .await?;
+
+    let other_department = course_service::create_course(
+        1,
+        "CS201".to_string(),
+        "Systems Programming".to_string(),
+        None,
+        3,
+        &math_admin,
+        &pool,
+    )
+    .await;
+    match other_department {
+        Err(Error::Forbidden(forbidden)) => {
+            assert_eq!(forbidden.permission, Permission::ManageCourses);
+            assert_eq!(forbidden.department_id, Some(1));
+        }
+        _ => panic!("A math admin should not manage CS courses."),
+    }
+
+    // Institution-wide permissions are not granted to department admins.
+    let department = department_service::create_department(
+        "PHYS".to_string(),
+        "Physics".to_string(),
+        &math_admin,
+        &pool,
+    )
+    .await;
+    assert!(matches!(department, Err(Error::Forbidden(_))));
+
+    // Terms are shared by every department; meeting times belong to their offering's department.
+    let fall = NaiveDate::from_ymd_opt(2031, 9, 1).unwrap();
+    let winter = NaiveDate::from_ymd_opt(2031, 12, 20).unwrap();
+    let term = term_service::create_term(fall, winter, &math_admin, &pool).await;
+    assert!(matches!(term, Err(Error::Forbidden(_))));
+    let refused = term_service::set_max_credits(1, 12, &math_admin, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    term_service::create_term(fall, winter, &admin, &pool).await?;
+
+    let math_offering =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+    let cs_offering = sqlx::query_scalar!(
+        "SELECT co.id FROM course_offerings co JOIN courses c ON c.id = co.course_id WHERE c.course_number = 'CS101'"
+    )
+    .fetch_one(&pool)
+    .await?;
+    let start = NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+    let end = NaiveTime::from_hms_opt(14, 0, 0).unwrap();
+    let meeting_time = course_service::add_meeting_time(
+        math_offering,
+        Weekday::Friday,
+        start,
+        end,
+        &math_admin,
+        &pool,
+    )
+    .await?;
+    let refused = course_service::add_meeting_time(
+        cs_offering,
+        Weekday::Friday,
+        start,
+        end,
+        &math_admin,
+        &pool,
+    )
+    .await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    course_service::remove_meeting_time(&meeting_time, &math_admin, &pool).await?;
+
+    let actors = sqlx::query_scalar!(
+        "SELECT DISTINCT actor_id FROM audit_log WHERE entity IN ('terms', 'course_meeting_times')"
+    )
+    .fetch_all(&pool)
+    .await?;
+    assert_eq!(actors.len(), 2);
+    assert!(actors.contains(&Some(admin_id)));
+    assert!(actors.contains(&Some(math_admin.user_id)));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_students_register_only_themselves(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::hold::HoldType;
+    use crate::security::authorization::Actor;
+    use crate::services::{hold_service, registration_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let other_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_one(&pool)
+    .await?;
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let for_someone_else =
+        registration_service::enroll(other_id, offering_id, &student, &pool).await;
+    assert!(matches!(for_someone_else, Err(Error::Forbidden(_))));
+    registration_service::enroll(student_id, offering_id, &student, &pool).await?;
+
+    let hold = hold_service::place_hold(
+        other_id,
+        HoldType::Admin,
+        "Students cannot place holds".to_string(),
+        None,
+        &student,
+        &pool,
+    )
+    .await;
+    assert!(matches!(hold, Err(Error::Forbidden(_))));
+
+    Ok(())
+}
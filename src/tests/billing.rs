@@ -0,0 +1,107 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_enroll_charges_and_drop_refunds(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::term_billing::{RefundRule, TermBillingRate, TermFee};
+    use crate::security::authorization::Actor;
+    use crate::services::{billing_service, registration_service};
+    use chrono::{Duration, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+    let today = Utc::now().date_naive();
+
+    TermBillingRate::set(1, 10_000, today + Duration::days(30), &pool).await?;
+    TermFee::create(1, "Technology".to_string(), 5_000, &pool).await?;
+    RefundRule::create(1, today + Duration::days(7), 50, &pool).await?;
+
+    // MATH101 is 3 credits, plus the flat fee.
+    registration_service::enroll(student_id, offering_id, &admin, &pool).await?;
+    assert_eq!(
+        billing_service::get_balance(student_id, &pool).await?,
+        35_000
+    );
+
+    // The student is still registered in CS101, so only tuition is refunded.
+    registration_service::drop_course(student_id, offering_id, &admin, &pool).await?;
+    assert_eq!(
+        billing_service::get_balance(student_id, &pool).await?,
+        20_000
+    );
+
+    // Re-registering only charges what was refunded.
+    registration_service::enroll(student_id, offering_id, &admin, &pool).await?;
+    let statement = billing_service::get_statement(student_id, 1, &pool).await?;
+    assert_eq!(statement.balance_cents, 35_000);
+    assert_eq!(statement.charges_cents, 50_000);
+    assert_eq!(statement.credits_cents, 15_000);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_overdue_balance_places_financial_hold(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::models::hold::HoldType;
+    use crate::models::term_billing::TermBillingRate;
+    use crate::security::authorization::Actor;
+    use crate::services::{billing_service, hold_service, registration_service};
+    use chrono::{Duration, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+    let today = Utc::now().date_naive();
+
+    TermBillingRate::set(1, 10_000, today - Duration::days(1), &pool).await?;
+    registration_service::enroll(student_id, offering_id, &admin, &pool).await?;
+
+    let holds = billing_service::place_overdue_holds(today, &admin, &pool).await?;
+    assert_eq!(holds.len(), 1);
+    assert_eq!(holds[0].hold_type, HoldType::Financial);
+    assert_eq!(holds[0].placed_by, None);
+
+    // An existing financial hold is not duplicated.
+    assert!(
+        billing_service::place_overdue_holds(today, &admin, &pool)
+            .await?
+            .is_empty()
+    );
+    assert_eq!(
+        hold_service::get_active_holds(student_id, &pool)
+            .await?
+            .len(),
+        1
+    );
+
+    Ok(())
+}
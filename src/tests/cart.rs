@@ -0,0 +1,165 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_validate_and_submit_cart(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::cart::Cart;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::security::authorization::Actor;
+    use crate::services::cart_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+    let cs102_id = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'CS102'")
+        .fetch_one(&pool)
+        .await?;
+    // The student has not yet passed CS101.
+    let cs102 = CourseOffering::create(
+        cs102_id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room CS102".to_string(),
+        &pool,
+    )
+    .await?;
+
+    let cart = Cart::get_or_create(student_id, 1, &pool).await?;
+    cart.add_item(math101, &pool).await?;
+    cart.add_item(cs102.id, &pool).await?;
+
+    let validation = cart_service::validate_cart(student_id, 1, &admin, &pool).await?;
+    assert_eq!(validation.len(), 2);
+    assert!(validation[0].is_ok());
+    assert_eq!(validation[1].course_number, "CS102");
+    assert!(!validation[1].is_ok());
+
+    // Validating does not register anything.
+    let registered = sqlx::query_scalar!(
+        "SELECT COUNT(*) AS \"count!\" FROM registrations WHERE student_id = $1 AND status = 'registered'",
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(registered, 1);
+
+    let results = cart_service::submit_cart(student_id, 1, &admin, &pool).await?;
+    assert!(results[0].is_ok());
+    assert!(!results[1].is_ok());
+
+    // Only the refused offering is left in the cart.
+    let remaining = Cart::get_or_create(student_id, 1, &pool)
+        .await?
+        .items(&pool)
+        .await?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].offering_id, cs102.id);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_cart_credit_limit_and_conflicts(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::cart::Cart;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::security::authorization::Actor;
+    use crate::services::{cart_service, term_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_one(&pool)
+    .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let cs101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room CS101'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+    let math101_course =
+        sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'MATH101'")
+            .fetch_one(&pool)
+            .await?;
+    // A second MATH101 section that meets at the same time as CS101.
+    let math101_002 = CourseOffering::create(
+        math101_course,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "002"),
+        30,
+        "Room MATH202".to_string(),
+        &pool,
+    )
+    .await?;
+    sqlx::query!(
+        "INSERT INTO course_meeting_times (offering_id, day_of_week, start_time, end_time) VALUES ($1, 'Monday', '09:30', '10:30')",
+        math101_002.id
+    )
+    .execute(&pool)
+    .await?;
+
+    let cart = Cart::get_or_create(student_id, 1, &pool).await?;
+    cart.add_item(cs101, &pool).await?;
+    cart.add_item(math101_002.id, &pool).await?;
+    let results = cart_service::validate_cart(student_id, 1, &admin, &pool).await?;
+    assert!(results[0].is_ok());
+    assert!(
+        results[1]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("CS101")),
+        "MATH101 002 should conflict with CS101 in the same cart."
+    );
+
+    // CS101 (4 credits) and MATH101 (3 credits) together exceed a 6 credit limit.
+    term_service::set_max_credits(1, 6, &admin, &pool).await?;
+    cart.remove_item(math101_002.id, &pool).await?;
+    cart.add_item(math101, &pool).await?;
+    let results = cart_service::submit_cart(student_id, 1, &admin, &pool).await?;
+    assert!(results[0].is_ok());
+    assert!(
+        results[1]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("credits"))
+    );
+
+    Ok(())
+}
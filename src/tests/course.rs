@@ -110,3 +110,33 @@ async fn test_remove_prerequisite(pool: PgPool) -> Result<(), sqlx::Error> {
 
     Ok(())
 }
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_offering_capacity_must_be_positive(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'MATH101'")
+        .fetch_one(&pool)
+        .await?;
+
+    // An empty offering is refused before it reaches the database's CHECK.
+    let empty = CourseOffering::create(
+        math101,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "002"),
+        0,
+        "Room SCI114".to_string(),
+        &pool,
+    )
+    .await;
+    assert!(matches!(empty, Err(Error::Validation(_))));
+
+    Ok(())
+}
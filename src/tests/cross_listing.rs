@@ -0,0 +1,188 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_listing_seat_cap_and_shared_pool(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::course::create_course;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::security::authorization::Actor;
+    use crate::services::{course_service, registration_service};
+    use uuid::Uuid;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let students = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student'),
+               ('b@example.edu', 'x', 'B', 'B', 'student'),
+               ('c@example.edu', 'x', 'C', 'C', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let cosc340 = create_course(
+        Uuid::new_v4(),
+        1,
+        "COSC340".to_string(),
+        "Numerical Methods".to_string(),
+        None,
+        3,
+    );
+    let math340 = create_course(
+        Uuid::new_v4(),
+        2,
+        "MATH340".to_string(),
+        "Numerical Methods".to_string(),
+        None,
+        3,
+    );
+    cosc340.insert(&pool).await?;
+    math340.insert(&pool).await?;
+
+    let offering = CourseOffering::create(
+        cosc340.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        2,
+        "Room SCI114".to_string(),
+        &pool,
+    )
+    .await?;
+    offering.cross_list(math340.id, Some(1), &pool).await?;
+
+    // The offering is visible under both course numbers.
+    let under_math = course_service::get_offerings_for_course(math340.id, &pool).await?;
+    assert_eq!(under_math.len(), 1);
+    assert_eq!(under_math[0].id, offering.id);
+
+    registration_service::enroll_under_listing(students[0], offering.id, math340.id, &admin, &pool)
+        .await?;
+    let capped = registration_service::enroll_under_listing(
+        students[1],
+        offering.id,
+        math340.id,
+        &admin,
+        &pool,
+    )
+    .await;
+    assert!(
+        capped.is_err(),
+        "MATH340 listing should be capped at 1 seat."
+    );
+
+    // The remaining seat in the shared pool is still available under COSC340.
+    registration_service::enroll(students[1], offering.id, &admin, &pool).await?;
+    let full = registration_service::enroll(students[2], offering.id, &admin, &pool).await;
+    assert!(full.is_err(), "Offering should be full.");
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_cross_listed_course_satisfies_prerequisite(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::models::course::create_course;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::models::course_prerequisite::CoursePrerequisite;
+    use crate::security::authorization::Actor;
+    use crate::services::registration_service;
+    use uuid::Uuid;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+
+    let cosc340 = create_course(
+        Uuid::new_v4(),
+        1,
+        "COSC340".to_string(),
+        "Numerical Methods".to_string(),
+        None,
+        3,
+    );
+    let math340 = create_course(
+        Uuid::new_v4(),
+        2,
+        "MATH340".to_string(),
+        "Numerical Methods".to_string(),
+        None,
+        3,
+    );
+    let math440 = create_course(
+        Uuid::new_v4(),
+        2,
+        "MATH440".to_string(),
+        "Numerical Analysis".to_string(),
+        None,
+        3,
+    );
+    cosc340.insert(&pool).await?;
+    math340.insert(&pool).await?;
+    math440.insert(&pool).await?;
+    math440
+        .add_prerequisite(&pool, &CoursePrerequisite::new(math440.id, math340.id))
+        .await?;
+
+    // The student passed the offering under COSC340.
+    let joint = CourseOffering::create(
+        cosc340.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room SCI114".to_string(),
+        &pool,
+    )
+    .await?;
+    joint.cross_list(math340.id, None, &pool).await?;
+    registration_service::enroll(student_id, joint.id, &admin, &pool).await?;
+    sqlx::query!(
+        "UPDATE registrations SET grade = 'B' WHERE student_id = $1 AND offering_id = $2",
+        student_id,
+        joint.id
+    )
+    .execute(&pool)
+    .await?;
+
+    let advanced = CourseOffering::create(
+        math440.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room SCI200".to_string(),
+        &pool,
+    )
+    .await?;
+    registration_service::enroll(student_id, advanced.id, &admin, &pool).await?;
+
+    Ok(())
+}
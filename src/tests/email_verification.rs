@@ -0,0 +1,124 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_unverified_account_cannot_login(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (user, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    assert!(!user.is_verified());
+    let first = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+
+    let pending =
+        user_service::try_login("carol@example.edu", "correct horse", "test", &pool).await;
+    assert!(matches!(pending, Err(Error::EmailNotVerified)));
+    // Without the right password, a pending account looks like any other failed login.
+    let wrong = user_service::try_login("carol@example.edu", "wrong", "test", &pool).await?;
+    assert!(wrong.is_none());
+
+    // Sending a new token replaces the first one.
+    email_verification_service::resend_verification("Carol@Example.edu", &notifier, &pool).await?;
+    let second = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    let replaced = email_verification_service::verify_email(&first, &pool).await;
+    assert!(matches!(replaced, Err(Error::Unauthenticated(_))));
+
+    email_verification_service::verify_email(&second, &pool).await?;
+    let verified = user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+        .await?
+        .expect("A verified account should log in");
+    assert!(verified.is_verified());
+
+    let reused = email_verification_service::verify_email(&second, &pool).await;
+    assert!(matches!(reused, Err(Error::Unauthenticated(_))));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_emails_are_normalized(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (user, _profile) = user_service::register_student(
+        "  Carol@Example.EDU ".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    assert_eq!(user.email, "carol@example.edu");
+
+    let duplicate = user_service::register_student(
+        "CAROL@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Again"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await;
+    assert!(matches!(duplicate, Err(Error::Database(_))));
+
+    for invalid in [
+        "not-an-email",
+        "carol@localhost",
+        "carol@@example.edu",
+        "ca rol@example.edu",
+    ] {
+        let refused = user_service::register_student(
+            invalid.to_string(),
+            "correct horse".to_string(),
+            FullName::new("Carol", "Invalid"),
+            2025,
+            &notifier,
+            &pool,
+        )
+        .await;
+        assert!(
+            matches!(refused, Err(Error::Validation(_))),
+            "{} should be rejected",
+            invalid
+        );
+    }
+
+    let verification = notifier
+        .sent
+        .lock()
+        .unwrap()
+        .first()
+        .cloned()
+        .expect("A verification token should be sent");
+    let crate::notifications::Notification::EmailVerification { token, .. } = verification else {
+        panic!("The first notification should be the verification");
+    };
+    email_verification_service::verify_email(&token, &pool).await?;
+    let logged_in = user_service::try_login("CAROL@EXAMPLE.EDU", "correct horse", "test", &pool)
+        .await?
+        .expect("Login should ignore the email's case");
+    assert_eq!(logged_in.id, user.id);
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_offerings_require_instructor_within_load(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::models::faculty_profile::FacultyTitle;
+    use crate::models::user::{FullName, Role};
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::services::{faculty_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let cs102 = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'CS102'")
+        .fetch_one(&pool)
+        .await?;
+
+    let refused = CourseOffering::create(
+        cs102,
+        1,
+        admin_id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room CS102".to_string(),
+        &pool,
+    )
+    .await;
+    assert!(refused.is_err(), "Admins cannot teach offerings.");
+
+    let (instructor, _) = user_service::register_instructor(
+        "lecturer@example.edu".to_string(),
+        "correct horse battery staple".to_string(),
+        FullName::new("Lena", "Lecturer"),
+        1,
+        FacultyTitle::Lecturer,
+        1,
+        &notifier,
+        &admin,
+        &pool,
+    )
+    .await?;
+    assert_eq!(instructor.role, Role::Instructor);
+    let lecturer = Actor::load(instructor.id, &pool)
+        .await?
+        .expect("Lecturer should exist");
+    faculty_service::set_office(
+        instructor.id,
+        Some("Room CS310".to_string()),
+        Some("Fridays 10:00-11:00".to_string()),
+        &lecturer,
+        &pool,
+    )
+    .await?;
+
+    CourseOffering::create(
+        cs102,
+        1,
+        instructor.id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room CS102".to_string(),
+        &pool,
+    )
+    .await?;
+    let overloaded = CourseOffering::create(
+        cs102,
+        1,
+        instructor.id,
+        Section::new(SectionType::Lecture, "002"),
+        30,
+        "Room CS103".to_string(),
+        &pool,
+    )
+    .await;
+    assert!(overloaded.is_err(), "Lecturer may only teach one section.");
+
+    // The database refuses non-instructors too, for writes that bypass `CourseOffering`.
+    let reassigned = sqlx::query!(
+        "UPDATE course_offerings SET instructor_id = $1 WHERE course_id = $2",
+        admin_id,
+        cs102
+    )
+    .execute(&pool)
+    .await;
+    assert!(reassigned.is_err(), "Admins cannot teach offerings.");
+
+    let saved = faculty_service::get_faculty_profile(instructor.id, &pool)
+        .await?
+        .expect("Profile should exist");
+    assert_eq!(saved.office_hours.as_deref(), Some("Fridays 10:00-11:00"));
+    assert_eq!(
+        faculty_service::get_faculty_for_department(1, &pool)
+            .await?
+            .len(),
+        2
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_teaching_schedule(pool: PgPool) -> Result<(), sqlx::Error> {
+    use crate::services::faculty_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+
+    let schedule = faculty_service::get_teaching_schedule(instructor_id, 1, &pool).await?;
+    let courses: Vec<_> = schedule
+        .iter()
+        .map(|assignment| assignment.course_number.as_str())
+        .collect();
+    assert_eq!(courses, vec!["CS101", "MATH101"]);
+    assert_eq!(schedule[0].meeting_times.len(), 2);
+
+    assert!(
+        faculty_service::get_teaching_schedule(instructor_id, 2, &pool)
+            .await?
+            .is_empty()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_active_hold_blocks_enrollment(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::hold::HoldType;
+    use crate::security::authorization::Actor;
+    use crate::services::{hold_service, registration_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let hold = hold_service::place_hold(
+        student_id,
+        HoldType::Advising,
+        "Meet with your advisor".to_string(),
+        None,
+        &admin,
+        &pool,
+    )
+    .await?;
+
+    let refused = registration_service::enroll(student_id, offering_id, &admin, &pool).await;
+    match refused {
+        Err(Error::Conflict(message)) => {
+            assert!(message.contains("Meet with your advisor"))
+        }
+        _ => panic!("Enrollment should be refused while a hold is active."),
+    }
+
+    let released = hold_service::release_hold(hold.id, &admin, &pool)
+        .await?
+        .expect("Hold should exist.");
+    assert_eq!(released.released_by, Some(admin_id));
+    assert!(
+        hold_service::get_active_holds(student_id, &pool)
+            .await?
+            .is_empty()
+    );
+
+    registration_service::enroll(student_id, offering_id, &admin, &pool).await?;
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_expired_hold_is_inactive(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::hold::HoldType;
+    use crate::security::authorization::Actor;
+    use crate::services::hold_service;
+    use chrono::{Duration, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let hold = hold_service::place_hold(
+        student_id,
+        HoldType::Financial,
+        "Outstanding balance".to_string(),
+        Some(Utc::now() - Duration::days(1)),
+        &admin,
+        &pool,
+    )
+    .await?;
+
+    assert!(!hold.is_active(Utc::now()));
+    assert!(
+        hold_service::get_active_holds(student_id, &pool)
+            .await?
+            .is_empty()
+    );
+    assert_eq!(
+        hold_service::get_holds_for_student(student_id, &pool)
+            .await?
+            .len(),
+        1
+    );
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_read_only_impersonation(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::impersonation::{ActionOutcome, ImpersonationEventKind};
+    use crate::security::authorization::Actor;
+    use crate::services::{cart_service, impersonation_service, mfa_service, session_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let math101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let refused =
+        impersonation_service::start_impersonation(admin_id, "Curious", true, &student, &pool)
+            .await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let not_student = impersonation_service::start_impersonation(
+        instructor_id,
+        "Checking their schedule",
+        true,
+        &admin,
+        &pool,
+    )
+    .await;
+    assert!(not_student.is_err(), "Only students can be impersonated");
+    let no_reason =
+        impersonation_service::start_impersonation(student_id, " ", true, &admin, &pool).await;
+    assert!(no_reason.is_err(), "A reason is required");
+
+    let (impersonation, token) = impersonation_service::start_impersonation(
+        student_id,
+        "Ticket 42: cannot register",
+        true,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let (_session, actor) = session_service::authenticate(&token, &pool)
+        .await?
+        .expect("The impersonation session should be usable");
+    assert_eq!(actor.user_id, student_id);
+    assert!(actor.is_read_only());
+
+    // The admin sees what the student sees, but cannot change anything as them.
+    let items = cart_service::get_cart_items(student_id, 1, &actor, &pool).await?;
+    assert!(items.is_empty());
+    let carts = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM registration_carts WHERE student_id = $1"#,
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(carts, 0, "Looking at the cart should not create one");
+    let add = cart_service::add_to_cart(student_id, 1, math101, &actor, &pool).await;
+    assert!(matches!(add, Err(Error::Forbidden(_))));
+    let mfa = mfa_service::begin_enrollment(student_id, &actor, &pool).await;
+    assert!(matches!(mfa, Err(Error::Forbidden(_))));
+    let sign_out = session_service::revoke_all_sessions(student_id, &actor, &pool).await;
+    assert!(matches!(sign_out, Err(Error::Forbidden(_))));
+
+    assert!(impersonation_service::stop_impersonation(&token, &pool).await?);
+    assert!(!impersonation_service::stop_impersonation(&token, &pool).await?);
+    assert!(
+        session_service::authenticate(&token, &pool)
+            .await?
+            .is_none()
+    );
+
+    // Every step is recorded against the admin, including the refused change.
+    let events =
+        impersonation_service::get_impersonation_events(impersonation.id, &admin, &pool).await?;
+    assert!(events.iter().all(|event| event.admin_id == admin_id));
+    let kinds: Vec<ImpersonationEventKind> = events.iter().map(|event| event.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            ImpersonationEventKind::Start,
+            ImpersonationEventKind::Action,
+            ImpersonationEventKind::Action,
+            ImpersonationEventKind::Stop,
+        ]
+    );
+    assert!(events[2].detail.starts_with("Add offering"));
+    let outcomes: Vec<Option<ActionOutcome>> = events.iter().map(|event| event.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            None,
+            Some(ActionOutcome::Succeeded),
+            Some(ActionOutcome::Refused),
+            None,
+        ]
+    );
+
+    let history = impersonation_service::get_impersonations(student_id, &admin, &pool).await?;
+    assert_eq!(history.len(), 1);
+    assert!(history[0].ended_at.is_some());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_impersonation_allowing_changes(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::impersonation::ActionOutcome;
+    use crate::security::authorization::Actor;
+    use crate::services::{
+        cart_service, impersonation_service, registration_service, session_service,
+    };
+    use uuid::Uuid;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let (impersonation, token) = impersonation_service::start_impersonation(
+        student_id,
+        "Registering on the student's behalf",
+        false,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let (_session, actor) = session_service::authenticate(&token, &pool)
+        .await?
+        .expect("The impersonation session should be usable");
+    assert!(!actor.is_read_only());
+
+    cart_service::add_to_cart(student_id, 1, math101, &actor, &pool).await?;
+    let items = cart_service::get_cart_items(student_id, 1, &admin, &pool).await?;
+    assert_eq!(items.len(), 1);
+
+    // An action that fails is recorded as failed, not as if it had happened.
+    let missing = registration_service::enroll(student_id, Uuid::new_v4(), &actor, &pool).await;
+    assert!(missing.is_err());
+    let events =
+        impersonation_service::get_impersonation_events(impersonation.id, &admin, &pool).await?;
+    let outcomes: Vec<Option<ActionOutcome>> = events.iter().map(|event| event.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            None,
+            Some(ActionOutcome::Succeeded),
+            Some(ActionOutcome::Failed),
+        ]
+    );
+
+    Ok(())
+}
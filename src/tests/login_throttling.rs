@@ -0,0 +1,225 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_backoff_and_lockout(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::login_attempt::LoginOutcome;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let (user, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+
+    // Moves the last failure into the past so the next attempt is not held back by the backoff.
+    let wait_out_backoff = || {
+        sqlx::query!(
+            "UPDATE login_throttles SET last_failed_at = now() - interval '1 minute' WHERE email = $1",
+            user.email
+        )
+        .execute(&pool)
+    };
+
+    for _ in 0..3 {
+        let attempt = user_service::try_login("carol@example.edu", "wrong", "test", &pool).await?;
+        assert!(attempt.is_none());
+    }
+    // Three failures in a row: even the right password has to wait out the backoff.
+    let backed_off =
+        user_service::try_login("carol@example.edu", "correct horse", "test", &pool).await;
+    assert!(matches!(backed_off, Err(Error::Throttled(_))));
+
+    wait_out_backoff().await?;
+    assert!(
+        user_service::try_login("carol@example.edu", "wrong", "test", &pool)
+            .await?
+            .is_none()
+    );
+    wait_out_backoff().await?;
+    assert!(
+        user_service::try_login("carol@example.edu", "wrong", "test", &pool)
+            .await?
+            .is_none()
+    );
+
+    // The fifth failure locks the account, regardless of the backoff.
+    wait_out_backoff().await?;
+    let locked = user_service::try_login("carol@example.edu", "correct horse", "test", &pool).await;
+    match locked {
+        Err(Error::Throttled(throttled)) => assert!(throttled.retry_after > chrono::Utc::now()),
+        _ => panic!("A locked account should refuse to log in"),
+    }
+
+    user_service::unlock_account(user.id, &admin, &pool).await?;
+    let unlocked = user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+        .await?
+        .expect("An unlocked account should log in");
+    assert_eq!(unlocked.id, user.id);
+
+    let history = user_service::get_login_history(user.id, &admin, &pool).await?;
+    let outcomes: Vec<LoginOutcome> = history.iter().map(|attempt| attempt.outcome).collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            LoginOutcome::Success,
+            LoginOutcome::Locked,
+            LoginOutcome::Invalid,
+            LoginOutcome::Invalid,
+            LoginOutcome::Throttled,
+            LoginOutcome::Invalid,
+            LoginOutcome::Invalid,
+            LoginOutcome::Invalid,
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_failing_source_is_throttled(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::throttling::SOURCE_FAILURE_LIMIT;
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+
+    // Failures spread across many accounts, as from password spraying.
+    sqlx::query!(
+        r#"
+        INSERT INTO login_attempts (email, source, outcome)
+        SELECT 'user' || n || '@example.edu', '10.0.0.1', 'invalid'
+        FROM generate_series(1, $1::BIGINT) AS n
+        "#,
+        SOURCE_FAILURE_LIMIT
+    )
+    .execute(&pool)
+    .await?;
+
+    let throttled =
+        user_service::try_login("carol@example.edu", "correct horse", "10.0.0.1", &pool).await;
+    assert!(matches!(throttled, Err(Error::Throttled(_))));
+
+    let elsewhere =
+        user_service::try_login("carol@example.edu", "correct horse", "10.0.0.2", &pool).await?;
+    assert!(elsewhere.is_some());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_login_history_and_unlock_permissions(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (user, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let student_id = user.id;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Registered student should exist");
+    let instructor = Actor::load(instructor_id, &pool)
+        .await?
+        .expect("Seed instructor should exist");
+
+    user_service::try_login("carol@example.edu", "wrong", "test", &pool).await?;
+
+    let own = user_service::get_login_history(student_id, &student, &pool).await?;
+    assert_eq!(own.len(), 1);
+    assert_eq!(own[0].source, "test");
+
+    let others = user_service::get_login_history(student_id, &instructor, &pool).await;
+    assert!(matches!(others, Err(Error::Forbidden(_))));
+    let unlock = user_service::unlock_account(student_id, &student, &pool).await;
+    assert!(matches!(unlock, Err(Error::Forbidden(_))));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_stale_throttles_are_swept(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    for email in ["nobody@example.edu", "ghost@example.edu"] {
+        assert!(
+            user_service::try_login(email, "guess", "test", &pool)
+                .await?
+                .is_none()
+        );
+    }
+    let count = || sqlx::query_scalar!("SELECT count(*) FROM login_throttles").fetch_one(&pool);
+    assert_eq!(count().await?, Some(2));
+
+    // Only the older failure is past the lockout, so only its row is forgotten.
+    sqlx::query!(
+        "UPDATE login_throttles SET last_failed_at = now() - interval '1 hour' WHERE email = 'nobody@example.edu'"
+    )
+    .execute(&pool)
+    .await?;
+    assert!(
+        user_service::try_login("ghost@example.edu", "guess", "test", &pool)
+            .await?
+            .is_none()
+    );
+    let remaining = sqlx::query_scalar!("SELECT email FROM login_throttles")
+        .fetch_all(&pool)
+        .await?;
+    assert_eq!(remaining, vec!["ghost@example.edu".to_string()]);
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[test]
+fn test_totp_matches_rfc_6238() {
+    use crate::security::totp;
+    use chrono::{TimeZone, Utc};
+
+    // The SHA1 test vectors from RFC 6238, truncated to six digits.
+    let secret = b"12345678901234567890";
+    for (time, code) in [
+        (59, "287082"),
+        (1111111109, "081804"),
+        (1111111111, "050471"),
+        (1234567890, "005924"),
+        (2000000000, "279037"),
+    ] {
+        let time = Utc.timestamp_opt(time, 0).unwrap();
+        assert_eq!(totp::code_at(secret, time), code);
+    }
+
+    let encoded = totp::base32_encode(secret);
+    assert_eq!(encoded, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    assert_eq!(totp::base32_decode(&encoded).as_deref(), Some(&secret[..]));
+
+    // Codes from the neighbouring steps are accepted, but never twice.
+    let now = Utc.timestamp_opt(1111111111, 0).unwrap();
+    let previous = totp::code_at(secret, now - chrono::Duration::seconds(30));
+    let step = totp::verify(secret, &previous, now, None).expect("Drift should be allowed");
+    assert_eq!(totp::verify(secret, &previous, now, Some(step)), None);
+    let stale = totp::code_at(secret, now - chrono::Duration::seconds(90));
+    assert_eq!(totp::verify(secret, &stale, now, None), None);
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_admin_must_complete_second_step(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::security::totp;
+    use crate::services::{mfa_service, session_service};
+    use chrono::{Duration, TimeZone, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let now = Utc.timestamp_opt(1_800_000_000, 0).unwrap();
+
+    let (session, token) = session_service::create_session(admin_id, &pool).await?;
+    assert!(session.mfa_pending);
+    assert!(
+        session_service::authenticate(&token, &pool)
+            .await?
+            .is_none()
+    );
+
+    // Admins without an authenticator have to set one up before they can finish logging in.
+    let not_set_up = mfa_service::complete_login(&token, "000000", now, &pool).await;
+    assert!(matches!(not_set_up, Err(Error::Conflict(_))));
+    let (_pending, actor) = session_service::authenticate_pending(&token, &pool)
+        .await?
+        .expect("A pending session should be usable for setup");
+    let setup = mfa_service::begin_enrollment(admin_id, &actor, &pool).await?;
+    assert!(
+        setup
+            .provisioning_uri
+            .starts_with("otpauth://totp/SchoolRedux:admin@example.edu?")
+    );
+    assert_eq!(setup.recovery_codes.len(), mfa_service::RECOVERY_CODE_COUNT);
+    let secret = totp::base32_decode(&setup.secret).expect("Secret should be base32");
+
+    mfa_service::complete_login(&token, &totp::code_at(&secret, now), now, &pool).await?;
+    assert!(
+        session_service::authenticate(&token, &pool)
+            .await?
+            .is_some()
+    );
+    assert!(mfa_service::is_enrolled(admin_id, &pool).await?);
+
+    // The same code cannot be used for another login.
+    let (_second, second_token) = session_service::create_session(admin_id, &pool).await?;
+    let replayed =
+        mfa_service::complete_login(&second_token, &totp::code_at(&secret, now), now, &pool).await;
+    assert!(replayed.is_err());
+    let later = now + Duration::seconds(60);
+    mfa_service::complete_login(&second_token, &totp::code_at(&secret, later), later, &pool)
+        .await?;
+
+    // Recovery codes work once each.
+    let (_third, third_token) = session_service::create_session(admin_id, &pool).await?;
+    mfa_service::complete_login(&third_token, &setup.recovery_codes[0], later, &pool).await?;
+    let (_fourth, fourth_token) = session_service::create_session(admin_id, &pool).await?;
+    let reused =
+        mfa_service::complete_login(&fourth_token, &setup.recovery_codes[0], later, &pool).await;
+    assert!(reused.is_err());
+
+    let disabled = mfa_service::disable_mfa(admin_id, &actor, &pool).await;
+    assert!(matches!(disabled, Err(Error::Conflict(_))));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_student_opts_in(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::security::{throttling, totp};
+    use crate::services::{mfa_service, session_service};
+    use chrono::{TimeZone, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let now = Utc.timestamp_opt(1_800_000_000, 0).unwrap();
+
+    let (session, token) = session_service::create_session(student_id, &pool).await?;
+    assert!(!session.mfa_pending);
+    let (_session, student) = session_service::authenticate(&token, &pool)
+        .await?
+        .expect("A student session should not need a second step");
+
+    let setup = mfa_service::begin_enrollment(student_id, &student, &pool).await?;
+    let secret = totp::base32_decode(&setup.secret).expect("Secret should be base32");
+    let wrong = mfa_service::confirm_enrollment(student_id, "000000", now, &student, &pool).await;
+    assert!(wrong.is_err());
+    mfa_service::confirm_enrollment(
+        student_id,
+        &totp::code_at(&secret, now),
+        now,
+        &student,
+        &pool,
+    )
+    .await?;
+
+    // Once set up, wrong codes count against the student across logins, and eventually revoke the pending sessions.
+    let (pending, pending_token) = session_service::create_session(student_id, &pool).await?;
+    assert!(pending.mfa_pending);
+    for _ in 1..mfa_service::MAX_FAILED_ATTEMPTS {
+        assert!(
+            mfa_service::complete_login(&pending_token, "000000", now, &pool)
+                .await
+                .is_err()
+        );
+    }
+    let (_other, other_token) = session_service::create_session(student_id, &pool).await?;
+    assert!(
+        mfa_service::complete_login(&other_token, "000000", now, &pool)
+            .await
+            .is_err()
+    );
+    for token in [&pending_token, &other_token] {
+        assert!(
+            session_service::authenticate_pending(token, &pool)
+                .await?
+                .is_none()
+        );
+    }
+
+    // Logging in again does not allow more guesses until the lock ends, even with the right code.
+    let later = now + chrono::Duration::seconds(60);
+    let (_locked, locked_token) = session_service::create_session(student_id, &pool).await?;
+    let refused =
+        mfa_service::complete_login(&locked_token, &totp::code_at(&secret, later), later, &pool)
+            .await;
+    assert!(matches!(refused, Err(Error::Throttled(_))));
+    let after_lock = later + throttling::LOCKOUT_DURATION;
+    mfa_service::complete_login(
+        &locked_token,
+        &totp::code_at(&secret, after_lock),
+        after_lock,
+        &pool,
+    )
+    .await?;
+
+    mfa_service::disable_mfa(student_id, &student, &pool).await?;
+    let (after, _after_token) = session_service::create_session(student_id, &pool).await?;
+    assert!(!after.mfa_pending);
+
+    Ok(())
+}
@@ -1 +1,24 @@
+pub mod account_lifecycle;
+pub mod audit_log;
+pub mod authentication;
+pub mod authorization;
+pub mod billing;
+pub mod cart;
 pub mod course;
+pub mod cross_listing;
+pub mod email_verification;
+pub mod faculty;
+pub mod hold;
+pub mod impersonation;
+pub mod login_throttling;
+pub mod mfa;
+pub mod overrides;
+pub mod password_hashing;
+pub mod password_policy;
+pub mod password_reset;
+pub mod programs;
+pub mod seat_reservation;
+pub mod sections;
+pub mod session;
+pub mod swap;
+pub mod transfer_credit;
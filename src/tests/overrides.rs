@@ -0,0 +1,274 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_consent_override_is_consumed(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::registration_override::{OverrideAction, OverrideRule};
+    use crate::security::authorization::Actor;
+    use crate::services::{course_service, override_service, registration_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor = Actor::load(instructor_id, &pool)
+        .await?
+        .expect("Seed instructor should exist");
+    let offering =
+        sqlx::query!("SELECT id, course_id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let mut course = course_service::get_course_by_id(offering.course_id, &pool)
+        .await?
+        .expect("MATH101 should exist");
+    course.set_requires_consent(true, &pool).await?;
+
+    let refused = registration_service::enroll(student_id, offering.id, &admin, &pool).await;
+    assert!(refused.is_err(), "MATH101 should require consent.");
+
+    let consent = override_service::issue_override(
+        offering.id,
+        student_id,
+        OverrideRule::Consent,
+        None,
+        &instructor,
+        &pool,
+    )
+    .await?;
+    registration_service::enroll(student_id, offering.id, &admin, &pool).await?;
+
+    // Students cannot look at overrides, since unused permission numbers could be taken from them.
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let refused = override_service::get_audit_log(consent.id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let log = override_service::get_audit_log(consent.id, &instructor, &pool).await?;
+    let actions: Vec<_> = log.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        vec![OverrideAction::Issued, OverrideAction::Consumed]
+    );
+    // The override was used by the admin who registered the student.
+    assert_eq!(log[1].actor_id, admin.user_id);
+
+    // A used override cannot be revoked.
+    assert!(
+        override_service::revoke_override(consent.id, &instructor, &pool)
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_permission_number_bypasses_capacity(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::registration_override::OverrideRule;
+    use crate::security::authorization::Actor;
+    use crate::services::{override_service, registration_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor = Actor::load(instructor_id, &pool)
+        .await?
+        .expect("Seed instructor should exist");
+    let students = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student'),
+               ('b@example.edu', 'x', 'B', 'B', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_all(&pool)
+    .await?;
+    let offering_id = sqlx::query_scalar!(
+        "UPDATE course_offerings SET capacity = 1 WHERE location = 'Room MATH201' RETURNING id"
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    registration_service::enroll(students[0], offering_id, &admin, &pool).await?;
+    let full = registration_service::enroll(students[1], offering_id, &admin, &pool).await;
+    assert!(full.is_err(), "Offering should be full.");
+
+    let permission = override_service::issue_permission_number(
+        offering_id,
+        OverrideRule::Capacity,
+        None,
+        &instructor,
+        &pool,
+    )
+    .await?;
+    let number = permission
+        .permission_number
+        .expect("Permission numbers should have a number");
+
+    let wrong = registration_service::enroll_with_permission(
+        students[1],
+        offering_id,
+        "000000000",
+        &admin,
+        &pool,
+    )
+    .await;
+    assert!(
+        wrong.is_err(),
+        "An unknown permission number should not work."
+    );
+
+    registration_service::enroll_with_permission(students[1], offering_id, &number, &admin, &pool)
+        .await?;
+
+    let overrides =
+        override_service::get_overrides_for_offering(offering_id, &instructor, &pool).await?;
+    assert_eq!(overrides[0].used_by, Some(students[1]));
+
+    // Permission numbers are secrets: only shown when issued, stored hashed, and never copied into the audit log.
+    assert_eq!(overrides[0].permission_number, None);
+    let stored = sqlx::query_scalar!(
+        "SELECT permission_number_hash FROM registration_overrides WHERE id = $1",
+        permission.id
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_ne!(stored.as_deref(), Some(number.as_str()));
+    let logged = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "entries!", COUNT(*) FILTER (WHERE COALESCE(before::TEXT, '') || COALESCE(after::TEXT, '') LIKE '%' || $1 || '%') AS "leaks!"
+        FROM audit_log
+        WHERE entity = 'registration_overrides'
+        "#,
+        number
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert!(logged.entries > 0);
+    assert_eq!(logged.leaks, 0);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_only_instructor_can_issue_overrides(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::registration_override::OverrideRule;
+    use crate::security::authorization::Actor;
+    use crate::services::override_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let refused = override_service::issue_permission_number(
+        offering_id,
+        OverrideRule::Prerequisite,
+        None,
+        &student,
+        &pool,
+    )
+    .await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let refused = override_service::get_overrides_for_offering(offering_id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let overrides_issued =
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM registration_overrides"#)
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(overrides_issued, 0);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_time_conflict_and_override(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::registration_override::OverrideRule;
+    use crate::security::authorization::Actor;
+    use crate::services::{override_service, registration_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor = Actor::load(instructor_id, &pool)
+        .await?
+        .expect("Seed instructor should exist");
+    let offering_id =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    // MATH101 now overlaps the student's Monday CS101 meeting.
+    sqlx::query!(
+        "INSERT INTO course_meeting_times (offering_id, day_of_week, start_time, end_time) VALUES ($1, 'Monday', '10:00', '11:00')",
+        offering_id
+    )
+    .execute(&pool)
+    .await?;
+
+    let conflict = registration_service::enroll(student_id, offering_id, &admin, &pool).await;
+    assert!(
+        matches!(conflict, Err(Error::Conflict(ref reason)) if reason.contains("CS101")),
+        "Expected a time conflict with CS101."
+    );
+
+    override_service::issue_override(
+        offering_id,
+        student_id,
+        OverrideRule::TimeConflict,
+        None,
+        &instructor,
+        &pool,
+    )
+    .await?;
+    registration_service::enroll(student_id, offering_id, &admin, &pool).await?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[test]
+fn test_needs_rehash_compares_parameters() {
+    use crate::security::password::{
+        PasswordError, PasswordParams, hash_password_with, needs_rehash, validate_password,
+    };
+
+    let current = PasswordParams::default();
+    let old = PasswordParams {
+        memory_kib: 1024,
+        iterations: 1,
+        parallelism: 1,
+    };
+    let old_hash = hash_password_with("correct horse", &old).expect("Hashing should succeed");
+    let current_hash =
+        hash_password_with("correct horse", &current).expect("Hashing should succeed");
+
+    // Hashes verify under whatever parameters they were made with.
+    assert!(validate_password("correct horse", &old_hash).expect("Hash is well formed"));
+    assert!(needs_rehash(&old_hash, &current).expect("Hash is well formed"));
+    assert!(!needs_rehash(&current_hash, &current).expect("Hash is well formed"));
+    assert!(matches!(
+        needs_rehash("hashed_student_pw", &current),
+        Err(PasswordError::MalformedHash(_))
+    ));
+}
+
+#[test]
+fn test_suggest_params_never_goes_below_defaults() {
+    use crate::security::password::{PasswordParams, suggest_params};
+    use std::time::Duration;
+
+    let suggestion = suggest_params(Duration::ZERO, 64 * 1024, 1);
+    assert_eq!(suggestion.params, PasswordParams::default());
+    assert!(suggestion.hash_time > Duration::ZERO);
+}
+
+#[test]
+fn test_out_of_range_params_are_invalid() {
+    use crate::security::password::PasswordParams;
+
+    assert!(PasswordParams::default().validate().is_ok());
+    let too_little_memory = PasswordParams {
+        memory_kib: 1,
+        ..PasswordParams::default()
+    };
+    assert!(too_little_memory.validate().is_err());
+    let no_iterations = PasswordParams {
+        iterations: 0,
+        ..PasswordParams::default()
+    };
+    assert!(no_iterations.validate().is_err());
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_login_upgrades_old_hash(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::password::{PasswordParams, hash_password_with, needs_rehash};
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (user, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+
+    let old = PasswordParams {
+        memory_kib: 1024,
+        iterations: 1,
+        parallelism: 1,
+    };
+    let old_hash = hash_password_with("correct horse", &old).expect("Hashing should succeed");
+    sqlx::query!(
+        "UPDATE users SET hashed_password = $2 WHERE id = $1",
+        user.id,
+        old_hash
+    )
+    .execute(&pool)
+    .await?;
+
+    // A wrong password leaves the hash alone.
+    user_service::try_login("carol@example.edu", "battery staple", "test", &pool).await?;
+    let stored = sqlx::query_scalar!("SELECT hashed_password FROM users WHERE id = $1", user.id)
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(stored, old_hash);
+
+    user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+        .await?
+        .expect("The old hash should still log in");
+    let stored = sqlx::query_scalar!("SELECT hashed_password FROM users WHERE id = $1", user.id)
+        .fetch_one(&pool)
+        .await?;
+    assert!(!needs_rehash(&stored, &PasswordParams::current()).expect("Hash is well formed"));
+
+    assert!(
+        user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+            .await?
+            .is_some()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[test]
+fn test_policy_reports_every_violation() {
+    use crate::models::user::FullName;
+    use crate::security::password_policy::{PasswordPolicy, PasswordViolation};
+
+    let policy = PasswordPolicy::default();
+    let name = FullName::new("Carol", "Danvers");
+
+    assert!(
+        policy
+            .check("correct horse battery", &name, "carol.d@example.edu")
+            .is_ok()
+    );
+
+    let short = policy
+        .check("", &name, "carol.d@example.edu")
+        .expect_err("An empty password should be rejected");
+    assert_eq!(
+        short.violations,
+        vec![PasswordViolation::TooShort { min_length: 10 }]
+    );
+
+    let common = policy
+        .check("Password123", &name, "carol.d@example.edu")
+        .expect_err("A common password should be rejected");
+    assert_eq!(common.violations, vec![PasswordViolation::Common]);
+
+    let personal = policy
+        .check("danvers-carol.d-1", &name, "carol.d@example.edu")
+        .expect_err("A password containing personal details should be rejected");
+    assert_eq!(
+        personal.violations,
+        vec![
+            PasswordViolation::ContainsName,
+            PasswordViolation::ContainsEmail
+        ]
+    );
+
+    let relaxed = PasswordPolicy {
+        min_length: 4,
+        reject_common: false,
+        reject_personal_info: false,
+        ..PasswordPolicy::default()
+    };
+    assert!(relaxed.check("carol", &name, "carol@example.edu").is_ok());
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_registration_rejects_weak_passwords(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::security::authorization::Actor;
+    use crate::security::password_policy::PasswordViolation;
+    use crate::services::user_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student = user_service::register_student(
+        "carol@example.edu".to_string(),
+        String::new(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await;
+    match student {
+        Err(Error::WeakPassword(weak)) => assert_eq!(
+            weak.violations,
+            vec![PasswordViolation::TooShort { min_length: 10 }]
+        ),
+        _ => panic!("An empty password should be rejected"),
+    }
+
+    let admin_user = user_service::register_admin(
+        "dave@example.edu".to_string(),
+        "dave-is-the-admin".to_string(),
+        FullName::new("Dave", "Admin"),
+        None,
+        &notifier,
+        &admin,
+        &pool,
+    )
+    .await;
+    assert!(matches!(admin_user, Err(Error::WeakPassword(_))));
+
+    let registered = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM users WHERE email IN ('carol@example.edu', 'dave@example.edu')"
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(registered, Some(0));
+
+    Ok(())
+}
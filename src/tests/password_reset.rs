@@ -0,0 +1,86 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_reset_password_with_token(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::user::FullName;
+    use crate::notifications::RecordingNotifier;
+    use crate::services::{password_reset_service, session_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (user, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let (_session, session_token) = session_service::create_session(user.id, &pool).await?;
+
+    password_reset_service::request_password_reset("carol@example.edu", &notifier, &pool).await?;
+    let token = notifier.last_token().expect("A reset token should be sent");
+
+    // A password breaking the policy leaves the token usable.
+    let weak = password_reset_service::reset_password(&token, "short", &pool).await;
+    assert!(matches!(weak, Err(Error::WeakPassword(_))));
+
+    password_reset_service::reset_password(&token, "battery staple", &pool).await?;
+
+    assert!(
+        user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+            .await?
+            .is_none()
+    );
+    assert!(
+        user_service::try_login("carol@example.edu", "battery staple", "test", &pool)
+            .await?
+            .is_some()
+    );
+    assert!(
+        session_service::authenticate(&session_token, &pool)
+            .await?
+            .is_none()
+    );
+
+    let reused = password_reset_service::reset_password(&token, "another passphrase", &pool).await;
+    assert!(matches!(reused, Err(Error::Unauthenticated(_))));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_reset_tokens_expire_and_are_replaced(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::notifications::RecordingNotifier;
+    use crate::services::password_reset_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let notifier = RecordingNotifier::default();
+
+    password_reset_service::request_password_reset("nobody@example.edu", &notifier, &pool).await?;
+    assert!(notifier.last_token().is_none());
+
+    password_reset_service::request_password_reset("student@example.edu", &notifier, &pool).await?;
+    let first = notifier.last_token().expect("A reset token should be sent");
+    password_reset_service::request_password_reset("student@example.edu", &notifier, &pool).await?;
+    let second = notifier.last_token().expect("A reset token should be sent");
+
+    let replaced =
+        password_reset_service::reset_password(&first, "correct horse battery", &pool).await;
+    assert!(matches!(replaced, Err(Error::Unauthenticated(_))));
+
+    sqlx::query!("UPDATE password_reset_tokens SET expires_at = now() - interval '1 minute'")
+        .execute(&pool)
+        .await?;
+    let expired =
+        password_reset_service::reset_password(&second, "correct horse battery", &pool).await;
+    assert!(matches!(expired, Err(Error::Unauthenticated(_))));
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_declaring_and_ending_programs(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::program::ProgramKind;
+    use crate::security::authorization::Actor;
+    use crate::services::program_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let math = sqlx::query_scalar!("SELECT id FROM departments WHERE code = 'MATH'")
+        .fetch_one(&pool)
+        .await?;
+
+    // The seed student's major was carried over from before majors were programs, and is the only program in use.
+    assert_eq!(program_service::get_programs(&pool).await?.len(), 1);
+    let declared = program_service::get_declared_programs(student_id, &student, &pool).await?;
+    assert_eq!(declared.len(), 1);
+    let cs_major = declared[0].program_id;
+
+    let minor = program_service::create_program(
+        math,
+        "Mathematics".to_string(),
+        ProgramKind::Minor,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let refused = program_service::create_program(
+        math,
+        "Statistics".to_string(),
+        ProgramKind::Major,
+        &student,
+        &pool,
+    )
+    .await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+
+    // Programs other than majors are declared by the department's admins, not by students themselves.
+    let refused = program_service::declare_program(student_id, minor.id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    program_service::declare_program(student_id, minor.id, &admin, &pool).await?;
+    let again = program_service::declare_program(student_id, minor.id, &admin, &pool).await;
+    assert!(matches!(again, Err(Error::Conflict(_))));
+
+    // A second major has to be requested.
+    let math_major = program_service::create_program(
+        math,
+        "Mathematics".to_string(),
+        ProgramKind::Major,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let refused = program_service::declare_program(student_id, math_major.id, &admin, &pool).await;
+    assert!(matches!(refused, Err(Error::Conflict(_))));
+    let refused = program_service::end_program(student_id, cs_major, &admin, &pool).await;
+    assert!(matches!(refused, Err(Error::Conflict(_))));
+
+    let refused = program_service::end_program(student_id, minor.id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+    let ended = program_service::end_program(student_id, minor.id, &admin, &pool)
+        .await?
+        .expect("The minor should be declared");
+    assert!(!ended.is_current());
+    let declared = program_service::get_declared_programs(student_id, &student, &pool).await?;
+    assert_eq!(declared.len(), 1);
+    let history = program_service::get_program_history(student_id, &student, &pool).await?;
+    assert_eq!(history.len(), 2);
+
+    // Ended programs can be declared again.
+    program_service::declare_program(student_id, minor.id, &admin, &pool).await?;
+    let history = program_service::get_program_history(student_id, &student, &pool).await?;
+    assert_eq!(history.len(), 3);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_major_change_requests(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::major_change_request::MajorChangeStatus;
+    use crate::models::program::ProgramKind;
+    use crate::security::authorization::Actor;
+    use crate::services::program_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let cs_major = sqlx::query_scalar!(
+        "SELECT id FROM programs WHERE name = 'Computer Science' AND kind = 'major'"
+    )
+    .fetch_one(&pool)
+    .await?;
+    let math = sqlx::query_scalar!("SELECT id FROM departments WHERE code = 'MATH'")
+        .fetch_one(&pool)
+        .await?;
+    let math_major = program_service::create_program(
+        math,
+        "Mathematics".to_string(),
+        ProgramKind::Major,
+        &admin,
+        &pool,
+    )
+    .await?
+    .id;
+    let statistics_major = program_service::create_program(
+        math,
+        "Statistics".to_string(),
+        ProgramKind::Major,
+        &admin,
+        &pool,
+    )
+    .await?
+    .id;
+
+    let request = program_service::request_major_change(
+        student_id,
+        Some(cs_major),
+        math_major,
+        "I prefer proofs to programs.".to_string(),
+        &student,
+        &pool,
+    )
+    .await?;
+    assert_eq!(request.status, MajorChangeStatus::Pending);
+    let second = program_service::request_major_change(
+        student_id,
+        None,
+        statistics_major,
+        "Double major.".to_string(),
+        &student,
+        &pool,
+    )
+    .await;
+    assert!(matches!(second, Err(Error::Conflict(_))));
+    assert_eq!(
+        program_service::get_pending_major_changes(&admin, &pool)
+            .await?
+            .len(),
+        1
+    );
+
+    // Students cannot decide their own requests.
+    let refused = program_service::approve_major_change(request.id, &student, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+
+    let approved = program_service::approve_major_change(request.id, &admin, &pool)
+        .await?
+        .expect("The request should exist");
+    assert_eq!(approved.status, MajorChangeStatus::Approved);
+    assert_eq!(approved.decided_by, Some(admin_id));
+    let again = program_service::reject_major_change(request.id, &admin, &pool).await;
+    assert!(matches!(again, Err(Error::Conflict(_))));
+
+    let declared = program_service::get_declared_programs(student_id, &student, &pool).await?;
+    let declared: Vec<i32> = declared.iter().map(|d| d.program_id).collect();
+    assert_eq!(declared, vec![math_major]);
+    // The old major stays on record as ended.
+    let history = program_service::get_program_history(student_id, &student, &pool).await?;
+    assert!(
+        history
+            .iter()
+            .any(|d| d.program_id == cs_major && !d.is_current())
+    );
+
+    // A rejected request leaves the student's programs alone.
+    let request = program_service::request_major_change(
+        student_id,
+        None,
+        statistics_major,
+        "Double major.".to_string(),
+        &student,
+        &pool,
+    )
+    .await?;
+    program_service::reject_major_change(request.id, &admin, &pool).await?;
+    let declared = program_service::get_declared_programs(student_id, &student, &pool).await?;
+    assert_eq!(declared.len(), 1);
+
+    let requests = program_service::get_major_change_requests(student_id, &student, &pool).await?;
+    let statuses: Vec<MajorChangeStatus> = requests.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![MajorChangeStatus::Rejected, MajorChangeStatus::Approved]
+    );
+
+    Ok(())
+}
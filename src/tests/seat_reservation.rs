@@ -0,0 +1,112 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_reserved_seats_until_release(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::models::seat_reservation::SeatReservation;
+    use crate::security::authorization::Actor;
+    use crate::services::registration_service;
+    use chrono::{Duration, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let cs_student =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'MATH101'")
+        .fetch_one(&pool)
+        .await?;
+    let cs_major = sqlx::query_scalar!(
+        "SELECT id FROM programs WHERE name = 'Computer Science' AND kind = 'major'"
+    )
+    .fetch_one(&pool)
+    .await?;
+    let others = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student'),
+               ('b@example.edu', 'x', 'B', 'B', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let offering = CourseOffering::create(
+        math101,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "002"),
+        3,
+        "Room SCI114".to_string(),
+        &pool,
+    )
+    .await?;
+    let reservation = SeatReservation::create(
+        offering.id,
+        2,
+        Some(cs_major),
+        None,
+        Utc::now() + Duration::days(1),
+        &pool,
+    )
+    .await?;
+
+    // Only one seat is unreserved.
+    registration_service::enroll(others[0], offering.id, &admin, &pool).await?;
+    assert!(
+        registration_service::enroll(others[1], offering.id, &admin, &pool)
+            .await
+            .is_err()
+    );
+    registration_service::enroll(cs_student, offering.id, &admin, &pool).await?;
+
+    // Once the reservation is released, its unfilled seat is open to everyone.
+    sqlx::query!(
+        "UPDATE seat_reservations SET release_at = now() - INTERVAL '1 hour' WHERE id = $1",
+        reservation.id
+    )
+    .execute(&pool)
+    .await?;
+    registration_service::enroll(others[1], offering.id, &admin, &pool).await?;
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_unknown_program_is_rejected(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::seat_reservation::SeatReservation;
+    use chrono::{Duration, Utc};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let offering_id = sqlx::query_scalar!("SELECT id FROM course_offerings LIMIT 1")
+        .fetch_one(&pool)
+        .await?;
+
+    // Reservations refer to programs, so seats cannot be held for one that does not exist.
+    let reserved = SeatReservation::create(
+        offering_id,
+        5,
+        Some(-1),
+        None,
+        Utc::now() + Duration::days(1),
+        &pool,
+    )
+    .await;
+    assert!(matches!(reserved, Err(Error::Database(_))));
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_linked_components_register_together(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::course::create_course;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::models::term_billing::{RefundRule, TermBillingRate};
+    use crate::security::authorization::Actor;
+    use crate::services::{
+        billing_service, course_service, registration_service, transcript_service,
+    };
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let other_student = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('other@example.edu', 'x', 'Other', 'Student', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_one(&pool)
+    .await?;
+    let today = Utc::now().date_naive();
+    TermBillingRate::set(1, 10_000, today + Duration::days(30), &pool).await?;
+    RefundRule::create(1, today + Duration::days(7), 100, &pool).await?;
+
+    let course = create_course(
+        Uuid::new_v4(),
+        1,
+        "COSC111".to_string(),
+        "Computer Programming I".to_string(),
+        None,
+        3,
+    );
+    course.insert(&pool).await?;
+
+    let lecture = CourseOffering::create(
+        course.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        100,
+        "Room SCI333".to_string(),
+        &pool,
+    )
+    .await?;
+    let lab_one = CourseOffering::create(
+        course.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lab, "L01"),
+        1,
+        "Room EME1151".to_string(),
+        &pool,
+    )
+    .await?;
+    let lab_two = CourseOffering::create(
+        course.id,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lab, "L02"),
+        20,
+        "Room EME1152".to_string(),
+        &pool,
+    )
+    .await?;
+    course_service::link_sections(
+        lecture.id,
+        SectionType::Lab,
+        &[lab_one.id, lab_two.id],
+        &admin,
+        &pool,
+    )
+    .await?;
+
+    // The lecture cannot be taken without exactly one lab.
+    assert!(
+        registration_service::enroll(student_id, lecture.id, &admin, &pool)
+            .await
+            .is_err()
+    );
+    assert!(
+        registration_service::enroll_with_components(
+            student_id,
+            &[lecture.id, lab_one.id, lab_two.id],
+            &admin,
+            &pool
+        )
+        .await
+        .is_err()
+    );
+
+    // L01 is full, so the lecture registration must be rolled back too.
+    registration_service::enroll(other_student, lab_one.id, &admin, &pool).await?;
+    assert!(
+        registration_service::enroll_with_components(
+            student_id,
+            &[lecture.id, lab_one.id],
+            &admin,
+            &pool
+        )
+        .await
+        .is_err()
+    );
+    let lecture_registrations = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM registrations WHERE student_id = $1 AND offering_id = $2"#,
+        student_id,
+        lecture.id
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(lecture_registrations, 0);
+
+    let registrations = registration_service::enroll_with_components(
+        student_id,
+        &[lecture.id, lab_two.id],
+        &admin,
+        &pool,
+    )
+    .await?;
+    assert_eq!(registrations.len(), 2);
+    // Tuition is charged once for the course, not once per component.
+    assert_eq!(
+        billing_service::get_balance(student_id, &pool).await?,
+        30_000
+    );
+
+    // A graded lecture and lab earn the course's credits once, at the lecture's grade.
+    sqlx::query!(
+        "UPDATE registrations SET grade = CASE WHEN offering_id = $2 THEN 'A'::grade ELSE 'C'::grade END WHERE student_id = $1 AND offering_id = ANY($3)",
+        student_id,
+        lecture.id,
+        &[lecture.id, lab_two.id]
+    )
+    .execute(&pool)
+    .await?;
+    assert_eq!(
+        transcript_service::get_earned_credits(student_id, &pool).await?,
+        3
+    );
+    assert_eq!(
+        transcript_service::get_gpa(student_id, &pool).await?,
+        Some(4.0)
+    );
+
+    // The lab cannot be dropped on its own, but dropping the lecture drops the lab too.
+    assert!(
+        registration_service::drop_course(student_id, lab_two.id, &admin, &pool)
+            .await
+            .is_err()
+    );
+    registration_service::drop_course(student_id, lecture.id, &admin, &pool).await?;
+    let still_registered = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM registrations WHERE student_id = $1 AND offering_id = ANY($2) AND status = 'registered'"#,
+        student_id,
+        &[lecture.id, lab_two.id]
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(still_registered, 0);
+    assert_eq!(billing_service::get_balance(student_id, &pool).await?, 0);
+
+    Ok(())
+}
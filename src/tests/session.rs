@@ -0,0 +1,173 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_login_returns_user(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::models::user::{FullName, Role};
+    use crate::notifications::RecordingNotifier;
+    use crate::services::{email_verification_service, user_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+    let notifier = RecordingNotifier::default();
+
+    let (registered, _profile) = user_service::register_student(
+        "carol@example.edu".to_string(),
+        "correct horse".to_string(),
+        FullName::new("Carol", "Student"),
+        2025,
+        &notifier,
+        &pool,
+    )
+    .await?;
+    let verification = notifier
+        .last_token()
+        .expect("A verification token should be sent");
+    email_verification_service::verify_email(&verification, &pool).await?;
+
+    let user = user_service::try_login("carol@example.edu", "correct horse", "test", &pool)
+        .await?
+        .expect("Correct credentials should log in");
+    assert_eq!(user.id, registered.id);
+    assert_eq!(user.role, Role::Student);
+
+    let wrong_password =
+        user_service::try_login("carol@example.edu", "battery staple", "test", &pool).await?;
+    assert!(wrong_password.is_none());
+    let unknown_user =
+        user_service::try_login("nobody@example.edu", "correct horse", "test", &pool).await?;
+    assert!(unknown_user.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_session_authenticates_until_revoked(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::services::session_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+
+    let (session, token) = session_service::create_session(student_id, &pool).await?;
+    let (authenticated, actor) = session_service::authenticate(&token, &pool)
+        .await?
+        .expect("A new session should authenticate");
+    assert_eq!(authenticated.id, session.id);
+    assert_eq!(actor.user_id, student_id);
+
+    assert!(
+        session_service::authenticate("not-a-token", &pool)
+            .await?
+            .is_none()
+    );
+
+    assert!(session_service::revoke_session(&token, &pool).await?);
+    assert!(
+        session_service::authenticate(&token, &pool)
+            .await?
+            .is_none()
+    );
+    assert!(!session_service::revoke_session(&token, &pool).await?);
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_idle_and_expired_sessions_are_rejected(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::services::session_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+
+    let (idle, idle_token) = session_service::create_session(student_id, &pool).await?;
+    sqlx::query!(
+        "UPDATE sessions SET last_seen_at = now() - interval '31 minutes' WHERE id = $1",
+        idle.id
+    )
+    .execute(&pool)
+    .await?;
+    assert!(
+        session_service::authenticate(&idle_token, &pool)
+            .await?
+            .is_none()
+    );
+
+    let (expired, expired_token) = session_service::create_session(student_id, &pool).await?;
+    sqlx::query!(
+        "UPDATE sessions SET expires_at = now() - interval '1 minute' WHERE id = $1",
+        expired.id
+    )
+    .execute(&pool)
+    .await?;
+    assert!(
+        session_service::authenticate(&expired_token, &pool)
+            .await?
+            .is_none()
+    );
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_revoke_all_sessions(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::security::authorization::Actor;
+    use crate::services::session_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let admin_id = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let instructor = Actor::load(instructor_id, &pool)
+        .await?
+        .expect("Seed instructor should exist");
+    let admin = Actor::load(admin_id, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let (_first, first_token) = session_service::create_session(student_id, &pool).await?;
+    let (_second, second_token) = session_service::create_session(student_id, &pool).await?;
+
+    let refused = session_service::revoke_all_sessions(student_id, &instructor, &pool).await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+
+    assert_eq!(
+        session_service::revoke_all_sessions(student_id, &student, &pool).await?,
+        2
+    );
+    assert!(
+        session_service::authenticate(&first_token, &pool)
+            .await?
+            .is_none()
+    );
+    assert!(
+        session_service::authenticate(&second_token, &pool)
+            .await?
+            .is_none()
+    );
+
+    let (_third, _third_token) = session_service::create_session(student_id, &pool).await?;
+    assert_eq!(
+        session_service::revoke_all_sessions(student_id, &admin, &pool).await?,
+        1
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_swap_into_full_offering_keeps_original(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::models::registration::RegistrationStatus;
+    use crate::security::authorization::Actor;
+    use crate::services::registration_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let other_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO users (email, hashed_password, first_name, last_name, role)
+        VALUES ('a@example.edu', 'x', 'A', 'A', 'student')
+        RETURNING id
+        "#
+    )
+    .fetch_one(&pool)
+    .await?;
+    let cs101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room CS101'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 = sqlx::query_scalar!(
+        "UPDATE course_offerings SET capacity = 1 WHERE location = 'Room MATH201' RETURNING id"
+    )
+    .fetch_one(&pool)
+    .await?;
+    registration_service::enroll(other_id, math101, &admin, &pool).await?;
+
+    let full =
+        registration_service::swap_registration(student_id, cs101, math101, &admin, &pool).await;
+    assert!(full.is_err(), "MATH101 should be full.");
+
+    let status = sqlx::query_scalar!(
+        r#"SELECT status AS "status: RegistrationStatus" FROM registrations WHERE student_id = $1 AND offering_id = $2"#,
+        student_id,
+        cs101
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(status, RegistrationStatus::Registered);
+
+    // Once a seat opens up, the swap goes through.
+    registration_service::drop_course(other_id, math101, &admin, &pool).await?;
+    let (dropped, added) =
+        registration_service::swap_registration(student_id, cs101, math101, &admin, &pool).await?;
+    assert_eq!(dropped.offering_id, Some(cs101));
+    assert_eq!(dropped.status.to_string(), "dropped");
+    assert_eq!(added.offering_id, Some(math101));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_swap_requires_existing_registration(pool: PgPool) -> Result<(), crate::error::Error> {
+    use crate::security::authorization::Actor;
+    use crate::services::registration_service;
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let cs101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room CS101'")
+            .fetch_one(&pool)
+            .await?;
+    let math101 =
+        sqlx::query_scalar!("SELECT id FROM course_offerings WHERE location = 'Room MATH201'")
+            .fetch_one(&pool)
+            .await?;
+
+    let not_registered =
+        registration_service::swap_registration(student_id, math101, cs101, &admin, &pool).await;
+    assert!(not_registered.is_err());
+    let count = sqlx::query_scalar!(
+        "SELECT COUNT(*) AS \"count!\" FROM registrations WHERE student_id = $1 AND status = 'registered'",
+        student_id
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
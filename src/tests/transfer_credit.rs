@@ -0,0 +1,157 @@
+#[cfg(test)]
+use sqlx::PgPool;
+
+#[sqlx::test(migrations = "./migrations_test")]
+async fn test_transfer_credit_satisfies_prerequisite(
+    pool: PgPool,
+) -> Result<(), crate::error::Error> {
+    use crate::error::Error;
+    use crate::models::course_equivalency::Equivalent;
+    use crate::models::course_offering::{CourseOffering, Section, SectionType};
+    use crate::security::authorization::Actor;
+    use crate::services::{registration_service, transcript_service, transfer_credit_service};
+    dotenvy::from_path("test.env").expect("Failed to load test.env");
+
+    let admin = sqlx::query_scalar!("SELECT id FROM users WHERE email = 'admin@example.edu'")
+        .fetch_one(&pool)
+        .await?;
+    let admin = Actor::load(admin, &pool)
+        .await?
+        .expect("Seed admin should exist");
+
+    let student_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'student@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let instructor_id =
+        sqlx::query_scalar!("SELECT id FROM users WHERE email = 'instructor@example.edu'")
+            .fetch_one(&pool)
+            .await?;
+    let cs101 = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'CS101'")
+        .fetch_one(&pool)
+        .await?;
+    let cs102 = sqlx::query_scalar!("SELECT id FROM courses WHERE course_number = 'CS102'")
+        .fetch_one(&pool)
+        .await?;
+    let offering = CourseOffering::create(
+        cs102,
+        1,
+        instructor_id,
+        Section::new(SectionType::Lecture, "001"),
+        30,
+        "Room CS102".to_string(),
+        &pool,
+    )
+    .await?;
+
+    // CS102 requires CS101, which the student has not passed here.
+    let refused = registration_service::enroll(student_id, offering.id, &admin, &pool).await;
+    assert!(matches!(refused, Err(Error::Conflict(message)) if message.contains("CS101")));
+
+    let student = Actor::load(student_id, &pool)
+        .await?
+        .expect("Seed student should exist");
+    let refused = transfer_credit_service::create_institution(
+        "Okanagan College".to_string(),
+        None,
+        &student,
+        &pool,
+    )
+    .await;
+    assert!(matches!(refused, Err(Error::Forbidden(_))));
+
+    let institution = transfer_credit_service::create_institution(
+        "Okanagan College".to_string(),
+        None,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let equivalency = transfer_credit_service::create_equivalency(
+        institution.id.expect("Institution should have an ID."),
+        "CPSC 111".to_string(),
+        "Computer Programming I".to_string(),
+        Equivalent::Course(cs101),
+        3,
+        &admin,
+        &pool,
+    )
+    .await?;
+    transfer_credit_service::record_transfer_credit(
+        student_id,
+        equivalency.id.expect("Equivalency should have an ID."),
+        Some("A".to_string()),
+        Some("Fall 2023".to_string()),
+        &admin,
+        &pool,
+    )
+    .await?;
+
+    let recorded_by = sqlx::query_scalar!(
+        "SELECT actor_id FROM audit_log WHERE entity = 'transfer_credits' AND action = 'create'"
+    )
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(recorded_by, Some(admin.user_id));
+
+    registration_service::enroll(student_id, offering.id, &admin, &pool).await?;
+
+    // Transfer credit counts toward earned credits but not GPA.
+    assert_eq!(
+        transcript_service::get_earned_credits(student_id, &pool).await?,
+        3
+    );
+    assert_eq!(transcript_service::get_gpa(student_id, &pool).await?, None);
+
+    // Credit for the same course from a second institution is not counted again.
+    let other = transfer_credit_service::create_institution(
+        "Thompson Rivers University".to_string(),
+        None,
+        &admin,
+        &pool,
+    )
+    .await?;
+    let other_equivalency = transfer_credit_service::create_equivalency(
+        other.id.expect("Institution should have an ID."),
+        "COMP 1130".to_string(),
+        "Computer Programming I".to_string(),
+        Equivalent::Course(cs101),
+        3,
+        &admin,
+        &pool,
+    )
+    .await?;
+    transfer_credit_service::record_transfer_credit(
+        student_id,
+        other_equivalency
+            .id
+            .expect("Equivalency should have an ID."),
+        None,
+        None,
+        &admin,
+        &pool,
+    )
+    .await?;
+    assert_eq!(
+        transcript_service::get_earned_credits(student_id, &pool).await?,
+        3
+    );
+
+    // Once CS101 is also passed here, only the local course's credits count.
+    sqlx::query!(
+        r#"
+        UPDATE registrations SET grade = 'A'
+        WHERE student_id = $1 AND offering_id IN (SELECT id FROM course_offerings WHERE course_id = $2)
+        "#,
+        student_id,
+        cs101
+    )
+    .execute(&pool)
+    .await?;
+    assert_eq!(
+        transcript_service::get_earned_credits(student_id, &pool).await?,
+        4
+    );
+
+    Ok(())
+}
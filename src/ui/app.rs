@@ -1,12 +1,23 @@
+use std::sync::Arc;
+
 use iced::{Element, Task};
 use sqlx::PgPool;
 use tokio::runtime::Handle;
 
-use crate::models::student_profile::StudentMajor;
-use crate::models::user::FullName;
+use crate::models::user::{FullName, Role};
+use crate::notifications::{FileNotifier, Notifier};
+use crate::ui::forgot_password::{self, ForgotPasswordState};
+use crate::ui::home::{
+    self, AdminHomeState, Impersonating, InstructorHomeState, SignedIn, StudentHomeState,
+};
 use crate::ui::login;
+use crate::ui::mfa::{self, MfaState};
 use crate::ui::page::{Page, PageMessage};
 use crate::ui::register;
+use crate::ui::verify_email::{self, VerifyEmailState};
+
+/// The source recorded for logins from this desktop client.
+const LOGIN_SOURCE: &str = "desktop";
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -16,8 +27,11 @@ pub enum Message {
 // The application state now holds a current page.
 pub struct App {
     pub current_page: Page,
+    pub session: Option<SignedIn>,
     pub pool: PgPool,
     pub handle: Handle,
+    /// Delivers password reset and email verification tokens.
+    pub notifier: Arc<dyn Notifier>,
 }
 
 impl App {
@@ -26,8 +40,10 @@ impl App {
         (
             Self {
                 current_page: Page::Login(login::LoginState::default()),
+                session: None,
                 pool,
                 handle,
+                notifier: Arc::new(FileNotifier::from_env()),
             },
             Task::none(),
         )
@@ -68,15 +84,42 @@ impl App {
                                 Task::perform(
                                     async move {
                                         let join_handle = handle.spawn(async move {
-                                            crate::services::user_service::try_login(
-                                                &email, &password, &pool,
-                                            )
-                                            .await
+                                            let Some(user) =
+                                                crate::services::user_service::try_login(
+                                                    &email,
+                                                    &password,
+                                                    LOGIN_SOURCE,
+                                                    &pool,
+                                                )
+                                                .await?
+                                            else {
+                                                return Ok(None);
+                                            };
+                                            let (session, token) =
+                                                crate::services::session_service::create_session(
+                                                    user.id, &pool,
+                                                )
+                                                .await?;
+                                            Ok::<_, crate::error::Error>(Some(SignedIn {
+                                                token,
+                                                user_id: user.id,
+                                                name: format!(
+                                                    "{} {}",
+                                                    user.first_name, user.last_name
+                                                ),
+                                                role: user.role,
+                                                mfa_pending: session.mfa_pending,
+                                                impersonation: None,
+                                            }))
                                         });
-                                        join_handle
-                                            .await
-                                            .expect("Tokio task panicked")
-                                            .map_err(|e| e.to_string())
+                                        join_handle.await.expect("Tokio task panicked").map_err(
+                                            |e| match e {
+                                                crate::error::Error::EmailNotVerified => {
+                                                    login::LoginFailure::EmailNotVerified
+                                                }
+                                                e => login::LoginFailure::Other(e.to_string()),
+                                            },
+                                        )
                                     },
                                     |result| {
                                         // Wrap the returned PageMessage into a Message
@@ -89,11 +132,31 @@ impl App {
                                 Task::none()
                             }
                         }
+                        login::Message::LoginResult(Ok(Some(signed_in))) => {
+                            if signed_in.mfa_pending {
+                                // The session is not usable until the second login step is done.
+                                let task = self.load_mfa_setup(&signed_in);
+                                self.current_page = Page::Mfa(MfaState::new(signed_in));
+                                return task;
+                            }
+                            self.current_page = home_page(&signed_in);
+                            self.session = Some(signed_in);
+                            Task::none()
+                        }
+                        login::Message::LoginResult(Err(login::LoginFailure::EmailNotVerified)) => {
+                            if let Page::Login(ref login_state) = self.current_page {
+                                self.current_page = Page::VerifyEmail(VerifyEmailState {
+                                    email: login_state.email.clone(),
+                                    status: Some("Verify your email before logging in.".into()),
+                                    ..VerifyEmailState::default()
+                                });
+                            }
+                            Task::none()
+                        }
                         login::Message::LoginResult(result) => {
                             if let Page::Login(ref mut login_state) = self.current_page {
                                 login_state.login_status = match result {
-                                    Ok(true) => Some("Login successful".into()),
-                                    Ok(false) => Some("Invalid credentials".into()),
+                                    Ok(_) => Some("Invalid credentials".into()),
                                     Err(e) => Some(format!("An error occurred: {}", e)),
                                 };
                             }
@@ -104,6 +167,11 @@ impl App {
                             self.current_page = Page::Register(register::RegisterState::default());
                             Task::none()
                         }
+                        login::Message::ForgotPasswordClicked => {
+                            self.current_page =
+                                Page::ForgotPassword(ForgotPasswordState::default());
+                            Task::none()
+                        }
                     }
                 }
                 // --- Registration page messages ---
@@ -136,6 +204,13 @@ impl App {
                             if let Page::Register(ref register_state) = self.current_page {
                                 let email = register_state.email.clone();
                                 let password = register_state.password.clone();
+                                let (first, last) = register_state
+                                    .name
+                                    .trim()
+                                    .split_once(' ')
+                                    .unwrap_or((register_state.name.trim(), ""));
+                                let name = FullName::new(first, last.trim());
+                                let notifier = self.notifier.clone();
                                 let pool = self.pool.clone();
                                 let handle = self.handle.clone();
                                 Task::perform(
@@ -144,9 +219,9 @@ impl App {
                                             crate::services::user_service::register_student(
                                                 email,
                                                 password,
-                                                FullName::new("test", "test"),
+                                                name,
                                                 1,
-                                                StudentMajor::Mathematics,
+                                                notifier.as_ref(),
                                                 &pool,
                                             )
                                             .await
@@ -155,7 +230,16 @@ impl App {
                                             .await
                                             .expect("Tokio task panicked")
                                             .map(|(_user, _profile)| true) // Convert the (User, StudentProfile) to Ok(true)
-                                            .map_err(|e| e.to_string())
+                                            .map_err(|e| match e {
+                                                crate::error::Error::WeakPassword(weak) => {
+                                                    register::RegisterFailure::WeakPassword(
+                                                        weak.violations,
+                                                    )
+                                                }
+                                                e => {
+                                                    register::RegisterFailure::Other(e.to_string())
+                                                }
+                                            })
                                     },
                                     |result| {
                                         Message::Page(crate::ui::page::PageMessage::Register(
@@ -170,26 +254,557 @@ impl App {
 
                         register::Message::RegisterResult(result) => {
                             if let Page::Register(ref mut register_state) = self.current_page {
+                                if let Ok(true) = result {
+                                    // New accounts are pending until their email is verified.
+                                    let email = register_state.email.trim().to_lowercase();
+                                    self.current_page = Page::VerifyEmail(VerifyEmailState {
+                                        email,
+                                        ..VerifyEmailState::default()
+                                    });
+                                    return Task::none();
+                                }
+                                register_state.password_errors.clear();
                                 register_state.register_status = match result {
                                     Ok(true) => Some("Login successful".into()),
                                     Ok(false) => Some("Failed to register.".into()),
-                                    Err(e) => Some(format!("An error occurred: {}", e)),
+                                    Err(register::RegisterFailure::WeakPassword(violations)) => {
+                                        register_state.password_errors = violations;
+                                        None
+                                    }
+                                    Err(register::RegisterFailure::Other(e)) => {
+                                        Some(format!("An error occurred: {}", e))
+                                    }
                                 };
                             }
                             Task::none()
                         }
                     }
                 }
+                // --- Forgot password page messages ---
+                crate::ui::page::PageMessage::ForgotPassword(forgot_msg) => {
+                    let Page::ForgotPassword(ref mut forgot_state) = self.current_page else {
+                        return Task::none();
+                    };
+                    match forgot_msg {
+                        forgot_password::Message::BackToLogin => {
+                            self.current_page = Page::Login(login::LoginState::default());
+                            Task::none()
+                        }
+                        forgot_password::Message::EmailChanged(email) => {
+                            forgot_state.email = email;
+                            Task::none()
+                        }
+                        forgot_password::Message::TokenChanged(token) => {
+                            forgot_state.token = token;
+                            Task::none()
+                        }
+                        forgot_password::Message::NewPasswordChanged(password) => {
+                            forgot_state.new_password = password;
+                            Task::none()
+                        }
+                        forgot_password::Message::SendTokenClicked => {
+                            let email = forgot_state.email.clone();
+                            let notifier = self.notifier.clone();
+                            let pool = self.pool.clone();
+                            let handle = self.handle.clone();
+                            Task::perform(
+                                async move {
+                                    let join_handle = handle.spawn(async move {
+                                        crate::services::password_reset_service::request_password_reset(
+                                            &email,
+                                            notifier.as_ref(),
+                                            &pool,
+                                        )
+                                        .await
+                                    });
+                                    join_handle
+                                        .await
+                                        .expect("Tokio task panicked")
+                                        .map_err(|e| e.to_string())
+                                },
+                                |result| {
+                                    Message::Page(crate::ui::page::PageMessage::ForgotPassword(
+                                        forgot_password::Message::SendTokenResult(result),
+                                    ))
+                                },
+                            )
+                        }
+                        forgot_password::Message::SendTokenResult(result) => {
+                            forgot_state.status = match result {
+                                Ok(()) => Some(
+                                    "If an account exists for that email, a reset token has been sent."
+                                        .into(),
+                                ),
+                                Err(e) => Some(format!("An error occurred: {}", e)),
+                            };
+                            Task::none()
+                        }
+                        forgot_password::Message::ResetClicked => {
+                            let token = forgot_state.token.trim().to_string();
+                            let password = forgot_state.new_password.clone();
+                            let pool = self.pool.clone();
+                            let handle = self.handle.clone();
+                            Task::perform(
+                                async move {
+                                    let join_handle = handle.spawn(async move {
+                                        crate::services::password_reset_service::reset_password(
+                                            &token, &password, &pool,
+                                        )
+                                        .await
+                                    });
+                                    join_handle
+                                        .await
+                                        .expect("Tokio task panicked")
+                                        .map_err(|e| e.to_string())
+                                },
+                                |result| {
+                                    Message::Page(crate::ui::page::PageMessage::ForgotPassword(
+                                        forgot_password::Message::ResetResult(result),
+                                    ))
+                                },
+                            )
+                        }
+                        forgot_password::Message::ResetResult(result) => {
+                            match result {
+                                Ok(()) => {
+                                    self.current_page = Page::Login(login::LoginState {
+                                        login_status: Some(
+                                            "Password reset. Log in with your new password.".into(),
+                                        ),
+                                        ..login::LoginState::default()
+                                    });
+                                }
+                                Err(e) => forgot_state.status = Some(e),
+                            }
+                            Task::none()
+                        }
+                    }
+                }
+                // --- Email verification page messages ---
+                crate::ui::page::PageMessage::VerifyEmail(verify_msg) => {
+                    let Page::VerifyEmail(ref mut verify_state) = self.current_page else {
+                        return Task::none();
+                    };
+                    match verify_msg {
+                        verify_email::Message::BackToLogin => {
+                            self.current_page = Page::Login(login::LoginState::default());
+                            Task::none()
+                        }
+                        verify_email::Message::TokenChanged(token) => {
+                            verify_state.token = token;
+                            Task::none()
+                        }
+                        verify_email::Message::VerifyClicked => {
+                            let token = verify_state.token.trim().to_string();
+                            let pool = self.pool.clone();
+                            let handle = self.handle.clone();
+                            Task::perform(
+                                async move {
+                                    let join_handle = handle.spawn(async move {
+                                        crate::services::email_verification_service::verify_email(
+                                            &token, &pool,
+                                        )
+                                        .await
+                                    });
+                                    join_handle
+                                        .await
+                                        .expect("Tokio task panicked")
+                                        .map_err(|e| e.to_string())
+                                },
+                                |result| {
+                                    Message::Page(crate::ui::page::PageMessage::VerifyEmail(
+                                        verify_email::Message::VerifyResult(result),
+                                    ))
+                                },
+                            )
+                        }
+                        verify_email::Message::VerifyResult(result) => {
+                            match result {
+                                Ok(()) => {
+                                    self.current_page = Page::Login(login::LoginState {
+                                        email: verify_state.email.clone(),
+                                        login_status: Some(
+                                            "Email verified. You can now log in.".into(),
+                                        ),
+                                        ..login::LoginState::default()
+                                    });
+                                }
+                                Err(e) => verify_state.status = Some(e),
+                            }
+                            Task::none()
+                        }
+                        verify_email::Message::ResendClicked => {
+                            let email = verify_state.email.clone();
+                            let notifier = self.notifier.clone();
+                            let pool = self.pool.clone();
+                            let handle = self.handle.clone();
+                            Task::perform(
+                                async move {
+                                    let join_handle = handle.spawn(async move {
+                                        crate::services::email_verification_service::resend_verification(
+                                            &email,
+                                            notifier.as_ref(),
+                                            &pool,
+                                        )
+                                        .await
+                                    });
+                                    join_handle
+                                        .await
+                                        .expect("Tokio task panicked")
+                                        .map_err(|e| e.to_string())
+                                },
+                                |result| {
+                                    Message::Page(crate::ui::page::PageMessage::VerifyEmail(
+                                        verify_email::Message::ResendResult(result),
+                                    ))
+                                },
+                            )
+                        }
+                        verify_email::Message::ResendResult(result) => {
+                            verify_state.status = match result {
+                                Ok(()) => Some("A new verification token has been sent.".into()),
+                                Err(e) => Some(format!("An error occurred: {}", e)),
+                            };
+                            Task::none()
+                        }
+                    }
+                }
+                // --- Two-factor authentication page messages ---
+                crate::ui::page::PageMessage::Mfa(mfa_msg) => {
+                    let Page::Mfa(ref mut mfa_state) = self.current_page else {
+                        return Task::none();
+                    };
+                    match mfa_msg {
+                        mfa::Message::SetupLoaded(result) => {
+                            match result {
+                                Ok(setup) => mfa_state.setup = setup,
+                                Err(e) => mfa_state.status = Some(e),
+                            }
+                            Task::none()
+                        }
+                        mfa::Message::CodeChanged(code) => {
+                            mfa_state.code = code;
+                            Task::none()
+                        }
+                        mfa::Message::SubmitClicked => {
+                            let code = mfa_state.code.trim().to_string();
+                            let signed_in = mfa_state.signed_in.clone();
+                            let pool = self.pool.clone();
+                            let handle = self.handle.clone();
+                            Task::perform(
+                                async move {
+                                    let join_handle = handle.spawn(async move {
+                                        let now = chrono::Utc::now();
+                                        if signed_in.mfa_pending {
+                                            return crate::services::mfa_service::complete_login(
+                                                &signed_in.token,
+                                                &code,
+                                                now,
+                                                &pool,
+                                            )
+                                            .await;
+                                        }
+                                        let Some((_session, actor)) =
+                                            crate::services::session_service::authenticate(
+                                                &signed_in.token,
+                                                &pool,
+                                            )
+                                            .await?
+                                        else {
+                                            return Err(crate::error::Error::Unauthenticated(
+                                                "Your session has ended. Please log in again."
+                                                    .to_string(),
+                                            ));
+                                        };
+                                        crate::services::mfa_service::confirm_enrollment(
+                                            actor.user_id,
+                                            &code,
+                                            now,
+                                            &actor,
+                                            &pool,
+                                        )
+                                        .await
+                                    });
+                                    join_handle
+                                        .await
+                                        .expect("Tokio task panicked")
+                                        .map_err(|e| e.to_string())
+                                },
+                                |result| {
+                                    Message::Page(crate::ui::page::PageMessage::Mfa(
+                                        mfa::Message::SubmitResult(result),
+                                    ))
+                                },
+                            )
+                        }
+                        mfa::Message::SubmitResult(result) => {
+                            match result {
+                                Ok(()) => {
+                                    let mut signed_in = mfa_state.signed_in.clone();
+                                    signed_in.mfa_pending = false;
+                                    self.current_page = home_page(&signed_in);
+                                    self.session = Some(signed_in);
+                                }
+                                Err(e) => mfa_state.status = Some(e),
+                            }
+                            Task::none()
+                        }
+                        mfa::Message::CancelClicked => {
+                            if mfa_state.signed_in.mfa_pending {
+                                // Abandoning the second step abandons the login.
+                                self.current_page = Page::Login(login::LoginState::default());
+                            } else {
+                                self.current_page = home_page(&mfa_state.signed_in);
+                            }
+                            Task::none()
+                        }
+                    }
+                }
+                // --- Home page messages ---
+                crate::ui::page::PageMessage::Home(home_msg) => match home_msg {
+                    home::Message::EnableMfaClicked => {
+                        let Some(signed_in) = self.session.clone() else {
+                            return Task::none();
+                        };
+                        let task = self.load_mfa_setup(&signed_in);
+                        self.current_page = Page::Mfa(MfaState::new(signed_in));
+                        task
+                    }
+                    home::Message::LogoutClicked => {
+                        self.current_page = Page::Login(login::LoginState::default());
+                        let Some(signed_in) = self.session.take() else {
+                            return Task::none();
+                        };
+                        let pool = self.pool.clone();
+                        let handle = self.handle.clone();
+                        // Revoke the session in the background; the user is signed out locally either way.
+                        // Logging out while impersonating ends the impersonation and signs the admin out too.
+                        Task::future(async move {
+                            let _ = handle
+                                .spawn(async move {
+                                    let token = match signed_in.impersonation {
+                                        Some(impersonation) => {
+                                            crate::services::impersonation_service::stop_impersonation(
+                                                &signed_in.token,
+                                                &pool,
+                                            )
+                                            .await?;
+                                            impersonation.admin.token
+                                        }
+                                        None => signed_in.token,
+                                    };
+                                    crate::services::session_service::revoke_session(&token, &pool)
+                                        .await
+                                })
+                                .await;
+                        })
+                        .discard()
+                    }
+                    home::Message::ImpersonateEmailChanged(email) => {
+                        if let Page::AdminHome(ref mut admin_state) = self.current_page {
+                            admin_state.impersonate_email = email;
+                        }
+                        Task::none()
+                    }
+                    home::Message::ImpersonateReasonChanged(reason) => {
+                        if let Page::AdminHome(ref mut admin_state) = self.current_page {
+                            admin_state.impersonate_reason = reason;
+                        }
+                        Task::none()
+                    }
+                    home::Message::ImpersonateAllowChangesToggled(allow_changes) => {
+                        if let Page::AdminHome(ref mut admin_state) = self.current_page {
+                            admin_state.impersonate_allow_changes = allow_changes;
+                        }
+                        Task::none()
+                    }
+                    home::Message::ImpersonateClicked => {
+                        let (Page::AdminHome(admin_state), Some(admin)) =
+                            (&self.current_page, self.session.clone())
+                        else {
+                            return Task::none();
+                        };
+                        let email = admin_state.impersonate_email.clone();
+                        let reason = admin_state.impersonate_reason.clone();
+                        let read_only = !admin_state.impersonate_allow_changes;
+                        let pool = self.pool.clone();
+                        let handle = self.handle.clone();
+                        Task::perform(
+                            async move {
+                                let join_handle =
+                                    handle.spawn(async move {
+                                        let Some((_session, actor)) =
+                                            crate::services::session_service::authenticate(
+                                                &admin.token,
+                                                &pool,
+                                            )
+                                            .await?
+                                        else {
+                                            return Err(crate::error::Error::Unauthenticated(
+                                                "Your session has ended. Please log in again."
+                                                    .to_string(),
+                                            ));
+                                        };
+                                        let student =
+                                            crate::services::user_service::find_user_by_email(
+                                                &email, &actor, &pool,
+                                            )
+                                            .await?
+                                            .ok_or_else(|| {
+                                                crate::error::Error::NotFound(
+                                                    "No account has that email.".to_string(),
+                                                )
+                                            })?;
+                                        let (_impersonation, token) =
+                                        crate::services::impersonation_service::start_impersonation(
+                                            student.id, &reason, read_only, &actor, &pool,
+                                        )
+                                        .await?;
+                                        Ok::<_, crate::error::Error>(SignedIn {
+                                            token,
+                                            user_id: student.id,
+                                            name: format!(
+                                                "{} {}",
+                                                student.first_name, student.last_name
+                                            ),
+                                            role: student.role,
+                                            mfa_pending: false,
+                                            impersonation: Some(Impersonating {
+                                                admin: Box::new(admin),
+                                                read_only,
+                                            }),
+                                        })
+                                    });
+                                join_handle
+                                    .await
+                                    .expect("Tokio task panicked")
+                                    .map_err(|e| e.to_string())
+                            },
+                            |result| {
+                                Message::Page(crate::ui::page::PageMessage::Home(
+                                    home::Message::ImpersonationStarted(result),
+                                ))
+                            },
+                        )
+                    }
+                    home::Message::ImpersonationStarted(Ok(signed_in)) => {
+                        self.current_page = home_page(&signed_in);
+                        self.session = Some(signed_in);
+                        Task::none()
+                    }
+                    home::Message::ImpersonationStarted(Err(e)) => {
+                        if let Page::AdminHome(ref mut admin_state) = self.current_page {
+                            admin_state.status = Some(e);
+                        }
+                        Task::none()
+                    }
+                    home::Message::StopImpersonationClicked => {
+                        let Some(signed_in) = self.session.take() else {
+                            return Task::none();
+                        };
+                        let Some(impersonation) = signed_in.impersonation else {
+                            self.session = Some(signed_in);
+                            return Task::none();
+                        };
+                        // Return to the admin's own session; the impersonation is ended in the background.
+                        let admin = *impersonation.admin;
+                        self.current_page = home_page(&admin);
+                        self.session = Some(admin);
+                        let token = signed_in.token;
+                        let pool = self.pool.clone();
+                        let handle = self.handle.clone();
+                        Task::future(async move {
+                            let _ = handle
+                                .spawn(async move {
+                                    crate::services::impersonation_service::stop_impersonation(
+                                        &token, &pool,
+                                    )
+                                    .await
+                                })
+                                .await;
+                        })
+                        .discard()
+                    }
+                },
             },
         }
     }
 
+    /// Starts setting up an authenticator if the user does not have one yet, for the two-factor authentication page.
+    fn load_mfa_setup(&self, signed_in: &SignedIn) -> Task<Message> {
+        let signed_in = signed_in.clone();
+        let pool = self.pool.clone();
+        let handle = self.handle.clone();
+        Task::perform(
+            async move {
+                let join_handle = handle.spawn(async move {
+                    let session = if signed_in.mfa_pending {
+                        crate::services::session_service::authenticate_pending(
+                            &signed_in.token,
+                            &pool,
+                        )
+                        .await?
+                    } else {
+                        crate::services::session_service::authenticate(&signed_in.token, &pool)
+                            .await?
+                    };
+                    let Some((_session, actor)) = session else {
+                        return Err(crate::error::Error::Unauthenticated(
+                            "Your session has ended. Please log in again.".to_string(),
+                        ));
+                    };
+                    if crate::services::mfa_service::is_enrolled(actor.user_id, &pool).await? {
+                        return Ok(None);
+                    }
+                    crate::services::mfa_service::begin_enrollment(actor.user_id, &actor, &pool)
+                        .await
+                        .map(Some)
+                });
+                join_handle
+                    .await
+                    .expect("Tokio task panicked")
+                    .map_err(|e: crate::error::Error| e.to_string())
+            },
+            |result| {
+                Message::Page(crate::ui::page::PageMessage::Mfa(
+                    mfa::Message::SetupLoaded(result),
+                ))
+            },
+        )
+    }
+
     /// Constructs the view.
     pub fn view(&self) -> Element<Message> {
         self.current_page.view().map(Message::Page)
     }
 }
 
+/// The home page for a signed-in user's role.
+fn home_page(signed_in: &SignedIn) -> Page {
+    let name = signed_in.name.clone();
+    match signed_in.role {
+        Role::Student => Page::StudentHome(StudentHomeState {
+            impersonation_banner: signed_in.impersonation.as_ref().map(|impersonation| {
+                let mode = if impersonation.read_only {
+                    "read-only"
+                } else {
+                    "changes allowed"
+                };
+                format!(
+                    "{} is viewing as {} ({})",
+                    impersonation.admin.name, name, mode
+                )
+            }),
+            name,
+        }),
+        Role::Instructor => Page::InstructorHome(InstructorHomeState { name }),
+        Role::Admin => Page::AdminHome(AdminHomeState {
+            name,
+            ..AdminHomeState::default()
+        }),
+    }
+}
+
 // Free functions required by iced::application.
 
 pub fn title_fn(app: &App) -> String {
@@ -0,0 +1,73 @@
+use iced::{
+    Alignment, Element, Padding,
+    widget::{Button, Column, Text, TextInput},
+};
+
+/// Messages for the forgot password view.
+#[derive(Debug, Clone)]
+pub enum Message {
+    EmailChanged(String),
+    SendTokenClicked,
+    SendTokenResult(Result<(), String>),
+    TokenChanged(String),
+    NewPasswordChanged(String),
+    ResetClicked,
+    ResetResult(Result<(), String>),
+    BackToLogin,
+}
+
+/// Forgot password state. The user first requests a reset token for their email, then enters the token with a new password.
+#[derive(Default)]
+pub struct ForgotPasswordState {
+    pub email: String,
+    pub token: String,
+    pub new_password: String,
+    pub status: Option<String>,
+}
+
+impl ForgotPasswordState {
+    /// Builds the forgot password UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        let email_input = TextInput::new("Email", &self.email)
+            .on_input(Message::EmailChanged)
+            .padding(10);
+
+        let send_button = Button::new(Text::new("Send reset token"))
+            .on_press(Message::SendTokenClicked)
+            .padding(10);
+
+        let token_input = TextInput::new("Reset token", &self.token)
+            .on_input(Message::TokenChanged)
+            .padding(10);
+
+        let password_input = TextInput::new("New password", &self.new_password)
+            .on_input(Message::NewPasswordChanged)
+            .secure(true)
+            .padding(10);
+
+        let reset_button = Button::new(Text::new("Reset password"))
+            .on_press(Message::ResetClicked)
+            .padding(10);
+
+        let back_button = Button::new(Text::new("Back to Login"))
+            .on_press(Message::BackToLogin)
+            .padding(10);
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .padding(Padding::from([150, 100]))
+            .push(email_input)
+            .push(send_button)
+            .push(token_input)
+            .push(password_input)
+            .push(reset_button)
+            .push(back_button);
+
+        if let Some(ref status) = self.status {
+            content = content.push(Text::new(status));
+        }
+
+        content.into()
+    }
+}
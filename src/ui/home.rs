@@ -0,0 +1,155 @@
+use iced::{
+    Alignment, Element, Padding,
+    widget::{Button, Checkbox, Column, Container, Row, Text, TextInput},
+};
+use uuid::Uuid;
+
+use crate::models::user::Role;
+
+/// The signed-in user and their session token, kept by the app for the lifetime of the session.
+#[derive(Debug, Clone)]
+pub struct SignedIn {
+    pub token: String,
+    pub user_id: Uuid,
+    pub name: String,
+    pub role: Role,
+    /// Set until the second login step is completed.
+    pub mfa_pending: bool,
+    /// Set while an admin is viewing the app as this user.
+    pub impersonation: Option<Impersonating>,
+}
+
+/// An admin viewing the app as a student. The admin's own sign-in is kept to return to when they stop.
+#[derive(Debug, Clone)]
+pub struct Impersonating {
+    pub admin: Box<SignedIn>,
+    pub read_only: bool,
+}
+
+/// Messages shared by the home pages.
+#[derive(Debug, Clone)]
+pub enum Message {
+    LogoutClicked,
+    EnableMfaClicked,
+    ImpersonateEmailChanged(String),
+    ImpersonateReasonChanged(String),
+    ImpersonateAllowChangesToggled(bool),
+    ImpersonateClicked,
+    ImpersonationStarted(Result<SignedIn, String>),
+    StopImpersonationClicked,
+}
+
+/// Home page for students.
+pub struct StudentHomeState {
+    pub name: String,
+    /// Shown above the page while an admin is viewing it as the student.
+    pub impersonation_banner: Option<String>,
+}
+
+/// Home page for instructors.
+pub struct InstructorHomeState {
+    pub name: String,
+}
+
+/// Home page for administrators, from which they can view the app as a student.
+#[derive(Default)]
+pub struct AdminHomeState {
+    pub name: String,
+    pub impersonate_email: String,
+    pub impersonate_reason: String,
+    /// Impersonations are read-only unless the admin allows changes.
+    pub impersonate_allow_changes: bool,
+    pub status: Option<String>,
+}
+
+impl StudentHomeState {
+    /// Builds the student home UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        if let Some(ref banner) = self.impersonation_banner {
+            // Admins cannot change the student's security settings, so only the way back is offered.
+            let stop_button = Button::new(Text::new("Stop viewing as student"))
+                .on_press(Message::StopImpersonationClicked)
+                .padding(10);
+            let banner = Container::new(
+                Row::new()
+                    .spacing(20)
+                    .align_y(Alignment::Center)
+                    .push(Text::new(banner))
+                    .push(stop_button),
+            )
+            .padding(10)
+            .style(iced::widget::container::rounded_box);
+            return Column::new()
+                .align_x(Alignment::Center)
+                .push(banner)
+                .push(home_view(&self.name, "Student"))
+                .into();
+        }
+        // Two-factor authentication is optional for students; admins set it up when they first log in.
+        let enable_mfa_button = Button::new(Text::new("Set up two-factor authentication"))
+            .on_press(Message::EnableMfaClicked)
+            .padding(10);
+        Column::new()
+            .align_x(Alignment::Center)
+            .push(home_view(&self.name, "Student"))
+            .push(enable_mfa_button)
+            .into()
+    }
+}
+
+impl InstructorHomeState {
+    /// Builds the instructor home UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        home_view(&self.name, "Instructor")
+    }
+}
+
+impl AdminHomeState {
+    /// Builds the administrator home UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        let email_input = TextInput::new("Student email", &self.impersonate_email)
+            .on_input(Message::ImpersonateEmailChanged)
+            .padding(10);
+        let reason_input = TextInput::new("Reason", &self.impersonate_reason)
+            .on_input(Message::ImpersonateReasonChanged)
+            .padding(10);
+        let allow_changes = Checkbox::new("Allow changes", self.impersonate_allow_changes)
+            .on_toggle(Message::ImpersonateAllowChangesToggled);
+        let impersonate_button = Button::new(Text::new("View as student"))
+            .on_press(Message::ImpersonateClicked)
+            .padding(10);
+
+        let mut impersonate = Column::new()
+            .spacing(10)
+            .align_x(Alignment::Center)
+            .padding(Padding::from([0, 100]))
+            .push(email_input)
+            .push(reason_input)
+            .push(allow_changes)
+            .push(impersonate_button);
+        if let Some(ref status) = self.status {
+            impersonate = impersonate.push(Text::new(status));
+        }
+
+        Column::new()
+            .align_x(Alignment::Center)
+            .push(home_view(&self.name, "Administrator"))
+            .push(impersonate)
+            .into()
+    }
+}
+
+fn home_view<'a>(name: &'a str, heading: &'a str) -> Element<'a, Message> {
+    let logout_button = Button::new(Text::new("Logout"))
+        .on_press(Message::LogoutClicked)
+        .padding(10);
+
+    Column::new()
+        .spacing(20)
+        .align_x(Alignment::Center)
+        .padding(Padding::from([250, 100]))
+        .push(Text::new(format!("{} Home", heading)))
+        .push(Text::new(format!("Welcome, {}", name)))
+        .push(logout_button)
+        .into()
+}
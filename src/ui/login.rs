@@ -1,8 +1,12 @@
+use std::fmt;
+
 use iced::{
     Alignment, Element, Padding,
     widget::{Button, Column, Text, TextInput},
 };
 
+use crate::ui::home::SignedIn;
+
 /// Messages for the login view.
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -10,7 +14,24 @@ pub enum Message {
     PasswordChanged(String),
     LoginClicked,
     RegisterClicked,
-    LoginResult(Result<bool, String>),
+    ForgotPasswordClicked,
+    LoginResult(Result<Option<SignedIn>, LoginFailure>),
+}
+
+/// Why a login attempt failed. Unverified accounts are sent on to verify their email.
+#[derive(Debug, Clone)]
+pub enum LoginFailure {
+    EmailNotVerified,
+    Other(String),
+}
+
+impl fmt::Display for LoginFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoginFailure::EmailNotVerified => write!(f, "Your email has not been verified yet."),
+            LoginFailure::Other(e) => write!(f, "{}", e),
+        }
+    }
 }
 
 /// Login state holding email, password, and login status.
@@ -41,6 +62,10 @@ impl LoginState {
             .on_press(Message::RegisterClicked)
             .padding(10);
 
+        let forgot_password_button = Button::new(Text::new("Forgot password"))
+            .on_press(Message::ForgotPasswordClicked)
+            .padding(10);
+
         let mut content = Column::new()
             .spacing(20)
             .align_x(Alignment::Center)
@@ -48,7 +73,8 @@ impl LoginState {
             .push(email_input)
             .push(password_input)
             .push(login_button)
-            .push(register_button);
+            .push(register_button)
+            .push(forgot_password_button);
 
         if let Some(ref status) = self.login_status {
             content = content.push(Text::new(status));
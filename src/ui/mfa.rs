@@ -0,0 +1,89 @@
+use iced::{
+    Alignment, Element, Padding,
+    widget::{Button, Column, Text, TextInput},
+};
+
+use crate::services::mfa_service::MfaSetup;
+use crate::ui::home::SignedIn;
+
+/// Messages for the two-factor authentication view.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The authenticator setup to show, or `None` if the user already has one.
+    SetupLoaded(Result<Option<MfaSetup>, String>),
+    CodeChanged(String),
+    SubmitClicked,
+    SubmitResult(Result<(), String>),
+    CancelClicked,
+}
+
+/// Two-factor authentication state. Used both for the second login step and for setting up an authenticator, which users without one
+/// do before they can finish logging in.
+pub struct MfaState {
+    pub signed_in: SignedIn,
+    pub setup: Option<MfaSetup>,
+    pub code: String,
+    pub status: Option<String>,
+}
+
+impl MfaState {
+    pub fn new(signed_in: SignedIn) -> MfaState {
+        MfaState {
+            signed_in,
+            setup: None,
+            code: String::new(),
+            status: None,
+        }
+    }
+
+    /// Builds the two-factor authentication UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut content = Column::new()
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .padding(Padding::from([150, 100]));
+
+        match &self.setup {
+            Some(setup) => {
+                content = content
+                    .push(Text::new(
+                        "Add this account to your authenticator app, then enter the code it shows.",
+                    ))
+                    .push(Text::new(format!("Secret: {}", setup.secret)))
+                    .push(Text::new(setup.provisioning_uri.as_str()).size(12))
+                    .push(Text::new(
+                        "Recovery codes (each works once if you lose your authenticator):",
+                    ))
+                    .push(Text::new(setup.recovery_codes.join("  ")));
+            }
+            None => {
+                content = content.push(Text::new(
+                    "Enter the code from your authenticator app or a recovery code.",
+                ));
+            }
+        }
+
+        let code_input = TextInput::new("Code", &self.code)
+            .on_input(Message::CodeChanged)
+            .padding(10);
+
+        let submit_button = Button::new(Text::new("Continue"))
+            .on_press(Message::SubmitClicked)
+            .padding(10);
+
+        let cancel_button = Button::new(Text::new("Cancel"))
+            .on_press(Message::CancelClicked)
+            .padding(10);
+
+        content = content
+            .push(code_input)
+            .push(submit_button)
+            .push(cancel_button);
+
+        if let Some(ref status) = self.status {
+            content = content.push(Text::new(status));
+        }
+
+        content.into()
+    }
+}
@@ -1,4 +1,8 @@
 pub mod app;
+pub mod forgot_password;
+pub mod home;
 pub mod login;
+pub mod mfa;
 pub mod page;
 pub mod register;
+pub mod verify_email;
@@ -1,5 +1,11 @@
+use crate::ui::forgot_password::{ForgotPasswordState, Message as ForgotPasswordMessage};
+use crate::ui::home::{
+    AdminHomeState, InstructorHomeState, Message as HomeMessage, StudentHomeState,
+};
 use crate::ui::login::{LoginState, Message as LoginMessage};
-use crate::ui::register::{RegisterState, Message as RegisterMessage};
+use crate::ui::mfa::{Message as MfaMessage, MfaState};
+use crate::ui::register::{Message as RegisterMessage, RegisterState};
+use crate::ui::verify_email::{Message as VerifyEmailMessage, VerifyEmailState};
 use iced::Element;
 
 #[derive(Debug, Clone)]
@@ -7,12 +13,22 @@ pub enum PageMessage {
     // A unified message that wraps both login and register messages.
     Login(LoginMessage),
     Register(RegisterMessage),
+    ForgotPassword(ForgotPasswordMessage),
+    VerifyEmail(VerifyEmailMessage),
+    Mfa(MfaMessage),
+    Home(HomeMessage),
 }
 
 /// All possible pages.
 pub enum Page {
     Login(LoginState),
     Register(RegisterState),
+    ForgotPassword(ForgotPasswordState),
+    VerifyEmail(VerifyEmailState),
+    Mfa(MfaState),
+    StudentHome(StudentHomeState),
+    InstructorHome(InstructorHomeState),
+    AdminHome(AdminHomeState),
 }
 
 impl Page {
@@ -21,6 +37,14 @@ impl Page {
         match self {
             Page::Login(login_state) => login_state.view().map(PageMessage::Login),
             Page::Register(register_state) => register_state.view().map(PageMessage::Register),
+            Page::ForgotPassword(forgot_state) => {
+                forgot_state.view().map(PageMessage::ForgotPassword)
+            }
+            Page::VerifyEmail(verify_state) => verify_state.view().map(PageMessage::VerifyEmail),
+            Page::Mfa(mfa_state) => mfa_state.view().map(PageMessage::Mfa),
+            Page::StudentHome(home_state) => home_state.view().map(PageMessage::Home),
+            Page::InstructorHome(home_state) => home_state.view().map(PageMessage::Home),
+            Page::AdminHome(home_state) => home_state.view().map(PageMessage::Home),
         }
     }
-}
\ No newline at end of file
+}
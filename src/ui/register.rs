@@ -3,6 +3,8 @@ use iced::{
     widget::{Button, Column, Text, TextInput},
 };
 
+use crate::security::password_policy::PasswordViolation;
+
 // Registration view messages
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -10,7 +12,7 @@ pub enum Message {
     EmailChanged(String),
     PasswordChanged(String),
     RegisterClicked,
-    RegisterResult(Result<bool, String>),
+    RegisterResult(Result<bool, RegisterFailure>),
     BackToLogin,
 }
 
@@ -21,6 +23,15 @@ pub struct RegisterState {
     pub email: String,
     pub password: String,
     pub register_status: Option<String>,
+    /// Password policy violations from the last attempt, shown under the password field.
+    pub password_errors: Vec<PasswordViolation>,
+}
+
+/// Why registration failed. Password policy violations are kept separate so they can be shown next to the password field.
+#[derive(Debug, Clone)]
+pub enum RegisterFailure {
+    WeakPassword(Vec<PasswordViolation>),
+    Other(String),
 }
 
 impl RegisterState {
@@ -53,9 +64,13 @@ impl RegisterState {
             .padding(Padding::from([250, 100]))
             .push(name_input)
             .push(email_input)
-            .push(password_input)
-            .push(register_button)
-            .push(back_button);
+            .push(password_input);
+
+        for violation in &self.password_errors {
+            content = content.push(Text::new(violation.to_string()).size(14));
+        }
+
+        content = content.push(register_button).push(back_button);
 
         if let Some(ref status) = self.register_status {
             content = content.push(Text::new(status));
@@ -0,0 +1,65 @@
+use iced::{
+    Alignment, Element, Padding,
+    widget::{Button, Column, Text, TextInput},
+};
+
+/// Messages for the email verification view.
+#[derive(Debug, Clone)]
+pub enum Message {
+    TokenChanged(String),
+    VerifyClicked,
+    VerifyResult(Result<(), String>),
+    ResendClicked,
+    ResendResult(Result<(), String>),
+    BackToLogin,
+}
+
+/// Email verification state, for an account that is pending verification.
+#[derive(Default)]
+pub struct VerifyEmailState {
+    pub email: String,
+    pub token: String,
+    pub status: Option<String>,
+}
+
+impl VerifyEmailState {
+    /// Builds the email verification UI.
+    pub fn view(&self) -> Element<'_, Message> {
+        let prompt = Text::new(format!(
+            "Enter the verification token sent to {}.",
+            self.email
+        ));
+
+        let token_input = TextInput::new("Verification token", &self.token)
+            .on_input(Message::TokenChanged)
+            .padding(10);
+
+        let verify_button = Button::new(Text::new("Verify email"))
+            .on_press(Message::VerifyClicked)
+            .padding(10);
+
+        let resend_button = Button::new(Text::new("Send a new token"))
+            .on_press(Message::ResendClicked)
+            .padding(10);
+
+        let back_button = Button::new(Text::new("Back to Login"))
+            .on_press(Message::BackToLogin)
+            .padding(10);
+
+        let mut content = Column::new()
+            .spacing(20)
+            .align_x(Alignment::Center)
+            .padding(Padding::from([250, 100]))
+            .push(prompt)
+            .push(token_input)
+            .push(verify_button)
+            .push(resend_button)
+            .push(back_button);
+
+        if let Some(ref status) = self.status {
+            content = content.push(Text::new(status));
+        }
+
+        content.into()
+    }
+}